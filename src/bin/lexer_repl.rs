@@ -37,12 +37,12 @@ fn main() {
                 l.update(input.clone());
                 loop {
                     let t = l.next_token();
-                    match t {
+                    match t.token {
                         Token::Eof => {
                             break;
                         }
                         _ => {
-                            println!("{t}");
+                            println!("{} at {}", t.token, t.span);
                         }
                     }
                 }
@@ -1,5 +1,6 @@
 use std::io::Write;
 
+use shaun::error::Error;
 use shaun::parser::Parser;
 
 const PARSER_HISTORY_NAME: &str = ".shaun_parser_history";
@@ -36,9 +37,14 @@ fn main() {
                     break;
                 }
                 p.update(&line);
-                match p.parse_stmt() {
-                    Ok(s) => {
-                        dbg!(s);
+                match p.parse_statements() {
+                    Ok(stmts) => {
+                        for s in stmts {
+                            dbg!(s);
+                        }
+                    }
+                    Err(Error::Parse(e)) => {
+                        println!("{}", e.render(&line));
                     }
                     Err(e) => {
                         println!("{:?}", e);
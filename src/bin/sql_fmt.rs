@@ -0,0 +1,151 @@
+use std::io::Read;
+use std::process::ExitCode;
+
+use shaun::error::Error;
+use shaun::parser::formatter::{format_sql, FormatOptions, KeywordCase};
+
+const USAGE: &str = "\
+Usage: sql_fmt [OPTIONS] [FILE]...
+
+Format SQL files in place. Without FILE, read from stdin and write to stdout.
+
+Options:
+    --check                 Don't write files, exit with 1 if any file needs formatting
+    --width <N>             Maximum line width [default: 80]
+    --indent <N>            Number of spaces per indentation level [default: 4]
+    --keyword-case <CASE>   upper, lower or preserve [default: upper]
+    -h, --help              Print help";
+
+struct Args {
+    options: FormatOptions,
+    check: bool,
+    files: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        options: FormatOptions::default(),
+        check: false,
+        files: Vec::new(),
+    };
+
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--check" => args.check = true,
+            "--width" | "--indent" | "--keyword-case" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("missing value for {}", arg))?;
+                match arg.as_str() {
+                    "--width" => args.options.line_width = parse_number(&arg, &value)?,
+                    "--indent" => args.options.indent = parse_number(&arg, &value)?,
+                    _ => {
+                        args.options.keyword_case = match value.as_str() {
+                            "upper" => KeywordCase::Upper,
+                            "lower" => KeywordCase::Lower,
+                            "preserve" => KeywordCase::Preserve,
+                            _ => return Err(format!("invalid keyword case {}", value)),
+                        }
+                    }
+                }
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option {}", arg));
+            }
+            _ => args.files.push(arg),
+        }
+    }
+
+    Ok(args)
+}
+
+fn parse_number(arg: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {} for {}", value, arg))
+}
+
+fn print_error(name: &str, sql: &str, err: Error) {
+    match err {
+        Error::Parse(e) => eprintln!("{}: {}", name, e.render(sql)),
+        e => eprintln!("{}: error: {}", name, e),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    // 没有指定文件时从 stdin 读取
+    if args.files.is_empty() || args.files == ["-"] {
+        let mut sql = String::new();
+        if let Err(e) = std::io::stdin().read_to_string(&mut sql) {
+            eprintln!("<stdin>: error: {}", e);
+            return ExitCode::FAILURE;
+        }
+
+        return match format_sql(&sql, &args.options) {
+            Ok(formatted) if args.check && formatted != sql => {
+                eprintln!("<stdin> needs formatting");
+                ExitCode::FAILURE
+            }
+            Ok(_) if args.check => ExitCode::SUCCESS,
+            Ok(formatted) => {
+                print!("{}", formatted);
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                print_error("<stdin>", &sql, e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let mut failed = false;
+    for file in &args.files {
+        let sql = match std::fs::read_to_string(file) {
+            Ok(sql) => sql,
+            Err(e) => {
+                eprintln!("{}: error: {}", file, e);
+                failed = true;
+                continue;
+            }
+        };
+
+        let formatted = match format_sql(&sql, &args.options) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                print_error(file, &sql, e);
+                failed = true;
+                continue;
+            }
+        };
+        if formatted == sql {
+            continue;
+        }
+
+        if args.check {
+            eprintln!("{} needs formatting", file);
+            failed = true;
+        } else if let Err(e) = std::fs::write(file, formatted) {
+            eprintln!("{}: error: {}", file, e);
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
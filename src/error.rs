@@ -1,10 +1,12 @@
 use core::fmt::Display;
 
+use crate::parser::span::Span;
+
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Error {
-    Parse(String),
+    Parse(ParseError),
     Storage(String),
     Internal(String),
     Conf(String),
@@ -14,20 +16,91 @@ pub enum Error {
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Parse(err)
-            | Self::Storage(err)
-            | Self::Internal(err)
-            | Self::Conf(err)
-            | Self::Other(err) => {
+            Self::Parse(err) => {
+                write!(f, "{}", err)
+            }
+            Self::Storage(err) | Self::Internal(err) | Self::Conf(err) | Self::Other(err) => {
                 write!(f, "{}", err)
             }
         }
     }
 }
 
-#[macro_export]
-macro_rules! fmt_err {
-    ($($arg:tt)*) => {
-        format!("{}:{} {}", file!(), line!(), format!($($arg)*))
-    };
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+// 解析错误, span 是出错位置在用户 SQL 中的区间
+// 解析多条语句时, statement_index 是出错语句的下标 (从 0 开始)
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+    pub statement_index: Option<usize>,
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        ParseError {
+            message: message.into(),
+            span,
+            statement_index: None,
+        }
+    }
+
+    // expected X, found Y
+    pub fn unexpected(expected: impl Display, found: impl Display, span: Span) -> Self {
+        ParseError {
+            message: format!("expected {}, found {}", expected, found),
+            span,
+            statement_index: None,
+        }
+    }
+
+    // 将出错的那一行 SQL 打印出来, 并在出错位置下面标上 ^
+    //
+    // error: expected Ident, found Token: keyword: From at line 2 col 8
+    //   |
+    // 2 | WHERE FROM = 1
+    //   |       ^^^^
+    pub fn render(&self, sql: &str) -> String {
+        let start = self.span.start;
+        let end = self.span.end;
+        let line = sql.lines().nth(start.line - 1).unwrap_or("");
+        let line_no = start.line.to_string();
+        let gutter = " ".repeat(line_no.len());
+
+        let line_width = line.chars().count() + 1;
+        let caret_start = start.column.min(line_width);
+        let caret_end = if end.line == start.line {
+            end.column.min(line_width)
+        } else {
+            line_width
+        };
+        let caret_len = caret_end.saturating_sub(caret_start).max(1);
+
+        format!(
+            "error: {}\n{} |\n{} | {}\n{} | {}{}",
+            self,
+            gutter,
+            line_no,
+            line,
+            gutter,
+            " ".repeat(caret_start.saturating_sub(1)),
+            "^".repeat(caret_len),
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.message, self.span)?;
+        if let Some(index) = self.statement_index {
+            write!(f, " (statement index {})", index)?;
+        }
+
+        Ok(())
+    }
 }
@@ -1,4 +1,8 @@
+use std::fmt;
+
+use crate::parser::display::{Ident, IdentList};
 use crate::parser::expression::Expression;
+use crate::parser::operator::Precedence;
 use crate::parser::DataType;
 
 #[derive(PartialEq, Debug, Clone)]
@@ -10,5 +14,126 @@ pub struct Column {
     pub default: Option<Expression>,
     pub unique: bool,
     pub index: bool,
-    pub references: Option<String>,
+    // [CONSTRAINT name] CHECK (expr), 一列可以有多个
+    pub checks: Vec<(Option<String>, Expression)>,
+    pub references: Option<ForeignKey>,
+}
+
+// name TYPE [PRIMARY KEY] [NOT NULL | NULL] [UNIQUE] [INDEX]
+//      [[CONSTRAINT name] CHECK (expr) ...] [REFERENCES table [(column)] [ON DELETE action]] [DEFAULT expr]
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", Ident(&self.name), self.data_type)?;
+        if self.primary_key {
+            write!(f, " PRIMARY KEY")?;
+        }
+        match self.nullable {
+            Some(true) => write!(f, " NULL")?,
+            Some(false) => write!(f, " NOT NULL")?,
+            None => {}
+        }
+        if self.unique {
+            write!(f, " UNIQUE")?;
+        }
+        if self.index {
+            write!(f, " INDEX")?;
+        }
+        for (name, check) in &self.checks {
+            if let Some(name) = name {
+                write!(f, " CONSTRAINT {}", Ident(name))?;
+            }
+            write!(f, " CHECK ({})", check)?;
+        }
+        if let Some(references) = &self.references {
+            write!(f, " {}", references)?;
+        }
+        match &self.default {
+            Some(default) if default.precedence() <= Precedence::Equals => {
+                write!(f, " DEFAULT ({})", default)?
+            }
+            Some(default) => write!(f, " DEFAULT {}", default)?,
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+// REFERENCES table [(column, ...)] [ON DELETE action]
+// 没有指定列时引用的是对方的主键
+#[derive(PartialEq, Debug, Clone)]
+pub struct ForeignKey {
+    pub table: String,
+    pub columns: Option<Vec<String>>,
+    pub on_delete: Option<ReferentialAction>,
+}
+
+impl fmt::Display for ForeignKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "REFERENCES {}", Ident(&self.table))?;
+        if let Some(columns) = &self.columns {
+            write!(f, " ({})", IdentList(columns))?;
+        }
+        if let Some(action) = &self.on_delete {
+            write!(f, " ON DELETE {}", action)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ReferentialAction {
+    Cascade,
+    SetNull,
+    Restrict,
+}
+
+impl fmt::Display for ReferentialAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Cascade => "CASCADE",
+            Self::SetNull => "SET NULL",
+            Self::Restrict => "RESTRICT",
+        })
+    }
+}
+
+// CREATE TABLE 中的表级约束
+// [CONSTRAINT name] PRIMARY KEY (a, b) | UNIQUE (a, b) | CHECK (expr)
+//     | FOREIGN KEY (a, b) REFERENCES table [(c, d)] [ON DELETE action]
+#[derive(PartialEq, Debug, Clone)]
+pub struct TableConstraint {
+    pub name: Option<String>,
+    pub kind: ConstraintKind,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum ConstraintKind {
+    PrimaryKey(Vec<String>),
+    Unique(Vec<String>),
+    Check(Expression),
+    ForeignKey {
+        columns: Vec<String>,
+        references: ForeignKey,
+    },
+}
+
+impl fmt::Display for TableConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "CONSTRAINT {} ", Ident(name))?;
+        }
+        match &self.kind {
+            ConstraintKind::PrimaryKey(columns) => {
+                write!(f, "PRIMARY KEY ({})", IdentList(columns))
+            }
+            ConstraintKind::Unique(columns) => write!(f, "UNIQUE ({})", IdentList(columns)),
+            ConstraintKind::Check(expr) => write!(f, "CHECK ({})", expr),
+            ConstraintKind::ForeignKey {
+                columns,
+                references,
+            } => write!(f, "FOREIGN KEY ({}) {}", IdentList(columns), references),
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt;
+
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub enum DataType {
     Char,
@@ -15,3 +17,24 @@ pub enum DataType {
     Varchar(usize),
     String,
 }
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Char => write!(f, "CHAR"),
+            Self::Bool => write!(f, "BOOLEAN"),
+            Self::Int8 => write!(f, "INT8"),
+            Self::Int16 => write!(f, "INT16"),
+            Self::Int32 => write!(f, "INT"),
+            Self::Int64 => write!(f, "INT64"),
+            Self::Uint8 => write!(f, "UINT8"),
+            Self::Uint16 => write!(f, "UINT16"),
+            Self::Uint32 => write!(f, "UINT32"),
+            Self::Uint64 => write!(f, "UINT64"),
+            Self::Float32 => write!(f, "FLOAT"),
+            Self::Float64 => write!(f, "DOUBLE"),
+            Self::Varchar(len) => write!(f, "VARCHAR({})", len),
+            Self::String => write!(f, "STRING"),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+use super::expression::Expression;
+use super::keyword::{find_keyword, Keyword};
+use super::stmt::OrderByType;
+
+// 输出 SQL 时使用的标识符
+// 关键字或者不能被词法分析器识别为标识符的名称需要加上双引号
+pub struct Ident<'a>(pub &'a str);
+
+impl fmt::Display for Ident<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut chars = self.0.chars();
+        let is_plain = match chars.next() {
+            Some(ch) => {
+                (ch.is_alphabetic() || ch == '_')
+                    && chars.all(|ch| ch.is_alphanumeric() || ch == '_')
+            }
+            None => false,
+        };
+
+        if is_plain && find_keyword(self.0) == Keyword::UserIdent {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+        }
+    }
+}
+
+// 字符串字面量, 单引号用两个单引号转义
+pub struct QuotedString<'a>(pub &'a str);
+
+impl fmt::Display for QuotedString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}'", self.0.replace('\'', "''"))
+    }
+}
+
+// a, b, c
+pub struct CommaSeparated<'a, T>(pub &'a [T]);
+
+impl<T: fmt::Display> fmt::Display for CommaSeparated<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", item)?;
+        }
+
+        Ok(())
+    }
+}
+
+// 标识符列表 a, "b c"
+pub struct IdentList<'a>(pub &'a [String]);
+
+impl fmt::Display for IdentList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, name) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", Ident(name))?;
+        }
+
+        Ok(())
+    }
+}
+
+// ORDER BY 之后的排序列表, 默认的 ASC 省略不输出
+pub struct OrderByList<'a>(pub &'a [(Expression, OrderByType)]);
+
+impl fmt::Display for OrderByList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (expr, order_type)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", expr)?;
+            if *order_type == OrderByType::Desc {
+                write!(f, " DESC")?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,11 @@
+use std::fmt;
+
+use crate::parser::data_type::DataType;
+use crate::parser::display::{CommaSeparated, Ident, OrderByList, QuotedString};
 use crate::parser::operation::Operation;
+use crate::parser::operator::Precedence;
+use crate::parser::span::{Span, Spanned};
+use crate::parser::stmt::{OrderByType, Query};
 
 #[derive(PartialEq, Debug, Clone)]
 // 字面量
@@ -11,13 +18,282 @@ pub enum Literal {
     String(String),
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub enum Expression {
     Field(Option<String>, String),
     Column(usize),
     Literal(Literal),
-    Function(String, Vec<Expression>),
+    Function(Function),
     Operation(Operation),
+    // CASE [operand] WHEN condition THEN result ... [ELSE else_result] END
+    // 有 operand 时, 依次比较 operand = condition
+    Case {
+        operand: Option<Box<Expression>>,
+        branches: Vec<(Expression, Expression)>,
+        else_result: Option<Box<Expression>>,
+    },
+    // CAST(expr AS data_type) 或 expr::data_type
+    Cast(Box<Expression>, DataType),
+    // 标量子查询 (SELECT ...), 只能返回一行一列
+    Subquery(Box<Query>),
+    // EXISTS (SELECT ...)
+    Exists(Box<Query>),
+    // 绑定参数 ?, $1 或 :name
+    Parameter(Parameter),
+    // 带有源码位置的表达式, 解析器产生的每个表达式节点都包在里面
+    // 位置不参与比较, 也不影响输出的 SQL
+    Spanned(Box<Spanned<Expression>>),
+}
+
+// 同一条语句中只能使用一种形式的参数
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Parameter {
+    // ?, 按出现的顺序绑定
+    Anonymous,
+    // $1, 从 1 开始编号
+    Indexed(usize),
+    // :name
+    Named(String),
+}
+
+// name([DISTINCT] args) [FILTER (WHERE filter)] [OVER window]
+#[derive(PartialEq, Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    pub args: Vec<Expression>,
+    pub distinct: bool,
+    pub filter: Option<Box<Expression>>,
+    pub over: Option<Box<WindowType>>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum WindowType {
+    // OVER w, w 在 WINDOW 子句中定义
+    Named(String),
+    // OVER (PARTITION BY ...)
+    Spec(WindowSpec),
+}
+
+// [name] [PARTITION BY partition_by] [ORDER BY order] [frame]
+#[derive(PartialEq, Debug, Clone)]
+pub struct WindowSpec {
+    pub name: Option<String>,
+    pub partition_by: Option<Vec<Expression>>,
+    pub order: Option<Vec<(Expression, OrderByType)>>,
+    pub frame: Option<WindowFrame>,
+}
+
+// {ROWS | RANGE} start 或者 {ROWS | RANGE} BETWEEN start AND end
+#[derive(PartialEq, Debug, Clone)]
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start: WindowFrameBound,
+    pub end: Option<WindowFrameBound>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum WindowFrameUnits {
+    Rows,
+    Range,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum WindowFrameBound {
+    CurrentRow,
+    // n PRECEDING, None 表示 UNBOUNDED PRECEDING
+    Preceding(Option<Box<Expression>>),
+    // n FOLLOWING, None 表示 UNBOUNDED FOLLOWING
+    Following(Option<Box<Expression>>),
+}
+
+impl Expression {
+    // 输出 SQL 时用来判断子表达式是否需要加括号
+    pub fn precedence(&self) -> Precedence {
+        match self.unspanned() {
+            Self::Operation(op) => op.precedence(),
+            _ => Precedence::Call,
+        }
+    }
+
+    // 去掉外层的位置信息
+    pub fn unspanned(&self) -> &Expression {
+        match self {
+            Self::Spanned(spanned) => spanned.node.unspanned(),
+            expr => expr,
+        }
+    }
+
+    // 表达式在 SQL 中的位置, 手动构造的表达式没有位置
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Self::Spanned(spanned) => Some(spanned.span),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.unspanned(), other.unspanned()) {
+            (Self::Field(t1, n1), Self::Field(t2, n2)) => t1 == t2 && n1 == n2,
+            (Self::Column(c1), Self::Column(c2)) => c1 == c2,
+            (Self::Literal(l1), Self::Literal(l2)) => l1 == l2,
+            (Self::Function(f1), Self::Function(f2)) => f1 == f2,
+            (Self::Operation(o1), Self::Operation(o2)) => o1 == o2,
+            (
+                Self::Case {
+                    operand: o1,
+                    branches: b1,
+                    else_result: e1,
+                },
+                Self::Case {
+                    operand: o2,
+                    branches: b2,
+                    else_result: e2,
+                },
+            ) => o1 == o2 && b1 == b2 && e1 == e2,
+            (Self::Cast(e1, t1), Self::Cast(e2, t2)) => e1 == e2 && t1 == t2,
+            (Self::Subquery(q1), Self::Subquery(q2)) | (Self::Exists(q1), Self::Exists(q2)) => {
+                q1 == q2
+            }
+            (Self::Parameter(p1), Self::Parameter(p2)) => p1 == p2,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "*"),
+            Self::Null => write!(f, "NULL"),
+            Self::Bool(true) => write!(f, "TRUE"),
+            Self::Bool(false) => write!(f, "FALSE"),
+            Self::Int(i) => write!(f, "{}", i),
+            Self::Float(n) if n.is_nan() => write!(f, "NAN"),
+            Self::Float(n) if n.is_infinite() && n.is_sign_positive() => write!(f, "INFINITY"),
+            Self::Float(n) if n.is_infinite() => write!(f, "-INFINITY"),
+            // {:?} 总是带上小数点或指数, 重新解析后仍然是浮点数
+            Self::Float(n) => write!(f, "{:?}", n),
+            Self::String(s) => write!(f, "{}", QuotedString(s)),
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Field(None, name) => write!(f, "{}", Ident(name)),
+            Self::Field(Some(table), name) => write!(f, "{}.{}", Ident(table), Ident(name)),
+            Self::Column(index) => write!(f, "#{}", index),
+            Self::Literal(literal) => write!(f, "{}", literal),
+            Self::Function(function) => write!(f, "{}", function),
+            Self::Operation(op) => write!(f, "{}", op),
+            Self::Case {
+                operand,
+                branches,
+                else_result,
+            } => {
+                write!(f, "CASE")?;
+                if let Some(operand) = operand {
+                    write!(f, " {}", operand)?;
+                }
+                for (condition, result) in branches {
+                    write!(f, " WHEN {} THEN {}", condition, result)?;
+                }
+                if let Some(else_result) = else_result {
+                    write!(f, " ELSE {}", else_result)?;
+                }
+                write!(f, " END")
+            }
+            Self::Cast(expr, data_type) => write!(f, "CAST({} AS {})", expr, data_type),
+            Self::Subquery(query) => write!(f, "({})", query),
+            Self::Exists(query) => write!(f, "EXISTS ({})", query),
+            Self::Parameter(parameter) => write!(f, "{}", parameter),
+            Self::Spanned(spanned) => write!(f, "{}", spanned.node),
+        }
+    }
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Anonymous => write!(f, "?"),
+            Self::Indexed(index) => write!(f, "${}", index),
+            Self::Named(name) => write!(f, ":{}", name),
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", Ident(&self.name))?;
+        if self.distinct {
+            write!(f, "DISTINCT ")?;
+        }
+        write!(f, "{})", CommaSeparated(&self.args))?;
+        if let Some(filter) = &self.filter {
+            write!(f, " FILTER (WHERE {})", filter)?;
+        }
+        match self.over.as_deref() {
+            Some(WindowType::Named(name)) => write!(f, " OVER {}", Ident(name))?,
+            Some(WindowType::Spec(spec)) => write!(f, " OVER ({})", spec)?,
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for WindowSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut clauses = Vec::new();
+        if let Some(name) = &self.name {
+            clauses.push(Ident(name).to_string());
+        }
+        if let Some(exprs) = &self.partition_by {
+            clauses.push(format!("PARTITION BY {}", CommaSeparated(exprs)));
+        }
+        if let Some(orders) = &self.order {
+            clauses.push(format!("ORDER BY {}", OrderByList(orders)));
+        }
+        if let Some(frame) = &self.frame {
+            clauses.push(frame.to_string());
+        }
+
+        write!(f, "{}", clauses.join(" "))
+    }
+}
+
+impl fmt::Display for WindowFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.units {
+            WindowFrameUnits::Rows => write!(f, "ROWS ")?,
+            WindowFrameUnits::Range => write!(f, "RANGE ")?,
+        }
+        match &self.end {
+            Some(end) => write!(f, "BETWEEN {} AND {}", self.start, end),
+            None => write!(f, "{}", self.start),
+        }
+    }
+}
+
+impl fmt::Display for WindowFrameBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (offset, direction) = match self {
+            Self::CurrentRow => return write!(f, "CURRENT ROW"),
+            Self::Preceding(offset) => (offset, "PRECEDING"),
+            Self::Following(offset) => (offset, "FOLLOWING"),
+        };
+        match offset {
+            None => write!(f, "UNBOUNDED {}", direction),
+            // 和 BETWEEN 的上下界一样, 偏移量中的 AND 需要加括号
+            Some(offset) if offset.precedence() <= Precedence::Equals => {
+                write!(f, "({}) {}", offset, direction)
+            }
+            Some(offset) => write!(f, "{} {}", offset, direction),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -26,26 +302,26 @@ mod test {
     use crate::parser::stmt::*;
     use crate::parser::test::init;
     use crate::parser::Parser;
-    use log::error;
 
     #[test]
     fn parse_expression_test() {
         init();
 
-        // Token::Number("123") Token::Plus Token::Number("456");
+        // Token::Integer("123") Token::Plus Token::Integer("456");
         let mut parser = Parser::new_parser("SELECT 123 + 456;".to_owned());
         let result_exp = Expression::Operation(Operation::Add(
             Box::new(Expression::Literal(Literal::Int(123))),
             Box::new(Expression::Literal(Literal::Int(456))),
         ));
-        let mut expr_selects = vec![];
-        expr_selects.push((result_exp.clone(), None));
+        let expr_selects = vec![SelectItem::Expr(result_exp.clone(), None)];
         let result = Statement::Select(SelectStmt {
+            distinct: None,
             selects: expr_selects,
-            froms: None,
+            from: None,
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
@@ -55,20 +331,20 @@ mod test {
                 assert_eq!(result, s);
             }
             Err(e) => {
-                error!("expected: {:?} but get: {:?}", result, e);
-                assert!(false);
+                panic!("expected: {:?} but get: {:?}", result, e);
             }
         };
 
         parser.update("SELECT 123 + 456 AS c1");
-        let mut expr_selects = vec![];
-        expr_selects.push((result_exp.clone(), Some("c1".to_owned())));
+        let expr_selects = vec![SelectItem::Expr(result_exp.clone(), Some("c1".to_owned()))];
         let result = Statement::Select(SelectStmt {
+            distinct: None,
             selects: expr_selects,
-            froms: None,
+            from: None,
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
@@ -78,8 +354,7 @@ mod test {
                 assert_eq!(result, s);
             }
             Err(e) => {
-                error!("expected: {:?} but get: {:?}", result, e);
-                assert!(false);
+                panic!("expected: {:?} but get: {:?}", result, e);
             }
         }
 
@@ -89,8 +364,7 @@ mod test {
                 assert_eq!(result, s);
             }
             Err(e) => {
-                error!("expected: {:?} but get: {:?}", result, e);
-                assert!(false);
+                panic!("expected: {:?} but get: {:?}", result, e);
             }
         }
 
@@ -117,13 +391,15 @@ mod test {
             Box::new(Expression::Literal(Literal::Int(3))),
         ));
         let mut expr_selects = vec![];
-        expr_selects.push((res_expr.clone(), Some("c1".to_owned())));
+        expr_selects.push(SelectItem::Expr(res_expr.clone(), Some("c1".to_owned())));
         let result = Statement::Select(SelectStmt {
+            distinct: None,
             selects: expr_selects.clone(),
-            froms: None,
+            from: None,
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
@@ -133,8 +409,7 @@ mod test {
                 assert_eq!(result, s);
             }
             Err(e) => {
-                error!("expected: {:?} but get: {:?}", result, e);
-                assert!(false);
+                panic!("expected: {:?} but get: {:?}", result, e);
             }
         }
 
@@ -145,13 +420,15 @@ mod test {
             Box::new(Expression::Literal(Literal::Int(10))),
         ));
         expr_selects.clear();
-        expr_selects.push((res_expr, None));
+        expr_selects.push(SelectItem::Expr(res_expr, None));
         let result = Statement::Select(SelectStmt {
+            distinct: None,
             selects: expr_selects.clone(),
-            froms: None,
+            from: None,
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
@@ -161,8 +438,7 @@ mod test {
                 assert_eq!(result, s);
             }
             Err(e) => {
-                error!("expected: {:?} but get: {:?}", result, e);
-                assert!(false);
+                panic!("expected: {:?} but get: {:?}", result, e);
             }
         }
         //
@@ -172,13 +448,15 @@ mod test {
             Box::new(Expression::Literal(Literal::Int(10))),
         ));
         expr_selects.clear();
-        expr_selects.push((res_expr, None));
+        expr_selects.push(SelectItem::Expr(res_expr, None));
         let result = Statement::Select(SelectStmt {
+            distinct: None,
             selects: expr_selects.clone(),
-            froms: None,
+            from: None,
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
@@ -188,8 +466,7 @@ mod test {
                 assert_eq!(result, s);
             }
             Err(e) => {
-                error!("expected: {:?} but get: {:?}", result, e);
-                assert!(false);
+                panic!("expected: {:?} but get: {:?}", result, e);
             }
         }
 
@@ -205,13 +482,15 @@ mod test {
             ))),
         ));
         expr_selects.clear();
-        expr_selects.push((res_expr, None));
+        expr_selects.push(SelectItem::Expr(res_expr, None));
         let result = Statement::Select(SelectStmt {
+            distinct: None,
             selects: expr_selects.clone(),
-            froms: None,
+            from: None,
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
@@ -221,22 +500,22 @@ mod test {
                 assert_eq!(result, s);
             }
             Err(e) => {
-                error!("expected: {:?} but get: {:?}", result, e);
-                assert!(false);
+                panic!("expected: {:?} but get: {:?}", result, e);
             }
         }
 
         // 测试 selects Comma是否正确
         let result = Statement::Select(SelectStmt {
+            distinct: None,
             selects: vec![
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::Add(
                         Box::new(Expression::Literal(Literal::Int(1))),
                         Box::new(Expression::Literal(Literal::Int(2))),
                     )),
                     None,
                 ),
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::Divide(
                         Box::new(Expression::Literal(Literal::Float(10.1))),
                         Box::new(Expression::Literal(Literal::Bool(false))),
@@ -244,10 +523,11 @@ mod test {
                     None,
                 ),
             ],
-            froms: None,
+            from: None,
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
@@ -257,27 +537,27 @@ mod test {
                 assert_eq!(result, s);
             }
             Err(e) => {
-                error!("expected: {:?} but get: {:?}", result, e);
-                assert!(false);
+                panic!("expected: {:?} but get: {:?}", result, e);
             }
         }
         let result = Statement::Select(SelectStmt {
+            distinct: None,
             selects: vec![
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::Add(
                         Box::new(Expression::Literal(Literal::Int(1))),
                         Box::new(Expression::Literal(Literal::Int(2))),
                     )),
                     None,
                 ),
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::Divide(
                         Box::new(Expression::Literal(Literal::Float(10.1))),
                         Box::new(Expression::Literal(Literal::Bool(false))),
                     )),
                     None,
                 ),
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::And(
                         Box::new(Expression::Literal(Literal::Bool(true))),
                         Box::new(Expression::Literal(Literal::Float(10.1))),
@@ -285,10 +565,11 @@ mod test {
                     None,
                 ),
             ],
-            froms: None,
+            from: None,
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
@@ -301,28 +582,28 @@ mod test {
                 assert_eq!(result, s);
             }
             Err(e) => {
-                error!("expected: {:?} but get: {:?}", result, e);
-                assert!(false);
+                panic!("expected: {:?} but get: {:?}", result, e);
             }
         }
 
         let result = Statement::Select(SelectStmt {
+            distinct: None,
             selects: vec![
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::Add(
                         Box::new(Expression::Literal(Literal::Int(1))),
                         Box::new(Expression::Literal(Literal::Int(2))),
                     )),
                     None,
                 ),
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::Divide(
                         Box::new(Expression::Literal(Literal::Float(10.1))),
                         Box::new(Expression::Literal(Literal::Bool(false))),
                     )),
                     None,
                 ),
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::And(
                         Box::new(Expression::Literal(Literal::Bool(true))),
                         Box::new(Expression::Literal(Literal::Float(10.1))),
@@ -330,10 +611,11 @@ mod test {
                     None,
                 ),
             ],
-            froms: None,
+            from: None,
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
@@ -346,9 +628,61 @@ mod test {
                 assert_eq!(result, s);
             }
             Err(e) => {
-                error!("expected: {:?} but get: {:?}", result, e);
-                assert!(false);
+                panic!("expected: {:?} but get: {:?}", result, e);
             }
         }
     }
+
+    #[test]
+    fn expression_span_test() {
+        init();
+        let sql = "SELECT 1,\n  a + foo(b * 2) AS x\nFROM t WHERE (c) = 1";
+        let text = |expr: &Expression| {
+            let span = expr.span().unwrap();
+            &sql[span.start.offset..span.end.offset]
+        };
+
+        let select = match Parser::new_parser(sql.to_owned()).parse_stmt() {
+            Ok(Statement::Select(select)) => select,
+            r => panic!("unexpected result {:?}", r),
+        };
+        let expr = match &select.selects[1] {
+            SelectItem::Expr(expr, _) => expr,
+            item => panic!("unexpected select item {:?}", item),
+        };
+        assert_eq!(text(expr), "a + foo(b * 2)");
+
+        // 每一层子表达式都有自己的位置
+        let (lhs, rhs) = match expr.unspanned() {
+            Expression::Operation(Operation::Add(lhs, rhs)) => (lhs, rhs),
+            e => panic!("unexpected expression {:?}", e),
+        };
+        assert_eq!(text(lhs), "a");
+        assert_eq!(text(rhs), "foo(b * 2)");
+        let arg = match rhs.unspanned() {
+            Expression::Function(function) => &function.args[0],
+            e => panic!("unexpected expression {:?}", e),
+        };
+        assert_eq!(text(arg), "b * 2");
+        let span = arg.span().unwrap();
+        assert_eq!((span.start.line, span.start.column), (2, 11));
+        assert_eq!((span.end.line, span.end.column), (2, 16));
+
+        // 括号包含在位置中
+        let wheres = select.wheres.as_ref().unwrap();
+        assert_eq!(text(wheres), "(c) = 1");
+        match wheres.unspanned() {
+            Expression::Operation(Operation::Equal(lhs, _)) => assert_eq!(text(lhs), "(c)"),
+            e => panic!("unexpected expression {:?}", e),
+        }
+
+        // 位置不参与比较
+        assert_eq!(
+            *arg,
+            Expression::Operation(Operation::Multiply(
+                Box::new(Expression::Field(None, "b".to_owned())),
+                Box::new(Expression::Literal(Literal::Int(2))),
+            ))
+        );
+    }
 }
@@ -0,0 +1,993 @@
+use crate::error::{Error, Result};
+
+use super::keyword::Keyword;
+use super::lexer::Lexer;
+use super::token::{SpannedToken, Token};
+use super::Parser;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeywordCase {
+    Upper,    // SELECT
+    Lower,    // select
+    Preserve, // 保持原样
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FormatOptions {
+    pub keyword_case: KeywordCase,
+    // 每一级缩进的空格数
+    pub indent: usize,
+    // 超过这个宽度的语句和子句会被拆成多行
+    pub line_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            keyword_case: KeywordCase::Upper,
+            indent: 4,
+            line_width: 80,
+        }
+    }
+}
+
+// 格式化一段 SQL 脚本, 注释会被保留
+//
+// 放得下一行的语句保持一行, 否则每个子句单独一行:
+//
+// SELECT
+//     person.id,
+//     person.name
+// FROM person
+//     LEFT JOIN address ON person.id = address.person_id
+// WHERE person.id > 10
+//     AND address.city = 'shanghai'
+// ORDER BY person.id DESC;
+//
+// 格式化只改变空白字符和关键字大小写, 格式化之后的 SQL 会被重新解析,
+// 如果和原来的语句不一致则返回错误
+pub fn format_sql(sql: &str, options: &FormatOptions) -> Result<String> {
+    let stmts = Parser::new_parser(sql.to_owned()).parse_statements()?;
+
+    let formatter = Formatter {
+        options,
+        unit: " ".repeat(options.indent),
+    };
+    let (scripts, comments) = collect_statements(sql);
+
+    let mut out = String::new();
+    let mut end_line = None;
+    for script in &scripts {
+        formatter.write_separator(&mut out, end_line, script.start_line);
+        let trailing = formatter.write_statement(&mut out, &script.words);
+        out.push(';');
+        push_trailing(&mut out, trailing);
+        push_trailing(&mut out, &script.trailing);
+        out.push('\n');
+        end_line = Some(script.end_line);
+    }
+    if let Some((_, line)) = comments.first() {
+        formatter.write_separator(&mut out, end_line, *line);
+        for (comment, _) in &comments {
+            out.push_str(comment);
+            out.push('\n');
+        }
+    }
+
+    // 格式化前后的语句必须完全一致
+    let expected: Vec<String> = stmts.iter().map(|s| s.node.to_string()).collect();
+    match Parser::new_parser(out.clone()).parse_statements() {
+        Ok(formatted) if formatted.iter().map(|s| s.node.to_string()).eq(expected) => Ok(out),
+        _ => Err(Error::Internal(format!(
+            "formatting changed the meaning of the SQL:\n{}",
+            out
+        ))),
+    }
+}
+
+// 去掉注释之后的 Token, 注释附着在前后的 Token 上
+struct Word<'a> {
+    token: Token,
+    text: &'a str,
+    // Token 在源码中的字节偏移, 用来判断两个 Token 之间是否有空白
+    start: usize,
+    end: usize,
+    // 是否是 -1 +1 这样的一元运算符
+    unary: bool,
+    // 在这个 Token 之前, 单独占一行的注释
+    leading: Vec<&'a str>,
+    // 和这个 Token 在同一行, 跟在它后面的注释
+    trailing: Vec<&'a str>,
+}
+
+// 一条语句以及它的 ; 后面同一行的注释
+struct Script<'a> {
+    words: Vec<Word<'a>>,
+    trailing: Vec<&'a str>,
+    start_line: usize,
+    end_line: usize,
+}
+
+// 按 ; 切分语句, 返回所有语句以及文件末尾的注释
+fn collect_statements(sql: &str) -> (Vec<Script<'_>>, Vec<(&str, usize)>) {
+    let mut lexer = Lexer::new_lexer(sql.to_owned()).with_comments();
+    let mut scripts: Vec<Script> = Vec::new();
+    let mut words: Vec<Word> = Vec::new();
+    // 还没有找到下一个 Token 的注释, 以及注释所在的行号
+    let mut pending: Vec<(&str, usize)> = Vec::new();
+    let mut start_line = 0;
+    // 上一个 Token 所在的行, 以及它是否是 ;
+    let mut last_line = 0;
+    let mut last_is_semicolon = false;
+
+    loop {
+        let SpannedToken { token, span } = lexer.next_token();
+        let text = &sql[span.start.offset..span.end.offset];
+        match token {
+            Token::Eof => break,
+            Token::Comment(_) => {
+                if span.start.line != last_line || !pending.is_empty() {
+                    pending.push((text, span.start.line));
+                } else if let Some(word) = words.last_mut() {
+                    word.trailing.push(text);
+                } else if let (true, Some(script)) = (last_is_semicolon, scripts.last_mut()) {
+                    script.trailing.push(text);
+                } else {
+                    pending.push((text, span.start.line));
+                }
+            }
+            Token::Semicolon => {
+                // 空语句直接忽略
+                if !words.is_empty() {
+                    scripts.push(Script {
+                        words: mark_unary(std::mem::take(&mut words)),
+                        trailing: Vec::new(),
+                        start_line,
+                        end_line: span.end.line,
+                    });
+                }
+                last_line = span.end.line;
+                last_is_semicolon = !scripts.is_empty();
+            }
+            token => {
+                if words.is_empty() {
+                    start_line = pending.first().map_or(span.start.line, |(_, line)| *line);
+                }
+                words.push(Word {
+                    token,
+                    text,
+                    start: span.start.offset,
+                    end: span.end.offset,
+                    unary: false,
+                    leading: pending.drain(..).map(|(comment, _)| comment).collect(),
+                    trailing: Vec::new(),
+                });
+                last_line = span.end.line;
+                last_is_semicolon = false;
+            }
+        }
+    }
+
+    if !words.is_empty() {
+        scripts.push(Script {
+            words: mark_unary(words),
+            trailing: Vec::new(),
+            start_line,
+            end_line: last_line,
+        });
+    }
+
+    (scripts, pending)
+}
+
+fn mark_unary(mut words: Vec<Word<'_>>) -> Vec<Word<'_>> {
+    for i in 0..words.len() {
+        words[i].unary = match words[i].token {
+            Token::Exclamation | Token::Tilde => true,
+            Token::Minus | Token::Add => match i.checked_sub(1).map(|j| &words[j].token) {
+                None => true,
+                Some(Token::KeyWord(k)) => !matches!(
+                    k,
+                    Keyword::True
+                        | Keyword::False
+                        | Keyword::Null
+                        | Keyword::Infinity
+                        | Keyword::NaN
+                        | Keyword::End
+                ),
+                Some(t) => !matches!(
+                    t,
+                    Token::Ident(_)
+                        | Token::String(_)
+                        | Token::Integer(_)
+                        | Token::Float(_)
+                        | Token::HexInteger(_)
+                        | Token::RightParen
+                ),
+            },
+            _ => false,
+        };
+    }
+
+    words
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ClauseKind {
+    List,      // 逗号分隔的列表, 例如 SELECT a, b
+    Condition, // AND OR 连接的条件, 例如 WHERE a = 1 AND b = 2
+    Join,      // LEFT JOIN t ON a = b
+}
+
+// 语句中的一个子句, header 是子句开头的关键字个数, 例如 GROUP BY 是 2
+struct Clause {
+    start: usize,
+    end: usize,
+    header: usize,
+    kind: ClauseKind,
+}
+
+// words[i] 是否是一个子句的开头, 返回子句的类型以及开头关键字的个数
+fn clause_keyword(words: &[Word<'_>], i: usize) -> Option<(ClauseKind, usize)> {
+    let keyword = match words[i].token {
+        Token::KeyWord(k) => k,
+        _ => return None,
+    };
+    let prev = i.checked_sub(1).map(|j| &words[j].token);
+    let next = words.get(i + 1).map(|w| &w.token);
+
+    match keyword {
+        Keyword::Select => Some((ClauseKind::List, select_header(words, i))),
+        Keyword::From
+        | Keyword::Values
+        | Keyword::Set
+        | Keyword::Window
+        | Keyword::Limit
+        | Keyword::Offset
+        | Keyword::Returning => Some((ClauseKind::List, 1)),
+        // DELETE ... USING 后面是表, JOIN ... USING 后面是括号
+        Keyword::Using if next != Some(&Token::LeftParen) => Some((ClauseKind::List, 1)),
+        Keyword::On if next == Some(&Token::KeyWord(Keyword::Conflict)) => {
+            Some((ClauseKind::List, 2))
+        }
+        Keyword::Group | Keyword::Order if next == Some(&Token::KeyWord(Keyword::By)) => {
+            Some((ClauseKind::List, 2))
+        }
+        Keyword::With if next == Some(&Token::KeyWord(Keyword::Recursive)) => {
+            Some((ClauseKind::List, 2))
+        }
+        Keyword::With => Some((ClauseKind::List, 1)),
+        // UNION ALL 单独一行, 后面的 SELECT 是新的子句
+        Keyword::Union | Keyword::Intersect | Keyword::Except
+            if next == Some(&Token::KeyWord(Keyword::All)) =>
+        {
+            Some((ClauseKind::List, 2))
+        }
+        Keyword::Union | Keyword::Intersect | Keyword::Except => Some((ClauseKind::List, 1)),
+        Keyword::Where | Keyword::Having => Some((ClauseKind::Condition, 1)),
+        // NATURAL LEFT OUTER JOIN 中的 LEFT, OUTER 和 JOIN 不是子句的开头
+        Keyword::Join
+        | Keyword::Inner
+        | Keyword::Left
+        | Keyword::Right
+        | Keyword::Full
+        | Keyword::Cross
+        | Keyword::Natural
+            if !matches!(
+                prev,
+                Some(Token::KeyWord(
+                    Keyword::Natural
+                        | Keyword::Inner
+                        | Keyword::Left
+                        | Keyword::Right
+                        | Keyword::Full
+                        | Keyword::Outer
+                ))
+            ) =>
+        {
+            let join = words[i..]
+                .iter()
+                .position(|w| w.token == Token::KeyWord(Keyword::Join))?;
+            Some((ClauseKind::Join, join + 1))
+        }
+        _ => None,
+    }
+}
+
+fn split_clauses(words: &[Word<'_>]) -> Vec<Clause> {
+    let mut clauses = Vec::new();
+    let (header, kind) = match clause_keyword(words, 0) {
+        Some((kind, header)) => (header, kind),
+        None => (0, ClauseKind::List),
+    };
+    let mut clause = Clause {
+        start: 0,
+        end: words.len(),
+        header,
+        kind,
+    };
+
+    let mut depth = 0usize;
+    for i in 1..words.len() {
+        match words[i - 1].token {
+            Token::LeftParen => depth += 1,
+            Token::RightParen => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth > 0 || i < clause.start + clause.header {
+            continue;
+        }
+
+        if let Some((kind, header)) = clause_keyword(words, i) {
+            // DELETE FROM, EXPLAIN SELECT 这样以关键字开头的语句和后面的子句放在一起
+            // PREPARE q AS SELECT 和 CREATE VIEW v (a, b) AS SELECT 也是一样
+            let prefix = &words[clause.start..i];
+            let is_prefix = prefix.iter().all(|w| matches!(w.token, Token::KeyWord(_)))
+                || matches!(prefix, [first, .., as_]
+                    if matches!(first.token, Token::KeyWord(Keyword::Prepare | Keyword::Create))
+                        && as_.token == Token::KeyWord(Keyword::As));
+            if clause.header == 0 && is_prefix {
+                clause.header = i - clause.start + header;
+                clause.kind = kind;
+                continue;
+            }
+
+            clause.end = i;
+            clauses.push(clause);
+            clause = Clause {
+                start: i,
+                end: words.len(),
+                header,
+                kind,
+            };
+        }
+    }
+    clauses.push(clause);
+
+    clauses
+}
+
+// 按深度为 0 的分隔符切分, 返回每一段以及它后面的分隔符
+fn split_items<'w, 'a>(
+    words: &'w [Word<'a>],
+    mut is_separator: impl FnMut(&Token) -> bool,
+) -> Vec<(&'w [Word<'a>], Option<&'w Word<'a>>)> {
+    let mut items = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, word) in words.iter().enumerate() {
+        match word.token {
+            Token::LeftParen => depth += 1,
+            Token::RightParen => depth = depth.saturating_sub(1),
+            ref t if depth == 0 && is_separator(t) => {
+                items.push((&words[start..i], Some(word)));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push((&words[start..], None));
+
+    items
+}
+
+// 最后一个 Token 后面的注释由 render 返回给调用者, 不在这一段之内, 不算在内
+fn has_comments(words: &[Word<'_>]) -> bool {
+    words
+        .iter()
+        .enumerate()
+        .any(|(i, w)| !w.leading.is_empty() || (i + 1 < words.len() && !w.trailing.is_empty()))
+}
+
+fn is_line_comment(comment: &str) -> bool {
+    comment.starts_with("--")
+}
+
+fn width(s: &str) -> usize {
+    s.chars().count()
+}
+
+fn push_trailing(out: &mut String, comments: &[&str]) {
+    for comment in comments {
+        out.push(' ');
+        out.push_str(comment);
+    }
+}
+
+// 两个 Token 之间是否需要空格
+fn need_space(prev: &Word<'_>, next: &Word<'_>) -> bool {
+    match (&prev.token, &next.token) {
+        (_, Token::Comma | Token::RightParen | Token::Period | Token::DoubleColon) => false,
+        (Token::LeftParen | Token::Period | Token::DoubleColon, _) => false,
+        // 函数调用 f(x) 和 VARCHAR(10) 保持原样
+        (Token::Ident(_) | Token::KeyWord(_), Token::LeftParen) => prev.end != next.start,
+        // - -1 不能写成 --1, 否则会变成注释
+        (Token::Minus | Token::Add, Token::Minus | Token::Add) => true,
+        (Token::Minus | Token::Add | Token::Exclamation | Token::Tilde, _) => !prev.unary,
+        _ => true,
+    }
+}
+
+// SELECT DISTINCT ON (a, b) 整体作为子句的开头
+fn select_header(words: &[Word<'_>], i: usize) -> usize {
+    let token = |j: usize| words.get(j).map(|w| &w.token);
+    match token(i + 1) {
+        Some(Token::KeyWord(Keyword::All)) => 2,
+        Some(Token::KeyWord(Keyword::Distinct)) => {
+            if token(i + 2) != Some(&Token::KeyWord(Keyword::On)) {
+                return 2;
+            }
+
+            let mut depth = 0usize;
+            for (j, word) in words.iter().enumerate().skip(i + 3) {
+                match word.token {
+                    Token::LeftParen => depth += 1,
+                    Token::RightParen => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return j - i + 1;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            3
+        }
+        _ => 1,
+    }
+}
+
+// 如果 words 以一个深度为 0 的括号结尾, 返回左括号的下标
+fn find_last_group(words: &[Word<'_>]) -> Option<usize> {
+    if words.last()?.token != Token::RightParen {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    for (i, word) in words.iter().enumerate().rev() {
+        match word.token {
+            Token::RightParen => depth += 1,
+            Token::LeftParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return (i + 1 < words.len() - 1).then_some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+struct Formatter<'o> {
+    options: &'o FormatOptions,
+    // 一级缩进
+    unit: String,
+}
+
+impl Formatter<'_> {
+    // 源码中两条语句之间有空行时保留一个空行
+    fn write_separator(&self, out: &mut String, end_line: Option<usize>, start_line: usize) {
+        if let Some(end_line) = end_line {
+            if start_line > end_line + 1 {
+                out.push('\n');
+            }
+        }
+    }
+
+    fn word_text(&self, word: &Word<'_>) -> String {
+        match (&word.token, self.options.keyword_case) {
+            (Token::KeyWord(_), KeywordCase::Upper) => word.text.to_uppercase(),
+            (Token::KeyWord(_), KeywordCase::Lower) => word.text.to_lowercase(),
+            _ => word.text.to_owned(),
+        }
+    }
+
+    fn fits(&self, indent: &str, line: &str) -> bool {
+        !line.contains('\n') && width(indent) + width(line) <= self.options.line_width
+    }
+
+    // 把 Token 拼成一行, 注释会导致换行, 换行后多缩进一级
+    // 最后一个 Token 后面的注释不输出, 而是返回给调用者, 放在 , 或 ; 之后
+    fn render<'a>(&self, words: &'a [Word<'a>], indent: &str) -> (String, &'a [&'a str]) {
+        let continuation = format!("{}{}", indent, self.unit);
+        let mut out = String::new();
+        let mut line_start = true;
+
+        for (i, word) in words.iter().enumerate() {
+            let word_indent = if i == 0 { indent } else { &continuation };
+            if !word.leading.is_empty() && !line_start {
+                out.push('\n');
+                out.push_str(word_indent);
+            }
+            for comment in &word.leading {
+                out.push_str(comment);
+                out.push('\n');
+                out.push_str(word_indent);
+                line_start = true;
+            }
+
+            if !line_start && need_space(&words[i - 1], word) {
+                out.push(' ');
+            }
+            out.push_str(&self.word_text(word));
+            line_start = false;
+
+            if i + 1 == words.len() {
+                return (out, &word.trailing);
+            }
+            for comment in &word.trailing {
+                out.push(' ');
+                out.push_str(comment);
+                if is_line_comment(comment) {
+                    out.push('\n');
+                    out.push_str(&continuation);
+                    line_start = true;
+                }
+            }
+        }
+
+        (out, &[])
+    }
+
+    // 输出一条语句, 不包括结尾的 ;
+    fn write_statement<'a>(&self, out: &mut String, words: &'a [Word<'a>]) -> &'a [&'a str] {
+        if !has_comments(words) {
+            let (line, trailing) = self.render(words, "");
+            if self.fits("", &line) {
+                out.push_str(&line);
+                return trailing;
+            }
+        }
+
+        let clauses = split_clauses(words);
+        let mut trailing: &[&str] = &[];
+        for (i, clause) in clauses.iter().enumerate() {
+            if i > 0 {
+                push_trailing(out, trailing);
+                out.push('\n');
+            }
+            let indent = match clause.kind {
+                ClauseKind::Join => self.unit.as_str(),
+                _ => "",
+            };
+            trailing = self.write_clause(out, &words[clause.start..clause.end], clause, indent);
+        }
+
+        trailing
+    }
+
+    fn write_clause<'a>(
+        &self,
+        out: &mut String,
+        words: &'a [Word<'a>],
+        clause: &Clause,
+        indent: &str,
+    ) -> &'a [&'a str] {
+        out.push_str(indent);
+        let (line, trailing) = self.render(words, indent);
+        if !has_comments(words) && self.fits(indent, &line) {
+            out.push_str(&line);
+            return trailing;
+        }
+
+        let inner = format!("{}{}", indent, self.unit);
+        let body = &words[clause.header..];
+        match clause.kind {
+            // SELECT
+            //     a,
+            //     b
+            ClauseKind::List if clause.header > 0 => {
+                let (header, trailing) = self.render(&words[..clause.header], indent);
+                out.push_str(&header);
+                push_trailing(out, trailing);
+                self.write_list(out, body, &inner)
+            }
+            // CREATE TABLE t (
+            //     a INT,
+            //     b INT
+            // )
+            ClauseKind::List => match find_last_group(words) {
+                Some(open) => {
+                    let (head, trailing) = self.render(&words[..=open], indent);
+                    out.push_str(&head);
+                    push_trailing(out, trailing);
+                    let close = words.len() - 1;
+                    let trailing = self.write_list(out, &words[open + 1..close], &inner);
+                    push_trailing(out, trailing);
+                    out.push('\n');
+                    out.push_str(indent);
+                    let (tail, trailing) = self.render(&words[close..], indent);
+                    out.push_str(&tail);
+                    trailing
+                }
+                None => {
+                    out.push_str(&line);
+                    trailing
+                }
+            },
+            // WHERE a = 1
+            //     AND b = 2
+            ClauseKind::Condition => {
+                // BETWEEN low AND high 中的 AND 不是逻辑运算符
+                let mut in_between = false;
+                let conditions = split_items(body, |t| match t {
+                    Token::KeyWord(Keyword::Between) => {
+                        in_between = true;
+                        false
+                    }
+                    Token::KeyWord(Keyword::And) if in_between => {
+                        in_between = false;
+                        false
+                    }
+                    Token::KeyWord(Keyword::And | Keyword::Or) => true,
+                    _ => false,
+                });
+                let mut start = 0;
+                let mut end = clause.header + conditions[0].0.len();
+                let mut line_indent = indent;
+                let mut trailing: &[&str] = &[];
+                for (i, (condition, _)) in conditions.iter().enumerate() {
+                    // 除第一行外, 每一行以 AND 或 OR 开头
+                    if i > 0 {
+                        push_trailing(out, trailing);
+                        out.push('\n');
+                        out.push_str(&inner);
+                        start = end;
+                        end = start + 1 + condition.len();
+                        line_indent = &inner;
+                    }
+                    let (line, rest) = self.render(&words[start..end], line_indent);
+                    out.push_str(&line);
+                    trailing = rest;
+                }
+
+                trailing
+            }
+            // LEFT JOIN t
+            //     ON a = b
+            ClauseKind::Join => {
+                let on = split_items(body, |t| *t == Token::KeyWord(Keyword::On));
+                if on.len() < 2 {
+                    out.push_str(&line);
+                    return trailing;
+                }
+
+                let on_index = clause.header + on[0].0.len();
+                let (join, trailing) = self.render(&words[..on_index], indent);
+                out.push_str(&join);
+                push_trailing(out, trailing);
+                out.push('\n');
+                out.push_str(&inner);
+                let (condition, trailing) = self.render(&words[on_index..], &inner);
+                out.push_str(&condition);
+                trailing
+            }
+        }
+    }
+
+    // 每一项单独一行
+    fn write_list<'a>(
+        &self,
+        out: &mut String,
+        words: &'a [Word<'a>],
+        indent: &str,
+    ) -> &'a [&'a str] {
+        let items = split_items(words, |t| *t == Token::Comma);
+        for (item, comma) in items {
+            out.push('\n');
+            out.push_str(indent);
+            let (line, trailing) = self.render(item, indent);
+            out.push_str(&line);
+            match comma {
+                Some(comma) => {
+                    out.push(',');
+                    push_trailing(out, trailing);
+                    push_trailing(out, &comma.trailing);
+                }
+                None => return trailing,
+            }
+        }
+
+        &[]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::test::init;
+
+    fn format(sql: &str) -> String {
+        format_sql(sql, &FormatOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn format_short_statement_test() {
+        init();
+        assert_eq!(
+            format("select   a,b from t where a=-1 and b!=1"),
+            "SELECT a, b FROM t WHERE a = -1 AND b != 1;\n"
+        );
+        assert_eq!(
+            format("begin; select count(*), t.id from t;commit"),
+            "BEGIN;\nSELECT count(*), t.id FROM t;\nCOMMIT;\n"
+        );
+        assert_eq!(
+            format("select 1 - -1, \"from\", E'a\\n'"),
+            "SELECT 1 - -1, \"from\", E'a\\n';\n"
+        );
+        assert_eq!(
+            format("select distinct t.*, count(distinct a) filter (where a > 1) from t"),
+            "SELECT DISTINCT t.*, count(DISTINCT a) FILTER (WHERE a > 1) FROM t;\n"
+        );
+        assert_eq!(
+            format("select a from t where a=? limit ?;select -:x, :y::int, :x"),
+            "SELECT a FROM t WHERE a = ? LIMIT ?;\nSELECT -:x, :y::INT, :x;\n"
+        );
+        // 作为列名的非保留关键字也会转换大小写, 不改变语句的含义
+        assert_eq!(
+            format("start transaction; select start from t"),
+            "START TRANSACTION;\nSELECT START FROM t;\n"
+        );
+    }
+
+    #[test]
+    fn format_long_statement_test() {
+        init();
+        let sql = "select person.id, person.name, address.city, address.street, address.zip_code, address.country \
+                   from person left join address on person.id = address.person_id \
+                   where person.id > 10 and address.city = 'shanghai' or person.name like 'a%' and person.age >= 18 \
+                   group by person.id order by person.id desc limit 10 offset 20";
+        let expected = "\
+SELECT
+    person.id,
+    person.name,
+    address.city,
+    address.street,
+    address.zip_code,
+    address.country
+FROM person
+    LEFT JOIN address ON person.id = address.person_id
+WHERE person.id > 10
+    AND address.city = 'shanghai'
+    OR person.name LIKE 'a%'
+    AND person.age >= 18
+GROUP BY person.id
+ORDER BY person.id DESC
+LIMIT 10
+OFFSET 20;
+";
+        assert_eq!(format(sql), expected);
+        // 格式化的结果不会再改变
+        assert_eq!(format(expected), expected);
+
+        let sql = "create table movie (id integer primary key, title string not null, \
+                   release_year integer index, imdb_id string index unique)";
+        let expected = "\
+CREATE TABLE movie (
+    id INTEGER PRIMARY KEY,
+    title STRING NOT NULL,
+    release_year INTEGER INDEX,
+    imdb_id STRING INDEX UNIQUE
+);
+";
+        assert_eq!(format(sql), expected);
+
+        let sql = "create table rating (movie_id integer references movie (id) on delete cascade, \
+                   user_id integer, score integer check (score between 1 and 5), primary key (movie_id, user_id))";
+        let expected = "\
+CREATE TABLE rating (
+    movie_id INTEGER REFERENCES movie (id) ON DELETE CASCADE,
+    user_id INTEGER,
+    score INTEGER CHECK (score BETWEEN 1 AND 5),
+    PRIMARY KEY (movie_id, user_id)
+);
+";
+        assert_eq!(format(sql), expected);
+
+        let sql = "with recursive parents (id, parent_id) as (select id, parent_id from category where id = 1), \
+                   children as (select * from category where parent_id = 1) select * from parents, children";
+        let expected = "\
+WITH RECURSIVE
+    parents (id, parent_id) AS (SELECT id, parent_id FROM category WHERE id = 1),
+    children AS (SELECT * FROM category WHERE parent_id = 1)
+SELECT *
+FROM parents, children;
+";
+        assert_eq!(format(sql), expected);
+
+        let sql =
+            "select id, name from person where age > 18 union all select id, name from employee \
+                   where salary > 1000 intersect (select 1, 'a') order by id limit 10";
+        let expected = "\
+SELECT id, name
+FROM person
+WHERE age > 18
+UNION ALL
+SELECT id, name
+FROM employee
+WHERE salary > 1000
+INTERSECT (SELECT 1, 'a')
+ORDER BY id
+LIMIT 10;
+";
+        assert_eq!(format(sql), expected);
+
+        // DISTINCT ON (...) 和 SELECT 放在同一行
+        let sql = "select distinct on (person.id, person.name) person.id, person.name, address.city, address.street \
+                   from person, address";
+        let expected = "\
+SELECT DISTINCT ON (person.id, person.name)
+    person.id,
+    person.name,
+    address.city,
+    address.street
+FROM person, address;
+";
+        assert_eq!(format(sql), expected);
+
+        let sql = "select person.id, address.city, company.name from person natural left outer join address \
+                   full join company using (company_id) cross join settings where person.id > 10";
+        let expected = "\
+SELECT person.id, address.city, company.name
+FROM person
+    NATURAL LEFT OUTER JOIN address
+    FULL JOIN company USING (company_id)
+    CROSS JOIN settings
+WHERE person.id > 10;
+";
+        assert_eq!(format(sql), expected);
+
+        let sql = "insert into person (id, name, age) select id, name, age from staging where age > 18 \
+                   on conflict (id) do update set name = excluded.name, age = excluded.age returning id, name";
+        let expected = "\
+INSERT INTO person (id, name, age)
+SELECT id, name, age
+FROM staging
+WHERE age > 18
+ON CONFLICT (id) DO UPDATE
+SET name = excluded.name, age = excluded.age
+RETURNING id, name;
+";
+        assert_eq!(format(sql), expected);
+
+        let sql = "prepare get_person as select person.id, person.name, address.city from person \
+                   join address on person.id = address.person_id where person.id = $1; execute get_person(1)";
+        let expected = "\
+PREPARE get_person AS SELECT person.id, person.name, address.city
+FROM person
+    JOIN address ON person.id = address.person_id
+WHERE person.id = $1;
+EXECUTE get_person(1);
+";
+        assert_eq!(format(sql), expected);
+
+        // JOIN ... USING (...) 不是子句的开头
+        let sql = "delete from person using address join city using (city_id) \
+                   where person.id = address.person_id and city.name = 'x' order by person.id limit 100 returning person.id";
+        let expected = "\
+DELETE FROM person
+USING address
+    JOIN city USING (city_id)
+WHERE person.id = address.person_id AND city.name = 'x'
+ORDER BY person.id
+LIMIT 100
+RETURNING person.id;
+";
+        assert_eq!(format(sql), expected);
+
+        let sql = "create or replace view adult (id, name) as select id, name from person \
+                   where age >= 18 order by id; refresh materialized view adult_summary";
+        let expected = "\
+CREATE OR REPLACE VIEW adult (id, name) AS SELECT id, name
+FROM person
+WHERE age >= 18
+ORDER BY id;
+REFRESH MATERIALIZED VIEW adult_summary;
+";
+        assert_eq!(format(sql), expected);
+
+        // OVER (...) 中的 ORDER BY 不是子句的开头
+        let sql = "select id, salary, rank() over (partition by department order by salary desc), \
+                   sum(salary) over w from employee window w as (order by id rows between 1 preceding and current row)";
+        let expected = "\
+SELECT
+    id,
+    salary,
+    rank() OVER (PARTITION BY department ORDER BY salary DESC),
+    sum(salary) OVER w
+FROM employee
+WINDOW w AS (ORDER BY id ROWS BETWEEN 1 PRECEDING AND CURRENT ROW);
+";
+        assert_eq!(format(sql), expected);
+    }
+
+    #[test]
+    fn format_comment_test() {
+        init();
+        let sql = "\
+-- 查询所有用户
+select id, -- 主键
+  name /* 名字 */ from person
+where id > 1 -- 条件
+;
+
+/* 文件结尾 */";
+        let expected = "\
+-- 查询所有用户
+SELECT
+    id, -- 主键
+    name /* 名字 */
+FROM person
+WHERE id > 1; -- 条件
+
+/* 文件结尾 */
+";
+        assert_eq!(format(sql), expected);
+        assert_eq!(format(expected), expected);
+    }
+
+    #[test]
+    fn format_idempotent_test() {
+        init();
+        // 格式化之后的 SQL 再次格式化不会改变
+        for sql in [
+            "insert into t values (1, 2), (3, 4) -- x\n;",
+            "select a from t -- x\n",
+            "select a /* x */ from t /* y */;",
+            "create table t (a int, b int) -- x\n; drop table t -- y",
+            "select a, -- x\n b from t where a > 1 /* y */ and b < 2 -- z\n",
+            "update t set a = 1 -- x\n where b = 2 -- y\n",
+        ] {
+            let once = format(sql);
+            assert_eq!(format(&once), once, "{}", sql);
+        }
+
+        assert_eq!(
+            format("insert into t values (1, 2), (3, 4) -- x\n;"),
+            "INSERT INTO t VALUES (1, 2), (3, 4); -- x\n"
+        );
+    }
+
+    #[test]
+    fn format_options_test() {
+        init();
+        let options = FormatOptions {
+            keyword_case: KeywordCase::Lower,
+            indent: 2,
+            line_width: 20,
+        };
+        assert_eq!(
+            format_sql("SELECT a, b FROM t WHERE a = 1 AND b = 2", &options).unwrap(),
+            "select a, b\nfrom t\nwhere a = 1\n  and b = 2;\n"
+        );
+        assert_eq!(
+            format_sql(
+                "SELECT a FROM t WHERE a BETWEEN 1 AND 2 AND b = 1",
+                &options
+            )
+            .unwrap(),
+            "select a\nfrom t\nwhere a between 1 and 2\n  and b = 1;\n"
+        );
+
+        let options = FormatOptions {
+            keyword_case: KeywordCase::Preserve,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_sql("Select a From t", &options).unwrap(),
+            "Select a From t;\n"
+        );
+    }
+
+    #[test]
+    fn format_error_test() {
+        init();
+        match format_sql("SELECT FROM", &FormatOptions::default()) {
+            Err(Error::Parse(e)) => assert_eq!(e.span.start.column, 8),
+            r => panic!("expected parse error but get: {:?}", r),
+        }
+    }
+}
@@ -3,41 +3,68 @@ use std::fmt;
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash)]
 pub enum Keyword {
     And,
+    All,
     As,
     Asc,
     Alter,
     Add,
     Begin,
+    Between,
     Bool,
     Boolean,
     By,
+    Case,
+    Cascade,
+    Cast,
     Char,
+    Check,
     Commit,
+    Conflict,
+    Constraint,
     Create,
+    Current,
     Cross,
     Column,
+    Columns,
     Committed,
     Default,
+    Do,
+    Deallocate,
     Delete,
     Desc,
     Double,
     Drop,
+    Else,
+    End,
     Databases,
+    Database,
     Describe,
+    Distinct,
     Explain,
+    Exists,
+    Execute,
+    Except,
     False,
+    Filter,
     Float,
+    Following,
     From,
+    Foreign,
+    Full,
     Group,
     Global,
     Having,
     Level,
     Index,
+    Indexes,
+    If,
+    In,
     Infinity,
     Inner,
     Insert,
     Int,
     Integer,
+    Intersect,
     Into,
     Is,
     Isolation,
@@ -47,8 +74,11 @@ pub enum Keyword {
     Like,
     Limit,
     Modify,
+    Materialized,
     NaN,
+    Natural,
     Not,
+    Nothing,
     Null,
     Of,
     Offset,
@@ -57,33 +87,59 @@ pub enum Keyword {
     Or,
     Order,
     Outer,
+    Over,
+    Partition,
     Primary,
+    Preceding,
+    Prepare,
     Read,
+    Range,
     References,
+    Refresh,
+    Release,
+    Recursive,
     Right,
+    Restrict,
+    Returning,
     Rollback,
+    Row,
+    Rows,
     Rename,
     Repeatable,
+    Replace,
     Select,
+    Savepoint,
     Set,
     String,
     System,
     Session,
     Show,
+    Start,
     Serializable,
     To,
     Table,
     Tables,
     Text,
+    Then,
     Time,
     Transaction,
     True,
+    Truncate,
     Unique,
+    Unbounded,
+    Union,
     Uncommitted,
     Update,
+    Use,
+    Using,
     Values,
+    Variables,
+    View,
+    When,
     VarChar,
     Where,
+    Window,
+    With,
     Write,
     UserIdent,
     Int8,
@@ -98,6 +154,35 @@ pub enum Keyword {
     Float64,
 }
 
+impl Keyword {
+    // 非保留关键字只在特定的位置是关键字, 其他位置可以作为表名, 列名和函数名
+    pub fn is_reserved(&self) -> bool {
+        !matches!(
+            self,
+            Self::Start
+                | Self::Release
+                | Self::Savepoint
+                | Self::Over
+                | Self::Partition
+                | Self::Window
+                | Self::Rows
+                | Self::Range
+                | Self::Row
+                | Self::Current
+                | Self::Unbounded
+                | Self::Preceding
+                | Self::Following
+                | Self::Replace
+                | Self::End
+                | Self::Filter
+                | Self::Columns
+                | Self::Variables
+                | Self::Nothing
+                | Self::Recursive
+        )
+    }
+}
+
 impl fmt::Display for Keyword {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -105,41 +190,68 @@ impl fmt::Display for Keyword {
             "keyword: {}",
             match self {
                 Self::And => "And",
+                Self::All => "All",
                 Self::As => "As",
                 Self::Asc => "Asc",
                 Self::Add => "Add",
                 Self::Alter => "Alter",
                 Self::Begin => "Begin",
+                Self::Between => "Between",
                 Self::Bool => "Bool",
                 Self::Boolean => "Boolean",
                 Self::By => "By",
+                Self::Case => "Case",
+                Self::Cascade => "Cascade",
+                Self::Cast => "Cast",
                 Self::Char => "Char",
+                Self::Check => "Check",
                 Self::Commit => "Commit",
+                Self::Conflict => "Conflict",
+                Self::Constraint => "Constraint",
                 Self::Create => "Create",
+                Self::Current => "Current",
                 Self::Cross => "Cross",
                 Self::Column => "Column",
+                Self::Columns => "Columns",
                 Self::Committed => "Commited",
                 Self::Default => "Default",
+                Self::Do => "Do",
+                Self::Deallocate => "Deallocate",
                 Self::Delete => "Delete",
                 Self::Desc => "Desc",
                 Self::Double => "Double",
                 Self::Drop => "Drop",
+                Self::Else => "Else",
+                Self::End => "End",
                 Self::Databases => "Databases",
+                Self::Database => "Database",
                 Self::Describe => "Describe",
+                Self::Distinct => "Distinct",
                 Self::Explain => "Explain",
+                Self::Exists => "Exists",
+                Self::Execute => "Execute",
+                Self::Except => "Except",
                 Self::False => "False",
+                Self::Filter => "Filter",
                 Self::Float => "Float",
+                Self::Following => "Following",
                 Self::From => "From",
+                Self::Foreign => "Foreign",
+                Self::Full => "Full",
                 Self::Group => "Group",
                 Self::Global => "Global",
                 Self::Having => "Having",
                 Self::Level => "Level",
                 Self::Index => "Index",
+                Self::Indexes => "Indexes",
+                Self::If => "If",
+                Self::In => "In",
                 Self::Infinity => "Infinity",
                 Self::Inner => "Inner",
                 Self::Insert => "Insert",
                 Self::Int => "Int",
                 Self::Integer => "Integer",
+                Self::Intersect => "Intersect",
                 Self::Into => "Into",
                 Self::Is => "Is",
                 Self::Isolation => "Isolation",
@@ -149,8 +261,11 @@ impl fmt::Display for Keyword {
                 Self::Like => "Like",
                 Self::Limit => "Limit",
                 Self::Modify => "Modify",
+                Self::Materialized => "Materialized",
                 Self::NaN => "Nan",
+                Self::Natural => "Natural",
                 Self::Not => "Not",
+                Self::Nothing => "Nothing",
                 Self::Null => "Null",
                 Self::Of => "Of",
                 Self::Offset => "Offset",
@@ -159,33 +274,59 @@ impl fmt::Display for Keyword {
                 Self::Or => "Or",
                 Self::Order => "Order",
                 Self::Outer => "Outer",
+                Self::Over => "Over",
+                Self::Partition => "Partition",
                 Self::Primary => "Primary",
+                Self::Preceding => "Preceding",
+                Self::Prepare => "Prepare",
                 Self::Read => "Read",
+                Self::Range => "Range",
                 Self::References => "References",
+                Self::Refresh => "Refresh",
+                Self::Release => "Release",
+                Self::Recursive => "Recursive",
                 Self::Right => "Right",
+                Self::Restrict => "Restrict",
+                Self::Returning => "Returning",
                 Self::Rollback => "Rollback",
+                Self::Row => "Row",
+                Self::Rows => "Rows",
                 Self::Rename => "Rename",
                 Self::Select => "Select",
+                Self::Savepoint => "Savepoint",
                 Self::Set => "Set",
                 Self::String => "String",
                 Self::Show => "Show",
+                Self::Start => "Start",
                 Self::System => "System",
                 Self::Session => "Session",
                 Self::Serializable => "Serializable",
                 Self::Table => "Table",
                 Self::Tables => "Tables",
                 Self::Text => "Text",
+                Self::Then => "Then",
                 Self::Time => "Time",
                 Self::Transaction => "Transaction",
                 Self::True => "True",
+                Self::Truncate => "Truncate",
                 Self::To => "To",
                 Self::Unique => "Unique",
+                Self::Unbounded => "Unbounded",
+                Self::Union => "Union",
                 Self::Update => "Update",
+                Self::Use => "Use",
+                Self::Using => "Using",
                 Self::Values => "Values",
+                Self::Variables => "Variables",
+                Self::View => "View",
+                Self::When => "When",
                 Self::VarChar => "Varchar",
                 Self::Where => "Where",
+                Self::Window => "Window",
+                Self::With => "With",
                 Self::Write => "Write",
                 Self::Repeatable => "Repeatable",
+                Self::Replace => "Replace",
                 Self::Uncommitted => "Uncommited",
                 Self::UserIdent => "UserIdent",
                 Self::Int8 => "Int8",
@@ -209,37 +350,64 @@ pub fn find_keyword(keyword_str: &str) -> Keyword {
 
     match upper_keyword_str.as_str() {
         "AND" => Keyword::And,
+        "ALL" => Keyword::All,
         "AS" => Keyword::As,
         "ASC" => Keyword::Asc,
         "ADD" => Keyword::Add,
         "ALTER" => Keyword::Alter,
         "BEGIN" => Keyword::Begin,
+        "BETWEEN" => Keyword::Between,
         "BOOL" => Keyword::Bool,
         "BOOLEAN" => Keyword::Boolean,
         "BY" => Keyword::By,
+        "CASE" => Keyword::Case,
+        "CASCADE" => Keyword::Cascade,
+        "CAST" => Keyword::Cast,
         "CHAR" => Keyword::Char,
+        "CHECK" => Keyword::Check,
         "COMMIT" => Keyword::Commit,
+        "CONFLICT" => Keyword::Conflict,
+        "CONSTRAINT" => Keyword::Constraint,
         "CREATE" => Keyword::Create,
+        "CURRENT" => Keyword::Current,
         "CROSS" => Keyword::Cross,
         "COLUMN" => Keyword::Column,
+        "COLUMNS" => Keyword::Columns,
         "DEFAULT" => Keyword::Default,
+        "DO" => Keyword::Do,
+        "DEALLOCATE" => Keyword::Deallocate,
         "DELETE" => Keyword::Delete,
         "DESC" => Keyword::Desc,
         "DOUBLE" => Keyword::Double,
         "DROP" => Keyword::Drop,
+        "ELSE" => Keyword::Else,
+        "END" => Keyword::End,
         "DATABASES" => Keyword::Databases,
+        "DATABASE" => Keyword::Database,
         "DESCRIBE" => Keyword::Describe,
+        "DISTINCT" => Keyword::Distinct,
         "EXPLAIN" => Keyword::Explain,
+        "EXISTS" => Keyword::Exists,
+        "EXECUTE" => Keyword::Execute,
+        "EXCEPT" => Keyword::Except,
         "FALSE" => Keyword::False,
+        "FILTER" => Keyword::Filter,
         "FLOAT" => Keyword::Float,
+        "FOLLOWING" => Keyword::Following,
         "FROM" => Keyword::From,
+        "FOREIGN" => Keyword::Foreign,
+        "FULL" => Keyword::Full,
         "GROUP" => Keyword::Group,
         "GLOBAL" => Keyword::Global,
         "HAVING" => Keyword::Having,
         "INDEX" => Keyword::Index,
+        "INDEXES" => Keyword::Indexes,
+        "IF" => Keyword::If,
+        "IN" => Keyword::In,
         "INFINITY" => Keyword::Infinity,
         "INNER" => Keyword::Inner,
         "INTEGER" => Keyword::Integer,
+        "INTERSECT" => Keyword::Intersect,
         "INSERT" => Keyword::Insert,
         "INT" => Keyword::Int,
         "INTO" => Keyword::Into,
@@ -248,6 +416,7 @@ pub fn find_keyword(keyword_str: &str) -> Keyword {
         "TRANSACTION" => Keyword::Transaction,
         "ISOLATION" => Keyword::Isolation,
         "READ" => Keyword::Read,
+        "RANGE" => Keyword::Range,
         "COMMITTED" => Keyword::Committed,
         "JOIN" => Keyword::Join,
         "KEY" => Keyword::Key,
@@ -255,7 +424,9 @@ pub fn find_keyword(keyword_str: &str) -> Keyword {
         "LIKE" => Keyword::Like,
         "LIMIT" => Keyword::Limit,
         "NAN" => Keyword::NaN,
+        "NATURAL" => Keyword::Natural,
         "NOT" => Keyword::Not,
+        "NOTHING" => Keyword::Nothing,
         "NULL" => Keyword::Null,
         "OF" => Keyword::Of,
         "OFFSET" => Keyword::Offset,
@@ -264,31 +435,57 @@ pub fn find_keyword(keyword_str: &str) -> Keyword {
         "OR" => Keyword::Or,
         "ORDER" => Keyword::Order,
         "OUTER" => Keyword::Outer,
+        "OVER" => Keyword::Over,
+        "PARTITION" => Keyword::Partition,
         "PRIMARY" => Keyword::Primary,
+        "PRECEDING" => Keyword::Preceding,
+        "PREPARE" => Keyword::Prepare,
         "REFERENCES" => Keyword::References,
+        "REFRESH" => Keyword::Refresh,
+        "RELEASE" => Keyword::Release,
+        "RECURSIVE" => Keyword::Recursive,
         "RIGHT" => Keyword::Right,
+        "RESTRICT" => Keyword::Restrict,
+        "RETURNING" => Keyword::Returning,
         "ROLLBACK" => Keyword::Rollback,
+        "ROW" => Keyword::Row,
+        "ROWS" => Keyword::Rows,
         "RENAME" => Keyword::Rename,
         "SELECT" => Keyword::Select,
+        "SAVEPOINT" => Keyword::Savepoint,
         "STRING" => Keyword::String,
         "SHOW" => Keyword::Show,
+        "START" => Keyword::Start,
         "SET" => Keyword::Set,
         "SYSTEM" => Keyword::System,
         "SESSION" => Keyword::Session,
         "TABLE" => Keyword::Table,
         "TABLES" => Keyword::Tables,
         "TEXT" => Keyword::Text,
+        "THEN" => Keyword::Then,
         "TIME" => Keyword::Time,
         "TRUE" => Keyword::True,
+        "TRUNCATE" => Keyword::Truncate,
         "TO" => Keyword::To,
         "UNIQUE" => Keyword::Unique,
+        "UNBOUNDED" => Keyword::Unbounded,
+        "UNION" => Keyword::Union,
         "UPDATE" => Keyword::Update,
+        "USE" => Keyword::Use,
+        "USING" => Keyword::Using,
         "VALUES" => Keyword::Values,
+        "VARIABLES" => Keyword::Variables,
+        "VIEW" => Keyword::View,
+        "WHEN" => Keyword::When,
         "VARCHAR" => Keyword::VarChar,
         "WHERE" => Keyword::Where,
+        "WINDOW" => Keyword::Window,
+        "WITH" => Keyword::With,
         "WRITE" => Keyword::Write,
         "MODIFY" => Keyword::Modify,
+        "MATERIALIZED" => Keyword::Materialized,
         "REPEATABLE" => Keyword::Repeatable,
+        "REPLACE" => Keyword::Replace,
         "UNCOMMITTED" => Keyword::Uncommitted,
         "SERIALIZABLE" => Keyword::Serializable,
         "INT8" => Keyword::Int8,
@@ -1,45 +1,91 @@
 use super::keyword::Keyword;
-use super::token::Token;
+use super::span::{Location, Span};
+use super::token::{SpannedToken, Token};
 use crate::parser::keyword::find_keyword;
 
 const STOP_CHAR: char = 0 as char;
 
 pub struct Lexer {
     origin_str: String,
+    // origin_str.char_indices(), 按字符而不是字节读取
+    chars: Vec<(usize, char)>,
     cur_read_char: char,
+    // cur_read_char 的字节偏移
     pos: usize,
+    // 下一个字符在 chars 中的下标
     read_pos: usize,
+    // cur_read_char 所在的行列号
+    line: usize,
+    column: usize,
+    // 是否把注释作为 Token::Comment 返回, 格式化 SQL 时需要保留注释
+    keep_comments: bool,
 }
 
 impl Lexer {
     pub fn new_lexer(sql_str: String) -> Self {
         let mut lexer = Lexer {
+            chars: sql_str.char_indices().collect(),
             origin_str: sql_str,
             cur_read_char: STOP_CHAR,
             pos: 0,
             read_pos: 0,
+            line: 1,
+            column: 1,
+            keep_comments: false,
         };
         lexer.read_char();
 
         lexer
     }
 
+    pub fn with_comments(mut self) -> Self {
+        self.keep_comments = true;
+        self
+    }
+
     pub fn update(&mut self, new_sql_str: String) -> &Self {
+        self.chars = new_sql_str.char_indices().collect();
         self.origin_str = new_sql_str;
         self.cur_read_char = STOP_CHAR;
         self.pos = 0;
         self.read_pos = 0;
+        self.line = 1;
+        self.column = 1;
         self.read_char();
 
         self
     }
 
-    pub fn next_token(&mut self) -> Token {
-        self.skip_space();
+    // span 对应的原始 SQL
+    pub fn source(&self, span: Span) -> &str {
+        &self.origin_str[span.start.offset..span.end.offset]
+    }
+
+    pub fn next_token(&mut self) -> SpannedToken {
+        if let Some(t) = self.skip_space() {
+            return t;
+        }
+
+        let start = self.location();
+        let token = self.read_token();
+
+        SpannedToken {
+            token,
+            span: Span::new(start, self.location()),
+        }
+    }
 
+    fn read_token(&mut self) -> Token {
         let t = match self.cur_read_char {
             '=' => Token::Equal,
+            // .5 是一个浮点数
+            '.' if is_digit(self.peek_char()) => return self.read_number(),
             '.' => Token::Period,
+            // x::INT 类型转换
+            ':' if self.peek_char() == ':' => {
+                self.read_char();
+                Token::DoubleColon
+            }
             '>' => match self.peek_char() {
                 '=' => {
                     self.read_char();
@@ -60,6 +106,7 @@ impl Lexer {
             '/' => Token::Slash,
             '^' => Token::Caret,
             '%' => Token::Percent,
+            '~' => Token::Tilde,
             '!' => match self.peek_char() {
                 '=' => {
                     self.read_char();
@@ -68,16 +115,45 @@ impl Lexer {
                 _ => Token::Exclamation,
             },
             '?' => Token::Question,
+            // $1 和 :name 是绑定参数
+            '$' if is_digit(self.peek_char()) => {
+                let start = self.pos;
+                self.read_char();
+                while is_digit(self.cur_read_char) {
+                    self.read_char();
+                }
+                return Token::Parameter(self.origin_str[start..self.pos].to_owned());
+            }
+            ':' if is_letter(self.peek_char()) => {
+                let start = self.pos;
+                self.read_char();
+                self.read_identifier();
+                return Token::Parameter(self.origin_str[start..self.pos].to_owned());
+            }
             '(' => Token::LeftParen,
             ')' => Token::RightParen,
             ',' => Token::Comma,
             ';' => Token::Semicolon,
             STOP_CHAR => Token::Eof,
-            // 这里将 ' 和 " 混淆在一起
-            // 例如 'xxx" 是可以的,
-            // TODO: fix it
-            '\'' => Token::String(self.read_string()),
-            '\"' => Token::String(self.read_string()),
+            // 'xxx' 是字符串, 'it''s' 中两个单引号表示一个单引号
+            '\'' => match self.read_quoted('\'', false) {
+                Some(s) => Token::String(s),
+                None => Token::Illegal("unterminated string literal".to_owned()),
+            },
+            // "xxx" 和 `xxx` 是带引号的标识符, 保留大小写且不会被识别为关键字
+            '\"' | '`' => match self.read_quoted(self.cur_read_char, false) {
+                Some(s) if s.is_empty() => Token::Illegal("empty quoted identifier".to_owned()),
+                Some(s) => Token::Ident(s),
+                None => Token::Illegal("unterminated quoted identifier".to_owned()),
+            },
+            // E'xxx' 中可以使用 \n \t 等转义字符
+            'E' | 'e' if self.peek_char() == '\'' => {
+                self.read_char();
+                match self.read_quoted('\'', true) {
+                    Some(s) => Token::String(s),
+                    None => Token::Illegal("unterminated string literal".to_owned()),
+                }
+            }
             ch => {
                 if is_letter(ch) {
                     let ident_str = self.read_identifier();
@@ -89,10 +165,10 @@ impl Lexer {
 
                     return t;
                 } else if is_digit(self.cur_read_char) {
-                    return Token::Number(self.read_number());
+                    return self.read_number();
                 }
 
-                Token::KeyWord(Keyword::UserIdent)
+                Token::Illegal(format!("unexpected character {:?}", ch))
             }
         };
         self.read_char();
@@ -100,61 +176,134 @@ impl Lexer {
         t
     }
 
+    fn location(&self) -> Location {
+        Location {
+            offset: self.pos,
+            line: self.line,
+            column: self.column,
+        }
+    }
+
     fn read_char(&mut self) {
-        if self.read_pos >= self.origin_str.len() {
-            self.cur_read_char = STOP_CHAR;
-        } else {
-            self.cur_read_char = self.origin_str.as_bytes()[self.read_pos] as char;
+        // 跳过当前字符, 更新行列号
+        match self.cur_read_char {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+            }
+            STOP_CHAR => {}
+            _ => self.column += 1,
         }
 
-        self.pos = self.read_pos;
-        self.read_pos += 1;
+        match self.chars.get(self.read_pos) {
+            Some(&(offset, ch)) => {
+                self.cur_read_char = ch;
+                self.pos = offset;
+                self.read_pos += 1;
+            }
+            None => {
+                self.cur_read_char = STOP_CHAR;
+                self.pos = self.origin_str.len();
+            }
+        }
     }
 
-    fn skip_space(&mut self) {
+    // 跳过空白字符以及 -- 和 /* */ 注释
+    // 如果注释没有闭合, 返回一个 Token::Illegal
+    // keep_comments 为 true 时, 遇到注释返回 Token::Comment
+    fn skip_space(&mut self) -> Option<SpannedToken> {
         loop {
-            if self.cur_read_char == ' '
-                || self.cur_read_char == '\t'
-                || self.cur_read_char == '\n'
-                || self.cur_read_char == '\r'
-            {
-                self.read_char();
-                continue;
-            } else {
-                break;
+            let start = self.location();
+            match (self.cur_read_char, self.peek_char()) {
+                (' ' | '\t' | '\n' | '\r', _) => {
+                    self.read_char();
+                    continue;
+                }
+                ('-', '-') => {
+                    while self.cur_read_char != '\n' && self.cur_read_char != STOP_CHAR {
+                        self.read_char();
+                    }
+                }
+                ('/', '*') => {
+                    self.read_char();
+                    self.read_char();
+                    loop {
+                        match (self.cur_read_char, self.peek_char()) {
+                            ('*', '/') => {
+                                self.read_char();
+                                self.read_char();
+                                break;
+                            }
+                            (STOP_CHAR, _) => {
+                                return Some(SpannedToken {
+                                    token: Token::Illegal("unterminated block comment".to_owned()),
+                                    span: Span::new(start, self.location()),
+                                });
+                            }
+                            _ => self.read_char(),
+                        }
+                    }
+                }
+                _ => return None,
+            }
+
+            if self.keep_comments {
+                let end = self.location();
+                return Some(SpannedToken {
+                    token: Token::Comment(self.origin_str[start.offset..end.offset].to_owned()),
+                    span: Span::new(start, end),
+                });
             }
         }
     }
 
     fn peek_char(&self) -> char {
-        if self.read_pos >= self.origin_str.len() {
-            STOP_CHAR
-        } else {
-            self.origin_str.as_bytes()[self.read_pos] as char
+        match self.chars.get(self.read_pos) {
+            Some(&(_, ch)) => ch,
+            None => STOP_CHAR,
         }
     }
 
-    fn read_string(&mut self) -> String {
-        let pre_pos = self.pos + 1;
+    // 读取被 quote 包围的内容, 连续两个 quote 表示一个 quote 字符
+    // 进入时 cur_read_char 是开头的 quote, 返回时是结尾的 quote
+    // 没有闭合时返回 None
+    fn read_quoted(&mut self, quote: char, backslash_escape: bool) -> Option<String> {
+        let mut s = String::new();
         loop {
             self.read_char();
-
-            if self.cur_read_char == '\''
-                || self.cur_read_char == '\"'
-                || self.cur_read_char == STOP_CHAR
-            {
-                break;
+            match self.cur_read_char {
+                STOP_CHAR => return None,
+                ch if ch == quote => {
+                    if self.peek_char() != quote {
+                        return Some(s);
+                    }
+                    self.read_char();
+                    s.push(quote);
+                }
+                '\\' if backslash_escape => {
+                    self.read_char();
+                    match self.cur_read_char {
+                        STOP_CHAR => return None,
+                        'b' => s.push('\u{8}'),
+                        'f' => s.push('\u{c}'),
+                        'n' => s.push('\n'),
+                        'r' => s.push('\r'),
+                        't' => s.push('\t'),
+                        '0' => s.push('\0'),
+                        // 其余字符按字面意义处理, 例如 \\ \'
+                        ch => s.push(ch),
+                    }
+                }
+                ch => s.push(ch),
             }
         }
-
-        String::from(&self.origin_str[pre_pos..self.pos])
     }
 
     fn read_identifier(&mut self) -> String {
         let pre_pos = self.pos;
 
         loop {
-            if is_letter(self.cur_read_char) || is_digit(self.cur_read_char) {
+            if is_letter(self.cur_read_char) || self.cur_read_char.is_numeric() {
                 self.read_char();
             } else {
                 break;
@@ -164,28 +313,105 @@ impl Lexer {
         String::from(&self.origin_str[pre_pos..self.pos])
     }
 
-    fn read_number(&mut self) -> String {
+    // 读取一个数字:
+    //   整数:       123, 1_000
+    //   浮点数:     1.5, 1., .5, 1e10, 2.5E-3
+    //   十六进制:   0x1F
+    // 不合法的数字, 例如 1.2.3, 1e, 0x, 12abc 返回 Token::Illegal
+    fn read_number(&mut self) -> Token {
         let pre_pos = self.pos;
+        let token = self.scan_number();
+
+        // 数字后面不能紧跟字母, 数字, 下划线或者 '.'
+        let is_number_char = |ch: char| is_letter(ch) || ch.is_numeric() || ch == '.';
+        match token {
+            Some(t) if !is_number_char(self.cur_read_char) => t,
+            _ => {
+                while is_number_char(self.cur_read_char) {
+                    self.read_char();
+                }
+                Token::Illegal(format!(
+                    "invalid number literal {}",
+                    &self.origin_str[pre_pos..self.pos]
+                ))
+            }
+        }
+    }
+
+    fn scan_number(&mut self) -> Option<Token> {
+        if self.cur_read_char == '0' && matches!(self.peek_char(), 'x' | 'X') {
+            self.read_char();
+            self.read_char();
+            return Some(Token::HexInteger(
+                self.read_digits(|ch| ch.is_ascii_hexdigit())?,
+            ));
+        }
+
+        let mut number = String::new();
+        let mut is_float = false;
+        if self.cur_read_char != '.' {
+            number.push_str(&self.read_digits(is_digit)?);
+        }
+
+        if self.cur_read_char == '.' {
+            is_float = true;
+            number.push('.');
+            self.read_char();
+            if is_digit(self.cur_read_char) {
+                number.push_str(&self.read_digits(is_digit)?);
+            }
+        }
+
+        if matches!(self.cur_read_char, 'e' | 'E') {
+            is_float = true;
+            number.push('e');
+            self.read_char();
+            if matches!(self.cur_read_char, '+' | '-') {
+                number.push(self.cur_read_char);
+                self.read_char();
+            }
+            number.push_str(&self.read_digits(is_digit)?);
+        }
+
+        if is_float {
+            Some(Token::Float(number))
+        } else {
+            Some(Token::Integer(number))
+        }
+    }
+
+    // 读取连续的数字, 数字之间可以用一个 _ 分隔, 例如 1_000
+    // 返回去掉 _ 之后的数字, 没有读到数字时返回 None
+    fn read_digits(&mut self, is_digit: fn(char) -> bool) -> Option<String> {
+        let mut digits = String::new();
         loop {
-            if is_digit(self.cur_read_char) || self.cur_read_char == '.' {
-                self.read_char()
+            if is_digit(self.cur_read_char) {
+                digits.push(self.cur_read_char);
+                self.read_char();
+            } else if self.cur_read_char == '_' && !digits.is_empty() && is_digit(self.peek_char())
+            {
+                self.read_char();
             } else {
                 break;
             }
         }
 
-        String::from(&self.origin_str[pre_pos..self.pos])
+        if digits.is_empty() {
+            None
+        } else {
+            Some(digits)
+        }
     }
 }
 
-// 是否是 字母开头
+// 是否是 字母开头, 包括中文等 Unicode 字母
 fn is_letter(ch: char) -> bool {
-    ch.is_ascii_alphabetic() || ch == '_'
+    ch.is_alphabetic() || ch == '_'
 }
 
 // 是否是 数字
 fn is_digit(ch: char) -> bool {
-    ch.is_numeric()
+    ch.is_ascii_digit()
 }
 
 #[cfg(test)]
@@ -209,7 +435,7 @@ mod test {
         ];
 
         for t in result {
-            let token = lexer.next_token();
+            let token = lexer.next_token().token;
             assert_eq!(t, token);
         }
 
@@ -258,7 +484,7 @@ mod test {
 
         lexer.update(sql.to_owned());
         for t in result {
-            assert_eq!(lexer.next_token(), t);
+            assert_eq!(lexer.next_token().token, t);
         }
 
         sql = r#"INSERT INTO movie 
@@ -281,27 +507,27 @@ mod test {
             Token::RightParen,
             Token::KeyWord(Keyword::Values),
             Token::LeftParen,
-            Token::Number("1".to_string()),
+            Token::Integer("1".to_string()),
             Token::Comma,
-            Token::String("Sicario".to_string()),
+            Token::Ident("Sicario".to_string()),
             Token::Comma,
-            Token::Number("2015".to_string()),
+            Token::Integer("2015".to_string()),
             Token::RightParen,
             Token::Comma,
             Token::LeftParen,
-            Token::Number("2".to_string()),
+            Token::Integer("2".to_string()),
             Token::Comma,
-            Token::String("Stalker".to_string()),
+            Token::Ident("Stalker".to_string()),
             Token::Comma,
-            Token::Number("1979".to_string()),
+            Token::Integer("1979".to_string()),
             Token::RightParen,
             Token::Comma,
             Token::LeftParen,
-            Token::Number("3".to_string()),
+            Token::Integer("3".to_string()),
             Token::Comma,
-            Token::String("Her".to_string()),
+            Token::Ident("Her".to_string()),
             Token::Comma,
-            Token::Number("2013".to_string()),
+            Token::Integer("2013".to_string()),
             Token::RightParen,
             Token::Semicolon,
             Token::Eof,
@@ -309,7 +535,7 @@ mod test {
 
         lexer.update(sql.to_owned());
         for t in result {
-            assert_eq!(lexer.next_token(), t);
+            assert_eq!(lexer.next_token().token, t);
         }
 
         sql = r#"INSERT INTO movies VALUES 
@@ -323,45 +549,45 @@ mod test {
             Token::Ident("movies".to_string()),
             Token::KeyWord(Keyword::Values),
             Token::LeftParen,
-            Token::Number("1".to_string()),
+            Token::Integer("1".to_string()),
             Token::Comma,
             Token::String("Stalker".to_string()),
             Token::Comma,
-            Token::Number("1".to_string()),
+            Token::Integer("1".to_string()),
             Token::Comma,
-            Token::Number("1".to_string()),
+            Token::Integer("1".to_string()),
             Token::Comma,
-            Token::Number("1979".to_string()),
+            Token::Integer("1979".to_string()),
             Token::Comma,
-            Token::Number("8.2".to_string()),
+            Token::Float("8.2".to_string()),
             Token::RightParen,
             Token::Comma,
             Token::LeftParen,
-            Token::Number("2".to_string()),
+            Token::Integer("2".to_string()),
             Token::Comma,
             Token::String("Sicario".to_string()),
             Token::Comma,
-            Token::Number("2".to_string()),
+            Token::Integer("2".to_string()),
             Token::Comma,
-            Token::Number("2".to_string()),
+            Token::Integer("2".to_string()),
             Token::Comma,
-            Token::Number("2015".to_string()),
+            Token::Integer("2015".to_string()),
             Token::Comma,
-            Token::Number("7.6".to_string()),
+            Token::Float("7.6".to_string()),
             Token::RightParen,
             Token::Comma,
             Token::LeftParen,
-            Token::Number("12".to_string()),
+            Token::Integer("12".to_string()),
             Token::Comma,
             Token::String("Eternal Sunshine of the Spotless Mind".to_string()),
             Token::Comma,
-            Token::Number("5".to_string()),
+            Token::Integer("5".to_string()),
             Token::Comma,
-            Token::Number("3".to_string()),
+            Token::Integer("3".to_string()),
             Token::Comma,
-            Token::Number("2004".to_string()),
+            Token::Integer("2004".to_string()),
             Token::Comma,
-            Token::Number("8.3".to_string()),
+            Token::Float("8.3".to_string()),
             Token::RightParen,
             Token::Semicolon,
             Token::Eof,
@@ -369,7 +595,7 @@ mod test {
 
         lexer.update(sql.to_owned());
         for t in result {
-            assert_eq!(lexer.next_token(), t);
+            assert_eq!(lexer.next_token().token, t);
         }
     }
 
@@ -388,7 +614,7 @@ mod test {
         ];
 
         for t in result {
-            assert_eq!(lexer.next_token(), t);
+            assert_eq!(lexer.next_token().token, t);
         }
 
         sql = "DELETE FROM studios WHERE id = 1;";
@@ -402,13 +628,13 @@ mod test {
             Token::KeyWord(Keyword::Where),
             Token::Ident("id".to_string()),
             Token::Equal,
-            Token::Number("1".to_string()),
+            Token::Integer("1".to_string()),
             Token::Semicolon,
             Token::Eof,
         ];
 
         for t in result {
-            assert_eq!(t, lexer.next_token());
+            assert_eq!(t, lexer.next_token().token);
         }
 
         sql = "UPDATE movies set id = 1;";
@@ -420,13 +646,13 @@ mod test {
             Token::KeyWord(Keyword::Set),
             Token::Ident("id".to_string()),
             Token::Equal,
-            Token::Number("1".to_owned()),
+            Token::Integer("1".to_owned()),
             Token::Semicolon,
             Token::Eof,
         ];
 
         for t in result {
-            assert_eq!(t, lexer.next_token());
+            assert_eq!(t, lexer.next_token().token);
         }
 
         sql = "Select 3.14 * 8.091;";
@@ -434,15 +660,15 @@ mod test {
 
         result = vec![
             Token::KeyWord(Keyword::Select),
-            Token::Number("3.14".to_string()),
+            Token::Float("3.14".to_string()),
             Token::Asterisk,
-            Token::Number("8.091".to_string()),
+            Token::Float("8.091".to_string()),
             Token::Semicolon,
             Token::Eof,
         ];
 
         for t in result {
-            assert_eq!(t, lexer.next_token());
+            assert_eq!(t, lexer.next_token().token);
         }
 
         sql = "select 1 ^ 8 / infinity, 8 ^ 10, infinity, infinity / infinity;";
@@ -450,15 +676,15 @@ mod test {
 
         result = vec![
             Token::KeyWord(Keyword::Select),
-            Token::Number("1".to_string()),
+            Token::Integer("1".to_string()),
             Token::Caret,
-            Token::Number("8".to_string()),
+            Token::Integer("8".to_string()),
             Token::Slash,
             Token::KeyWord(Keyword::Infinity),
             Token::Comma,
-            Token::Number("8".to_string()),
+            Token::Integer("8".to_string()),
             Token::Caret,
-            Token::Number("10".to_string()),
+            Token::Integer("10".to_string()),
             Token::Comma,
             Token::KeyWord(Keyword::Infinity),
             Token::Comma,
@@ -470,7 +696,26 @@ mod test {
         ];
 
         for t in result {
-            assert_eq!(t, lexer.next_token());
+            assert_eq!(t, lexer.next_token().token);
+        }
+
+        sql = "select ~a, !~1;";
+        lexer.update(sql.to_string());
+
+        result = vec![
+            Token::KeyWord(Keyword::Select),
+            Token::Tilde,
+            Token::Ident("a".to_string()),
+            Token::Comma,
+            Token::Exclamation,
+            Token::Tilde,
+            Token::Integer("1".to_string()),
+            Token::Semicolon,
+            Token::Eof,
+        ];
+
+        for t in result {
+            assert_eq!(t, lexer.next_token().token);
         }
 
         sql = "SELECT Not True, Not False, Not Null;";
@@ -492,7 +737,7 @@ mod test {
         lexer.update(sql.to_owned());
 
         for t in result {
-            assert_eq!(t, lexer.next_token());
+            assert_eq!(t, lexer.next_token().token);
         }
 
         sql = r#"SELECT m.id, m.title, g.name AS genre, m.released, s.name
@@ -560,7 +805,7 @@ mod test {
             Token::Period,
             Token::Ident("rating".to_owned()),
             Token::GreaterThanOrEqual,
-            Token::Number("8".to_owned()),
+            Token::Integer("8".to_owned()),
             Token::KeyWord(Keyword::Where),
             Token::Ident("m".to_owned()),
             Token::Period,
@@ -574,13 +819,13 @@ mod test {
             Token::Period,
             Token::Ident("released".to_owned()),
             Token::GreaterThanOrEqual,
-            Token::Number("2000".to_owned()),
+            Token::Integer("2000".to_owned()),
             Token::KeyWord(Keyword::And),
             Token::Ident("g".to_owned()),
             Token::Period,
             Token::Ident("id".to_owned()),
             Token::Equal,
-            Token::Number("1".to_owned()),
+            Token::Integer("1".to_owned()),
             Token::KeyWord(Keyword::Order),
             Token::KeyWord(Keyword::By),
             Token::Ident("m".to_owned()),
@@ -594,7 +839,286 @@ mod test {
         lexer.update(sql.to_owned());
 
         for t in result {
-            assert_eq!(t, lexer.next_token());
+            assert_eq!(t, lexer.next_token().token);
+        }
+    }
+
+    #[test]
+    pub fn token_span_test() {
+        let sql = "SELECT id,\n  name FROM t;";
+        let mut lexer = Lexer::new_lexer(sql.to_string());
+
+        let result = vec![
+            (Token::KeyWord(Keyword::Select), (0, 1, 1), (6, 1, 7)),
+            (Token::Ident("id".to_owned()), (7, 1, 8), (9, 1, 10)),
+            (Token::Comma, (9, 1, 10), (10, 1, 11)),
+            (Token::Ident("name".to_owned()), (13, 2, 3), (17, 2, 7)),
+            (Token::KeyWord(Keyword::From), (18, 2, 8), (22, 2, 12)),
+            (Token::Ident("t".to_owned()), (23, 2, 13), (24, 2, 14)),
+            (Token::Semicolon, (24, 2, 14), (25, 2, 15)),
+            (Token::Eof, (25, 2, 15), (25, 2, 15)),
+        ];
+
+        for (token, start, end) in result {
+            let t = lexer.next_token();
+            assert_eq!(t.token, token);
+            assert_eq!(
+                (t.span.start.offset, t.span.start.line, t.span.start.column),
+                start
+            );
+            assert_eq!((t.span.end.offset, t.span.end.line, t.span.end.column), end);
+        }
+    }
+
+    #[test]
+    pub fn unicode_test() {
+        let mut sql = "SELECT 名字, città FROM 用户 WHERE 城市 = '北京' AND note = '😀 ok';";
+        let mut lexer = Lexer::new_lexer(sql.to_string());
+
+        let mut result = vec![
+            Token::KeyWord(Keyword::Select),
+            Token::Ident("名字".to_owned()),
+            Token::Comma,
+            Token::Ident("città".to_owned()),
+            Token::KeyWord(Keyword::From),
+            Token::Ident("用户".to_owned()),
+            Token::KeyWord(Keyword::Where),
+            Token::Ident("城市".to_owned()),
+            Token::Equal,
+            Token::String("北京".to_owned()),
+            Token::KeyWord(Keyword::And),
+            Token::Ident("note".to_owned()),
+            Token::Equal,
+            Token::String("😀 ok".to_owned()),
+            Token::Semicolon,
+            Token::Eof,
+        ];
+
+        for t in result {
+            assert_eq!(lexer.next_token().token, t);
+        }
+
+        sql = "INSERT INTO t VALUES ('Ünïcödé', \"ß\")";
+        lexer.update(sql.to_owned());
+        result = vec![
+            Token::KeyWord(Keyword::Insert),
+            Token::KeyWord(Keyword::Into),
+            Token::Ident("t".to_owned()),
+            Token::KeyWord(Keyword::Values),
+            Token::LeftParen,
+            Token::String("Ünïcödé".to_owned()),
+            Token::Comma,
+            Token::Ident("ß".to_owned()),
+            Token::RightParen,
+            Token::Eof,
+        ];
+
+        for t in result {
+            assert_eq!(lexer.next_token().token, t);
+        }
+    }
+
+    #[test]
+    pub fn unicode_span_test() {
+        // column 按字符计数, offset 按字节计数
+        let sql = "SELECT '北京', 名字";
+        let mut lexer = Lexer::new_lexer(sql.to_string());
+
+        lexer.next_token();
+        let t = lexer.next_token();
+        assert_eq!(t.token, Token::String("北京".to_owned()));
+        assert_eq!((t.span.start.offset, t.span.start.column), (7, 8));
+        assert_eq!((t.span.end.offset, t.span.end.column), (15, 12));
+
+        lexer.next_token();
+        let t = lexer.next_token();
+        assert_eq!(t.token, Token::Ident("名字".to_owned()));
+        assert_eq!((t.span.start.offset, t.span.start.column), (17, 14));
+        assert_eq!(&sql[t.span.start.offset..t.span.end.offset], "名字");
+    }
+
+    #[test]
+    pub fn quote_and_comment_test() {
+        let mut sql = r#"-- line comment
+            SELECT 'it''s', E'a\nb\\c\'d', "Select" /* block
+            comment */, `user ""x"`, "a""b" FROM t; -- end"#;
+        let mut lexer = Lexer::new_lexer(sql.to_string());
+
+        let mut result = vec![
+            Token::KeyWord(Keyword::Select),
+            Token::String("it's".to_owned()),
+            Token::Comma,
+            Token::String("a\nb\\c'd".to_owned()),
+            Token::Comma,
+            Token::Ident("Select".to_owned()),
+            Token::Comma,
+            Token::Ident("user \"\"x\"".to_owned()),
+            Token::Comma,
+            Token::Ident("a\"b".to_owned()),
+            Token::KeyWord(Keyword::From),
+            Token::Ident("t".to_owned()),
+            Token::Semicolon,
+            Token::Eof,
+        ];
+
+        for t in result {
+            assert_eq!(lexer.next_token().token, t);
+        }
+
+        // ' 和 " 不再可以混用
+        sql = r#"SELECT 'abc", 1"#;
+        lexer.update(sql.to_owned());
+        result = vec![
+            Token::KeyWord(Keyword::Select),
+            Token::Illegal("unterminated string literal".to_owned()),
+            Token::Eof,
+        ];
+
+        for t in result {
+            assert_eq!(lexer.next_token().token, t);
+        }
+
+        sql = "SELECT 1 - 2 /* unterminated";
+        lexer.update(sql.to_owned());
+        result = vec![
+            Token::KeyWord(Keyword::Select),
+            Token::Integer("1".to_owned()),
+            Token::Minus,
+            Token::Integer("2".to_owned()),
+            Token::Illegal("unterminated block comment".to_owned()),
+            Token::Eof,
+        ];
+
+        for t in result {
+            assert_eq!(lexer.next_token().token, t);
+        }
+
+        // 需要保留注释时, 注释作为 Token::Comment 返回
+        sql = "SELECT 1 -- one\n/* two */ , 2";
+        let mut comment_lexer = Lexer::new_lexer(sql.to_owned()).with_comments();
+        result = vec![
+            Token::KeyWord(Keyword::Select),
+            Token::Integer("1".to_owned()),
+            Token::Comment("-- one".to_owned()),
+            Token::Comment("/* two */".to_owned()),
+            Token::Comma,
+            Token::Integer("2".to_owned()),
+            Token::Eof,
+        ];
+
+        for t in result {
+            assert_eq!(comment_lexer.next_token().token, t);
+        }
+
+        sql = r#"SELECT "", "abc, @"#;
+        lexer.update(sql.to_owned());
+        result = vec![
+            Token::KeyWord(Keyword::Select),
+            Token::Illegal("empty quoted identifier".to_owned()),
+            Token::Comma,
+            Token::Illegal("unterminated quoted identifier".to_owned()),
+            Token::Eof,
+        ];
+
+        for t in result {
+            assert_eq!(lexer.next_token().token, t);
+        }
+
+        lexer.update("SELECT @".to_owned());
+        lexer.next_token();
+        assert_eq!(
+            lexer.next_token().token,
+            Token::Illegal("unexpected character '@'".to_owned())
+        );
+    }
+
+    #[test]
+    pub fn number_test() {
+        let sql = "SELECT 1_000, 1.5, 1., .5, 1e10, 2.5E-3, 3e+2, 0x1F, 0XfF_ff, t.id, 2::t;";
+        let mut lexer = Lexer::new_lexer(sql.to_string());
+
+        let result = vec![
+            Token::KeyWord(Keyword::Select),
+            Token::Integer("1000".to_owned()),
+            Token::Comma,
+            Token::Float("1.5".to_owned()),
+            Token::Comma,
+            Token::Float("1.".to_owned()),
+            Token::Comma,
+            Token::Float(".5".to_owned()),
+            Token::Comma,
+            Token::Float("1e10".to_owned()),
+            Token::Comma,
+            Token::Float("2.5e-3".to_owned()),
+            Token::Comma,
+            Token::Float("3e+2".to_owned()),
+            Token::Comma,
+            Token::HexInteger("1F".to_owned()),
+            Token::Comma,
+            Token::HexInteger("fFff".to_owned()),
+            Token::Comma,
+            Token::Ident("t".to_owned()),
+            Token::Period,
+            Token::Ident("id".to_owned()),
+            Token::Comma,
+            Token::Integer("2".to_owned()),
+            Token::DoubleColon,
+            Token::Ident("t".to_owned()),
+            Token::Semicolon,
+            Token::Eof,
+        ];
+
+        for t in result {
+            assert_eq!(lexer.next_token().token, t);
+        }
+
+        for number in [
+            "1.2.3", "1e", "1e+", "0x", "0xZ1", "12abc", "1_", "1__0", "1._5",
+        ] {
+            lexer.update(format!("{} + 1", number));
+            assert_eq!(
+                lexer.next_token().token,
+                Token::Illegal(format!("invalid number literal {}", number))
+            );
+            assert_eq!(lexer.next_token().token, Token::Add);
+        }
+    }
+
+    #[test]
+    pub fn parameter_test() {
+        let sql = "a = ? AND b IN ($1, $12) OR c = :name_1 AND d::int";
+        let mut lexer = Lexer::new_lexer(sql.to_string());
+
+        let result = vec![
+            Token::Ident("a".to_owned()),
+            Token::Equal,
+            Token::Question,
+            Token::KeyWord(Keyword::And),
+            Token::Ident("b".to_owned()),
+            Token::KeyWord(Keyword::In),
+            Token::LeftParen,
+            Token::Parameter("$1".to_owned()),
+            Token::Comma,
+            Token::Parameter("$12".to_owned()),
+            Token::RightParen,
+            Token::KeyWord(Keyword::Or),
+            Token::Ident("c".to_owned()),
+            Token::Equal,
+            Token::Parameter(":name_1".to_owned()),
+            Token::KeyWord(Keyword::And),
+            Token::Ident("d".to_owned()),
+            Token::DoubleColon,
+            Token::KeyWord(Keyword::Int),
+            Token::Eof,
+        ];
+
+        for t in result {
+            assert_eq!(lexer.next_token().token, t);
+        }
+
+        for sql in ["$a", ": name"] {
+            lexer.update(sql.to_owned());
+            assert!(matches!(lexer.next_token().token, Token::Illegal(_)));
         }
     }
 }
@@ -1,44 +1,59 @@
-mod column;
+pub mod column;
 
-mod data_type;
-mod expression;
-mod keyword;
+pub mod data_type;
+mod display;
+pub mod expression;
+pub mod formatter;
+pub mod keyword;
 pub mod lexer;
-mod operation;
+pub mod operation;
 mod operator;
-mod stmt;
+pub mod span;
+pub mod stmt;
 pub mod token;
+pub mod visitor;
 
 use crate::parser::operator::{is_infix_oper, is_prefix_oper};
 use crate::parser::stmt::{AlterStmt, AlterType, CreateIndexStmt, DeleteTableStmt};
 use crate::parser::{operation::Operation, operator::match_precedence};
 
-use crate::{
-    error::{Error, Result},
-    fmt_err,
-};
+use crate::error::{Error, ParseError, Result};
 use data_type::DataType;
-use expression::Expression;
 use expression::Literal;
+use expression::{
+    Expression, Function, Parameter, WindowFrame, WindowFrameBound, WindowFrameUnits, WindowSpec,
+    WindowType,
+};
 use keyword::Keyword;
 use lexer::Lexer;
-use std::collections::BTreeMap;
+use span::{Span, Spanned};
 use stmt::Statement;
-use token::Token;
+use token::{SpannedToken, Token};
 
 use self::stmt::{
-    DropTableStmt, ExplainStmt, SetStmt, SetVariableType, TransactionIsolationLevel, UpdateStmt,
+    ConflictAction, CreateDatabaseStmt, CreateViewStmt, DropDatabaseStmt, DropIndexStmt,
+    DropTableStmt, DropViewStmt, ExecuteStmt, ExplainStmt, InsertSource, OnConflict, PrepareStmt,
+    SetStmt, SetVariableType, ShowVariablesStmt, SystemTime, TransactionIsolationLevel, UpdateStmt,
 };
 use self::{
-    column::Column,
+    column::{Column, ConstraintKind, ForeignKey, ReferentialAction, TableConstraint},
     operator::Precedence,
-    stmt::{FromItem, JoinType, OrderByType, SelectStmt},
+    stmt::{
+        Cte, Distinct, FromItem, JoinConstraint, JoinType, OrderByType, Query, SelectItem,
+        SelectStmt, SetExpr, SetOperator, With,
+    },
 };
 
+// 约定: 进入每个 parse_xxx 时 pre_token 是该语法结构的第一个 Token,
+// 返回时 pre_token 是该语法结构的最后一个 Token, peek_token 是其后的 Token.
 pub struct Parser {
     lexer: lexer::Lexer,
     pre_token: token::Token,
+    pre_span: Span,
     peek_token: token::Token,
+    peek_span: Span,
+    // 当前语句中第一个绑定参数, 用于检查是否混用了不同形式的参数
+    parameter: Option<Parameter>,
 }
 
 impl Parser {
@@ -46,146 +61,208 @@ impl Parser {
         let mut p = Parser {
             lexer: Lexer::new_lexer(sql_str),
             pre_token: token::Token::Eof,
+            pre_span: Span::default(),
             peek_token: token::Token::Eof,
+            peek_span: Span::default(),
+            parameter: None,
         };
-        p.peek_token = p.lexer.next_token();
-        p.pre_token = p.peek_token.clone();
-        p.peek_token = p.lexer.next_token();
+        p.next_token();
+        p.next_token();
 
         p
     }
 
     pub fn update(&mut self, sql_str: &str) -> &mut Self {
         self.lexer.update(sql_str.to_owned());
-        self.peek_token = self.lexer.next_token();
-        self.pre_token = self.peek_token.clone();
-        self.peek_token = self.lexer.next_token();
+        self.next_token();
+        self.next_token();
 
         self
     }
 
-    pub fn parse_stmt(&mut self) -> Result<Statement> {
-        // 直接与 lexer 产生的第一个 Token 作比较
-        if self.pre_token == Token::Eof {
-            return Err(Error::Parse(fmt_err!("empty token {}", self.pre_token)));
+    // 解析由 ; 分隔的多条语句, 空语句和结尾的 ; 会被忽略
+    // 每条语句带有它在 SQL 中的位置, 不包括结尾的 ;
+    // 出错时 ParseError::statement_index 是出错语句的下标
+    pub fn parse_statements(&mut self) -> Result<Vec<Spanned<Statement>>> {
+        let mut stmts = Vec::new();
+
+        loop {
+            while self.pre_token == Token::Semicolon {
+                self.next_token();
+            }
+            if self.pre_token == Token::Eof {
+                break;
+            }
+
+            let stmt = self.parse_spanned_stmt().map_err(|e| match e {
+                Error::Parse(e) => Error::Parse(ParseError {
+                    statement_index: Some(stmts.len()),
+                    ..e
+                }),
+                e => e,
+            })?;
+            stmts.push(stmt);
+
+            // 跳过语句的最后一个 Token, 此时 pre_token 是 ; 或者 Eof
+            self.next_token();
         }
 
-        match &self.pre_token {
+        Ok(stmts)
+    }
+
+    pub fn parse_stmt(&mut self) -> Result<Statement> {
+        self.parameter = None;
+
+        // 直接与 lexer 产生的第一个 Token 作比较
+        let stmt = match &self.pre_token {
             Token::KeyWord(Keyword::Begin)
+            | Token::KeyWord(Keyword::Start)
             | Token::KeyWord(Keyword::Commit)
-            | Token::KeyWord(Keyword::Rollback) => self.parse_transaction_stmt(),
+            | Token::KeyWord(Keyword::Rollback)
+            | Token::KeyWord(Keyword::Savepoint)
+            | Token::KeyWord(Keyword::Release) => self.parse_transaction_stmt(),
 
             Token::KeyWord(Keyword::Create) => self.parse_create_stmt(),
             Token::KeyWord(Keyword::Drop) => self.parse_drop_stmt(),
+            Token::KeyWord(Keyword::Refresh) => self.parse_refresh_stmt(),
+            Token::KeyWord(Keyword::Truncate) => self.parse_truncate_stmt(),
+            Token::KeyWord(Keyword::Use) => Ok(Statement::Use(self.next_ident()?)),
 
             Token::KeyWord(Keyword::Delete) => self.parse_delete_stmt(),
             Token::KeyWord(Keyword::Insert) => self.parse_insert_stmt(),
-            Token::KeyWord(Keyword::Select) => self.parse_select_stmt(),
+            Token::KeyWord(Keyword::Select) | Token::KeyWord(Keyword::With) | Token::LeftParen => {
+                self.parse_query_stmt()
+            }
             Token::KeyWord(Keyword::Update) => self.parse_update_stmt(),
             Token::KeyWord(Keyword::Alter) => self.parse_alter_stmt(),
 
             Token::KeyWord(Keyword::Show) => self.parse_show_stmt(),
 
             Token::KeyWord(Keyword::Explain) => self.parse_explain_stmt(),
-            Token::KeyWord(Keyword::Describe) => match &self.peek_token {
-                Token::Ident(i) => Ok(Statement::DescribeTable(i.to_owned())),
-                _ => Err(Error::Parse(fmt_err!(
-                    "unexpected token: {}",
-                    &self.peek_token
-                ))),
-            },
+            Token::KeyWord(Keyword::Describe) => Ok(Statement::DescribeTable(self.next_ident()?)),
             Token::KeyWord(Keyword::Set) => self.parse_set_stmt(),
 
-            t => Err(Error::Parse(fmt_err!("unexpected token: {}", t))),
+            Token::KeyWord(Keyword::Prepare) => self.parse_prepare_stmt(),
+            Token::KeyWord(Keyword::Execute) => self.parse_execute_stmt(),
+            Token::KeyWord(Keyword::Deallocate) => self.parse_deallocate_stmt(),
+
+            _ => Err(self.unexpected("statement")),
+        }?;
+
+        // 一条完整的语句之后只能是 ; 或者结束
+        match self.peek_token {
+            Token::Semicolon | Token::Eof => Ok(stmt),
+            _ => Err(self.unexpected_peek("end of statement")),
         }
     }
 
-    fn parse_set_stmt(&mut self) -> Result<Statement> {
-        let mut is_session = true;
-        match &self.peek_token {
-            Token::KeyWord(Keyword::Session) => {
-                if !is_session {
-                    return Err(Error::Parse(fmt_err!("SET SESSION GLOBAL is not valid!")));
-                }
-                self.next_token();
-                match self.peek_token.clone() {
-                    Token::KeyWord(Keyword::Transaction) => Ok(Statement::Set(SetStmt {
-                        set_value: self.parse_set_transaction()?,
+    // 与 parse_stmt 相同, 但同时返回语句在 SQL 中的位置
+    pub fn parse_spanned_stmt(&mut self) -> Result<Spanned<Statement>> {
+        let start = self.pre_span;
+        let node = self.parse_stmt()?;
 
-                        is_session,
-                    })),
-                    t => Err(Error::Parse(fmt_err!("unexpected token: {}", t))),
-                }
-            }
-            Token::KeyWord(Keyword::Global) => {
-                is_session = false;
+        Ok(Spanned {
+            node,
+            span: start.to(self.pre_span),
+        })
+    }
 
-                self.next_token();
-                match self.peek_token.clone() {
-                    Token::KeyWord(Keyword::Transaction) => Ok(Statement::Set(SetStmt {
-                        set_value: self.parse_set_transaction()?,
-                        is_session,
-                    })),
-                    t => Err(Error::Parse(fmt_err!("unexpected token: {}", t))),
-                }
-            }
+    fn parse_set_stmt(&mut self) -> Result<Statement> {
+        // SET [SESSION | GLOBAL] TRANSACTION ISOLATION LEVEL level;
+        let is_session = self.parse_scope();
+
+        match self.peek_token {
             Token::KeyWord(Keyword::Transaction) => Ok(Statement::Set(SetStmt {
                 set_value: self.parse_set_transaction()?,
                 is_session,
             })),
-            t => {
-                // TODO: 需要支持 SET @var_name = expression;
-                Err(Error::Parse(fmt_err!("unexpected token: {}", t)))
-            }
+            // TODO: 需要支持 SET @var_name = expression;
+            _ => Err(self.unexpected_peek(Keyword::Transaction)),
         }
     }
 
     fn parse_set_transaction(&mut self) -> Result<SetVariableType> {
-        self.next_token();
+        self.next_expected_keyword(Keyword::Transaction)?;
         self.next_expected_keyword(Keyword::Isolation)?;
         self.next_expected_keyword(Keyword::Level)?;
 
-        match &self.peek_token {
-            Token::KeyWord(Keyword::Read) => {
-                self.next_token();
-                match self.peek_token.clone() {
-                    Token::KeyWord(Keyword::Committed) => Ok(SetVariableType::Transaction(
-                        TransactionIsolationLevel::ReadCommitted,
-                    )),
-
-                    Token::KeyWord(Keyword::Uncommitted) => Ok(SetVariableType::Transaction(
-                        TransactionIsolationLevel::ReadUncommitted,
-                    )),
+        Ok(SetVariableType::Transaction(self.parse_isolation_level()?))
+    }
 
-                    t => Err(Error::Parse(fmt_err!("unexpected token: {}", t))),
+    fn parse_isolation_level(&mut self) -> Result<TransactionIsolationLevel> {
+        match self.next_token() {
+            Token::KeyWord(Keyword::Read) => match self.next_token() {
+                Token::KeyWord(Keyword::Committed) => Ok(TransactionIsolationLevel::ReadCommitted),
+                Token::KeyWord(Keyword::Uncommitted) => {
+                    Ok(TransactionIsolationLevel::ReadUncommitted)
                 }
-            }
+                _ => Err(self.unexpected("COMMITTED or UNCOMMITTED")),
+            },
             Token::KeyWord(Keyword::Repeatable) => {
-                self.next_token();
-                match self.peek_token.clone() {
-                    Token::KeyWord(Keyword::Read) => Ok(SetVariableType::Transaction(
-                        TransactionIsolationLevel::RepeatableRead,
-                    )),
-
-                    t => Err(Error::Parse(fmt_err!("unexpected token: {}", t))),
-                }
+                self.next_expected_keyword(Keyword::Read)?;
+                Ok(TransactionIsolationLevel::RepeatableRead)
             }
-            Token::KeyWord(Keyword::Serializable) => Ok(SetVariableType::Transaction(
-                TransactionIsolationLevel::Serializable,
-            )),
-            t => Err(Error::Parse(fmt_err!("unexpected token: {}", t))),
+            Token::KeyWord(Keyword::Serializable) => Ok(TransactionIsolationLevel::Serializable),
+            _ => Err(self.unexpected("isolation level")),
         }
     }
 
     fn parse_show_stmt(&mut self) -> Result<Statement> {
-        match &self.peek_token {
+        // SHOW TABLES | DATABASES
+        // SHOW COLUMNS FROM table_name | SHOW INDEXES FROM table_name
+        // SHOW CREATE TABLE table_name
+        // SHOW [SESSION | GLOBAL] VARIABLES [LIKE 'pattern']
+        if let Token::KeyWord(Keyword::Session | Keyword::Global | Keyword::Variables) =
+            self.peek_token
+        {
+            return self.parse_show_variables();
+        }
+
+        match self.next_token() {
             Token::KeyWord(Keyword::Tables) => Ok(Statement::ShowTables),
             Token::KeyWord(Keyword::Databases) => Ok(Statement::ShowDatabase),
-            _ => Err(Error::Parse(fmt_err!(
-                "unexpected token: {}",
-                &self.peek_token
-            ))),
+            Token::KeyWord(Keyword::Columns) => {
+                self.next_expected_keyword(Keyword::From)?;
+                Ok(Statement::ShowColumns(self.next_ident()?))
+            }
+            Token::KeyWord(Keyword::Indexes) | Token::KeyWord(Keyword::Index) => {
+                self.next_expected_keyword(Keyword::From)?;
+                Ok(Statement::ShowIndexes(self.next_ident()?))
+            }
+            Token::KeyWord(Keyword::Create) => {
+                self.next_expected_keyword(Keyword::Table)?;
+                Ok(Statement::ShowCreateTable(self.next_ident()?))
+            }
+            _ => Err(self.unexpected("TABLES, DATABASES, COLUMNS, INDEXES, CREATE or VARIABLES")),
+        }
+    }
+
+    fn parse_show_variables(&mut self) -> Result<Statement> {
+        let is_session = self.parse_scope();
+        self.next_expected_keyword(Keyword::Variables)?;
+        let like = if self.next_if_keyword(Keyword::Like) {
+            match self.next_token() {
+                Token::String(pattern) => Some(pattern.clone()),
+                _ => return Err(self.unexpected("String")),
+            }
+        } else {
+            None
+        };
+
+        Ok(Statement::ShowVariables(ShowVariablesStmt {
+            is_session,
+            like,
+        }))
+    }
+
+    // [SESSION | GLOBAL], 默认是 SESSION
+    fn parse_scope(&mut self) -> bool {
+        if self.next_if_keyword(Keyword::Global) {
+            return false;
         }
+
+        self.next_if_keyword(Keyword::Session);
+        true
     }
 
     fn parse_alter_stmt(&mut self) -> Result<Statement> {
@@ -193,456 +270,602 @@ impl Parser {
         self.next_expected_keyword(Keyword::Table)?;
 
         let table_name = self.next_ident()?;
-        self.next_token();
 
-        match &self.pre_token {
+        let alter_type = match self.next_token() {
             Token::KeyWord(Keyword::Add) => match &self.peek_token {
                 Token::KeyWord(Keyword::Column) => {
                     // ALTER TABLE table_name ADD COLUMN new_column_name column_data_type
                     self.next_token();
-                    dbg!(&self.pre_token);
-                    let new_column = self.parse_column()?;
-
-                    Ok(Statement::Alter(AlterStmt {
-                        alter_type: AlterType::AddColumn(new_column),
-                        table_name,
-                    }))
+                    AlterType::AddColumn(self.parse_column()?)
                 }
-                Token::Ident(_) => {
+                _ if self.peek_is_ident() => {
                     // ALTER TABLE table_name ADD new_column_name column_data_type;
-                    self.next_token();
-                    let new_column = self.parse_column()?;
-
-                    Ok(Statement::Alter(AlterStmt {
-                        alter_type: AlterType::AddColumn(new_column),
-                        table_name,
-                    }))
+                    AlterType::AddColumn(self.parse_column()?)
                 }
-
                 Token::KeyWord(Keyword::Index) => {
                     // ALTER TABLE table_name ADD INDEX index_name(option) (column_1_name, xxx);
                     self.next_token();
 
-                    let add_index_name = match self.peek_token.clone() {
-                        Token::Ident(i) => {
-                            self.next_token();
-                            Some(i)
-                        }
-                        _ => None,
+                    let add_index_name = if self.peek_is_ident() {
+                        Some(self.next_ident()?)
+                    } else {
+                        None
                     };
-                    let mut column_list = vec![];
-                    match self.peek_token.clone() {
-                        Token::LeftParen => {
-                            self.next_token();
-                            loop {
-                                match self.peek_token.clone() {
-                                    Token::Ident(i) => {
-                                        self.next_token();
-                                        column_list.push(i);
-                                    }
-                                    Token::Comma => {
-                                        self.next_token();
-                                        continue;
-                                    }
-                                    Token::RightParen => {
-                                        self.next_token();
-                                        break;
-                                    }
-                                    _ => {
-                                        return Err(Error::Parse(fmt_err!(
-                                            "expected Token::LeftParen, but get: {}",
-                                            &self.peek_token
-                                        )));
-                                    }
-                                }
-                            }
-
-                            Ok(Statement::Alter(AlterStmt {
-                                alter_type: AlterType::AddIndex(add_index_name, column_list),
-                                table_name,
-                            }))
-                        }
-                        _ => Err(Error::Parse(fmt_err!(
-                            "expected Token::LeftParen, but get: {}",
-                            &self.peek_token
-                        ))),
-                    }
+
+                    AlterType::AddIndex(add_index_name, self.parse_ident_list()?)
                 }
-                _ => Err(Error::Parse(fmt_err!(
-                    "unexpected token: {}",
-                    &self.peek_token
-                ))),
+                _ => return Err(self.unexpected_peek("COLUMN, INDEX or column name")),
             },
-            Token::KeyWord(Keyword::Drop) => {
-                match &self.peek_token {
-                    Token::KeyWord(Keyword::Column) => {
-                        // ALTER TABLE table_name
-                        // DROP column_name;
-                        self.next_token();
-                        let column_name = self.next_ident()?;
-                        Ok(Statement::Alter(AlterStmt {
-                            alter_type: AlterType::DropColumn(column_name),
-                            table_name,
-                        }))
-                    }
-                    Token::KeyWord(Keyword::Index) => {
-                        // ALTER TABLE table_name
-                        // DROP INDEX index_name;
-                        self.next_token();
-                        let index_name = self.next_ident()?;
-
-                        Ok(Statement::Alter(AlterStmt {
-                            alter_type: AlterType::RemoveIndex(index_name),
-                            table_name,
-                        }))
-                    }
-                    _ => Err(Error::Parse(fmt_err!(
-                        "unexpected token: {}",
-                        &self.peek_token
-                    ))),
+            Token::KeyWord(Keyword::Drop) => match &self.peek_token {
+                Token::KeyWord(Keyword::Column) => {
+                    // ALTER TABLE table_name
+                    // DROP COLUMN column_name;
+                    self.next_token();
+                    AlterType::DropColumn(self.next_ident()?)
                 }
-            }
-            Token::KeyWord(Keyword::Rename) => {
-                match &self.peek_token {
-                    Token::KeyWord(Keyword::Column) => {
-                        // ALTER TABLE table_name RENAME COLUMN
-                        // old_column_name TO new_column_name;
-                        self.next_token();
-                        let old_column_name = self.next_ident()?;
-                        self.next_expected_keyword(Keyword::To)?;
-                        let new_column_name = self.next_ident()?;
-
-                        Ok(Statement::Alter(AlterStmt {
-                            alter_type: AlterType::RenameColumn(old_column_name, new_column_name),
-                            table_name,
-                        }))
-                    }
-                    Token::KeyWord(Keyword::To) => {
-                        // ALTER TABLE table_name RENAME TO new_table_name;
-                        self.next_token();
-                        let new_table_name = self.next_ident()?;
-
-                        Ok(Statement::Alter(AlterStmt {
-                            alter_type: AlterType::RenameTable(new_table_name),
-                            table_name,
-                        }))
-                    }
-                    _ => Err(Error::Parse(fmt_err!(
-                        "unexpected token: {}",
-                        &self.peek_token
-                    ))),
+                Token::KeyWord(Keyword::Index) => {
+                    // ALTER TABLE table_name
+                    // DROP INDEX index_name;
+                    self.next_token();
+                    AlterType::RemoveIndex(self.next_ident()?)
                 }
-            }
+                _ => return Err(self.unexpected_peek("COLUMN or INDEX")),
+            },
+            Token::KeyWord(Keyword::Rename) => match &self.peek_token {
+                Token::KeyWord(Keyword::Column) => {
+                    // ALTER TABLE table_name RENAME COLUMN
+                    // old_column_name TO new_column_name;
+                    self.next_token();
+                    let old_column_name = self.next_ident()?;
+                    self.next_expected_keyword(Keyword::To)?;
+                    let new_column_name = self.next_ident()?;
+
+                    AlterType::RenameColumn(old_column_name, new_column_name)
+                }
+                Token::KeyWord(Keyword::To) => {
+                    // ALTER TABLE table_name RENAME TO new_table_name;
+                    self.next_token();
+                    AlterType::RenameTable(self.next_ident()?)
+                }
+                _ => return Err(self.unexpected_peek("COLUMN or TO")),
+            },
             Token::KeyWord(Keyword::Modify) => {
                 // 修改列的属性
-                // ALTER TABLE table_name MODIFY
+                // ALTER TABLE table_name MODIFY [COLUMN]
                 // column_name column_data_type;
-                self.next_token();
-                Ok(Statement::Alter(AlterStmt {
-                    alter_type: AlterType::ModifyColumn(self.parse_column()?),
-                    table_name,
-                }))
+                self.next_if_keyword(Keyword::Column);
+                AlterType::ModifyColumn(self.parse_column()?)
             }
-            _ => Err(Error::Parse(fmt_err!(
-                "ALTER TABLE is not valid unexpected token: {}",
-                &self.pre_token
-            ))),
-        }
+            _ => return Err(self.unexpected("ADD, DROP, RENAME or MODIFY")),
+        };
+
+        Ok(Statement::Alter(AlterStmt {
+            alter_type,
+            table_name,
+        }))
     }
 
     fn parse_transaction_stmt(&mut self) -> Result<Statement> {
         match &self.pre_token {
-            // BEGIN TRANSACTION READ ONLY / WRITE;
-            // BEGIN TRANSACTION READ ONLY AS OF SYSTEM TIME TimeStamp(u64);
-            Token::KeyWord(Keyword::Begin) => {
-                let mut is_readonly = false;
+            // BEGIN [TRANSACTION] [mode [, ...]];
+            // mode: ISOLATION LEVEL level | READ ONLY | READ WRITE
+            //     | AS OF SYSTEM TIME TimeStamp(u64) / 'YYYY-MM-DD HH:MM:SS'
+            // START TRANSACTION 与 BEGIN TRANSACTION 相同
+            Token::KeyWord(Keyword::Begin) | Token::KeyWord(Keyword::Start) => {
+                let mut read_only = None;
+                let mut isolation_level = None;
                 let mut version = None;
 
-                self.next_if_keyword(Keyword::Transaction);
-
-                if self.next_if_token(Token::KeyWord(Keyword::Read)) {
-                    match self.next_token() {
-                        Token::KeyWord(Keyword::Only) => is_readonly = true,
-                        Token::KeyWord(Keyword::Write) => is_readonly = false,
-
-                        t => return Err(Error::Parse(fmt_err!("unexpected token: {}", t))),
-                    }
+                if self.pre_token == Token::KeyWord(Keyword::Start) {
+                    self.next_expected_keyword(Keyword::Transaction)?;
+                } else {
+                    self.next_if_keyword(Keyword::Transaction);
                 }
 
-                if self.next_if_keyword(Keyword::As) {
-                    self.next_expected_keyword(Keyword::Of)?;
-                    self.next_expected_keyword(Keyword::System)?;
-                    self.next_expected_keyword(Keyword::Time)?;
-
-                    match self.next_token() {
-                        Token::Number(n) => version = n.parse::<u64>().ok(),
-                        t => {
-                            return Err(Error::Parse(fmt_err!(
-                                "unexpected token: {} expected: Number",
-                                t
-                            )));
+                // 事务模式之间可以用逗号分隔, 顺序任意, 每种模式只能出现一次
+                loop {
+                    if self.next_if_keyword(Keyword::Read) {
+                        let mode = match self.next_token() {
+                            Token::KeyWord(Keyword::Only) => true,
+                            Token::KeyWord(Keyword::Write) => false,
+
+                            _ => return Err(self.unexpected("ONLY or WRITE")),
+                        };
+                        if read_only.replace(mode).is_some() {
+                            return Err(self.error(
+                                "conflicting or repeated READ ONLY / READ WRITE".to_owned(),
+                            ));
                         }
+                    } else if self.next_if_keyword(Keyword::Isolation) {
+                        self.next_expected_keyword(Keyword::Level)?;
+                        let level = self.parse_isolation_level()?;
+                        if isolation_level.replace(level).is_some() {
+                            return Err(
+                                self.error("ISOLATION LEVEL specified more than once".to_owned())
+                            );
+                        }
+                    } else if self.next_if_keyword(Keyword::As) {
+                        let system_time = self.parse_system_time()?;
+                        if version.replace(system_time).is_some() {
+                            return Err(
+                                self.error("AS OF SYSTEM TIME specified more than once".to_owned())
+                            );
+                        }
+                    } else {
+                        break;
                     }
+
+                    self.next_if_token(Token::Comma);
                 }
 
                 Ok(Statement::Begin(stmt::BeginStmt {
-                    is_readonly,
+                    is_readonly: read_only.unwrap_or(false),
+                    isolation_level,
                     version,
                 }))
             }
-            Token::KeyWord(Keyword::Commit) => {
-                self.next_token();
-                Ok(Statement::Commit)
-            }
+            Token::KeyWord(Keyword::Commit) => Ok(Statement::Commit),
+            // ROLLBACK TO [SAVEPOINT] name
             Token::KeyWord(Keyword::Rollback) => {
-                self.next_token();
-                Ok(Statement::Rollback)
+                if !self.next_if_keyword(Keyword::To) {
+                    return Ok(Statement::Rollback);
+                }
+
+                self.next_if_keyword(Keyword::Savepoint);
+                Ok(Statement::RollbackTo(self.next_ident()?))
             }
+            Token::KeyWord(Keyword::Savepoint) => Ok(Statement::Savepoint(self.next_ident()?)),
+            // RELEASE [SAVEPOINT] name
+            Token::KeyWord(Keyword::Release) => {
+                self.next_if_keyword(Keyword::Savepoint);
+                Ok(Statement::ReleaseSavepoint(self.next_ident()?))
+            }
+
+            _ => Err(self.unexpected("BEGIN, START, COMMIT, ROLLBACK, SAVEPOINT or RELEASE")),
+        }
+    }
 
-            t => Err(Error::Parse(fmt_err!("unexpected token: {}", t))),
+    // AS OF SYSTEM TIME 之后的版本号或者时间戳, 进入时 pre_token 是 AS
+    // 时间戳只检查格式, 保留原来的字符串
+    fn parse_system_time(&mut self) -> Result<SystemTime> {
+        self.next_expected_keyword(Keyword::Of)?;
+        self.next_expected_keyword(Keyword::System)?;
+        self.next_expected_keyword(Keyword::Time)?;
+
+        match self.next_token().clone() {
+            Token::Integer(n) => {
+                Ok(SystemTime::Version(n.parse::<u64>().map_err(|e| {
+                    self.error(format!("invalid system time {}: {}", n, e))
+                })?))
+            }
+            Token::String(timestamp) => {
+                let valid = chrono::DateTime::parse_from_rfc3339(&timestamp).is_ok()
+                    || chrono::NaiveDateTime::parse_from_str(&timestamp, "%Y-%m-%d %H:%M:%S%.f")
+                        .is_ok()
+                    || chrono::NaiveDate::parse_from_str(&timestamp, "%Y-%m-%d").is_ok();
+                if !valid {
+                    return Err(self.error(format!("invalid system time '{}'", timestamp)));
+                }
+                Ok(SystemTime::Timestamp(timestamp))
+            }
+            _ => Err(self.unexpected("Integer or String")),
         }
     }
 
     fn parse_delete_stmt(&mut self) -> Result<Statement> {
+        // DELETE FROM table_name [USING t1, t2] [WHERE expr]
+        // [ORDER BY expr] [LIMIT n] [RETURNING expr_list];
         self.next_expected_keyword(Keyword::From)?;
         let table_name = self.next_ident()?;
-        self.next_token();
+        let using = if self.next_if_keyword(Keyword::Using) {
+            Some(self.parse_from_list()?)
+        } else {
+            None
+        };
+
         Ok(Statement::Delete(DeleteTableStmt {
             table_name,
+            using,
             r#where: self.parse_clause_where()?,
+            order: self.parse_clause_order()?,
+            limit: self.parse_clause_limit()?,
+            returning: self.parse_clause_returning()?,
         }))
     }
 
     fn parse_insert_stmt(&mut self) -> Result<Statement> {
+        // INSERT INTO table_name [(column_1, column_2)]
+        // {VALUES (expr_1, DEFAULT), (expr_3, expr_4) | SELECT ... | DEFAULT VALUES}
+        // [ON CONFLICT ...] [RETURNING expr_list];
         self.next_expected_keyword(Keyword::Into)?;
         let table_name = self.next_ident()?;
         let columns = if self.peek_token == Token::LeftParen {
-            // todo: 这里应该有一个 next_if_peek 的方法处理这个情况
-            self.next_token();
-            let mut cols = Vec::new();
-            loop {
-                cols.push(self.next_ident()?);
-                match self.next_token() {
-                    Token::Comma => continue,
-                    Token::RightParen => break,
-                    t => {
-                        return Err(Error::Parse(fmt_err!(
-                            "excepted Comma or RightParen, get {}",
-                            t
-                        )));
-                    }
-                }
-            }
-            Some(cols)
+            Some(self.parse_ident_list()?)
         } else {
             None
         };
 
-        self.next_expected_keyword(Keyword::Values)?;
+        let source = match self.next_token() {
+            Token::KeyWord(Keyword::Values) => {
+                let width = columns.as_ref().map(|columns| columns.len());
+                InsertSource::Values(self.parse_insert_values(width)?)
+            }
+            Token::KeyWord(Keyword::Select) | Token::KeyWord(Keyword::With) => {
+                InsertSource::Query(Box::new(self.parse_query()?))
+            }
+            Token::KeyWord(Keyword::Default) => {
+                self.next_expected_keyword(Keyword::Values)?;
+                InsertSource::DefaultValues
+            }
+            _ => return Err(self.unexpected("VALUES, SELECT or DEFAULT VALUES")),
+        };
+
+        Ok(Statement::Insert(stmt::InsertStmt {
+            table_name,
+            columns,
+            source,
+            on_conflict: self.parse_clause_on_conflict()?,
+            returning: self.parse_clause_returning()?,
+        }))
+    }
+
+    // VALUES 之后的 (expr_1, DEFAULT), (expr_3, expr_4)
+    // 每一行的值个数必须相同, 指定了列时还要与列数相同
+    fn parse_insert_values(
+        &mut self,
+        mut width: Option<usize>,
+    ) -> Result<Vec<Vec<Option<Expression>>>> {
         let mut values = Vec::new();
 
         loop {
-            if self.peek_token != Token::LeftParen {
-                return Err(Error::Parse(fmt_err!(
-                    "except token LeftParen, get {:?}",
-                    self.peek_token
-                )));
-            }
-
+            let start = self.peek_span;
+            self.next_expected_token(Token::LeftParen)?;
             let mut exprs = Vec::new();
-            self.next_token();
 
             loop {
-                self.next_token();
-                exprs.push(Some(self.parse_expression(Precedence::Lowest)?));
+                if self.next_if_keyword(Keyword::Default) {
+                    exprs.push(None);
+                } else {
+                    self.next_token();
+                    exprs.push(Some(self.parse_expression(Precedence::Lowest)?));
+                }
                 match self.next_token() {
                     Token::RightParen => break,
                     Token::Comma => {}
-                    _ => return Err(Error::Parse("".to_owned())),
+                    _ => return Err(self.unexpected("Comma or RightParen")),
+                }
+            }
+
+            match width {
+                Some(width) if width != exprs.len() => {
+                    return Err(ParseError::new(
+                        format!("VALUES row has {} values, expected {}", exprs.len(), width),
+                        start.to(self.pre_span),
+                    )
+                    .into());
                 }
+                _ => width = Some(exprs.len()),
             }
 
             values.push(exprs);
-            if self.peek_token != Token::Comma {
+            if !self.next_if_token(Token::Comma) {
                 break;
             }
         }
 
-        Ok(Statement::Insert(stmt::InsertStmt {
-            table_name,
-            columns,
-            values,
-        }))
+        Ok(values)
     }
 
-    fn parse_create_index_stmt(&mut self) -> Result<Statement> {
-        // CREATE [UNIQUE] INDEX [index_name]
-        // ON [table_name] (column_name_1, column_name_2);
-        let is_unique = match self.peek_token {
-            Token::KeyWord(Keyword::Unique) => {
-                self.next_token();
-                match self.peek_token.clone() {
-                    Token::KeyWord(Keyword::Index) => true,
-                    _ => {
-                        return Err(Error::Parse(fmt_err!(
-                            "unexpected token: {}",
-                            self.peek_token
-                        )));
-                    }
+    // ON CONFLICT [(columns)] DO NOTHING
+    // ON CONFLICT (columns) DO UPDATE SET column = expr, ... [WHERE expr]
+    fn parse_clause_on_conflict(&mut self) -> Result<Option<OnConflict>> {
+        if !self.next_if_keyword(Keyword::On) {
+            return Ok(None);
+        }
+        self.next_expected_keyword(Keyword::Conflict)?;
+        let columns = if self.peek_token == Token::LeftParen {
+            Some(self.parse_ident_list()?)
+        } else {
+            None
+        };
+
+        self.next_expected_keyword(Keyword::Do)?;
+        let action = match self.next_token() {
+            Token::KeyWord(Keyword::Nothing) => ConflictAction::Nothing,
+            Token::KeyWord(Keyword::Update) => {
+                // 不知道冲突的列时无法确定要更新哪一行
+                if columns.is_none() {
+                    return Err(
+                        self.error("ON CONFLICT DO UPDATE requires conflict columns".to_owned())
+                    );
+                }
+                self.next_expected_keyword(Keyword::Set)?;
+                ConflictAction::Update {
+                    set: self.parse_assignments()?,
+                    wheres: self.parse_clause_where()?,
                 }
             }
-            _ => false,
+            _ => return Err(self.unexpected("NOTHING or UPDATE")),
         };
+
+        Ok(Some(OnConflict { columns, action }))
+    }
+
+    // UPDATE 和 DELETE 中的 LIMIT n
+    fn parse_clause_limit(&mut self) -> Result<Option<Expression>> {
+        if !self.next_if_keyword(Keyword::Limit) {
+            return Ok(None);
+        }
+
         self.next_token();
-        let index_name = self.next_ident()?;
+        Ok(Some(self.parse_expression(Precedence::Lowest)?))
+    }
+
+    // RETURNING expr_list, 与 SELECT 的列表相同
+    fn parse_clause_returning(&mut self) -> Result<Option<Vec<SelectItem>>> {
+        if !self.next_if_keyword(Keyword::Returning) {
+            return Ok(None);
+        }
+
+        Ok(Some(self.parse_clause_select()?))
+    }
+
+    fn parse_create_index_stmt(&mut self) -> Result<Statement> {
+        // CREATE [UNIQUE] INDEX [IF NOT EXISTS] [index_name]
+        // ON [table_name] (column_name_1, expr_2, ...) [WHERE expr];
+        let is_unique = self.next_if_keyword(Keyword::Unique);
+        self.next_expected_keyword(Keyword::Index)?;
+        let if_not_exists = self.parse_if_not_exists()?;
+        let index_name = match self.peek_token {
+            _ if self.peek_is_ident() => Some(self.next_ident()?),
+            _ if if_not_exists => return Err(self.unexpected_peek("Ident")),
+            _ => None,
+        };
         self.next_expected_keyword(Keyword::On)?;
         let table_name = self.next_ident()?;
-        match self.peek_token {
-            Token::LeftParen => {}
-            _ => {
-                return Err(Error::Parse(fmt_err!(
-                    "unexpected token: {}",
-                    self.peek_token
-                )));
-            }
-        };
-        self.next_token();
+
+        self.next_expected_token(Token::LeftParen)?;
         let mut columns = Vec::new();
         loop {
-            columns.push(self.next_ident()?);
-            let token = self.next_token();
+            self.next_token();
+            columns.push(self.parse_expression(Precedence::Lowest)?);
 
-            match token {
+            match self.next_token() {
                 Token::Comma => continue,
                 Token::RightParen => break,
-                _ => {
-                    return Err(Error::Parse(fmt_err!(
-                        "unexpected token {:?}, want Comma or RightParen",
-                        token
-                    )));
-                }
+                _ => return Err(self.unexpected("Comma or RightParen")),
             }
         }
 
         Ok(Statement::CreateIndex(CreateIndexStmt {
             is_unique,
+            if_not_exists,
             index_name,
             table_name,
             columns,
+            wheres: self.parse_clause_where()?,
         }))
     }
 
+    // IF NOT EXISTS
+    fn parse_if_not_exists(&mut self) -> Result<bool> {
+        if !self.next_if_keyword(Keyword::If) {
+            return Ok(false);
+        }
+
+        self.next_expected_keyword(Keyword::Not)?;
+        self.next_expected_keyword(Keyword::Exists)?;
+        Ok(true)
+    }
+
+    // IF EXISTS
+    fn parse_if_exists(&mut self) -> Result<bool> {
+        if !self.next_if_keyword(Keyword::If) {
+            return Ok(false);
+        }
+
+        self.next_expected_keyword(Keyword::Exists)?;
+        Ok(true)
+    }
+
     fn parse_create_stmt(&mut self) -> Result<Statement> {
         match self.peek_token {
             Token::KeyWord(Keyword::Table) => self.parse_create_table_stmt(),
             Token::KeyWord(Keyword::Unique) | Token::KeyWord(Keyword::Index) => {
                 self.parse_create_index_stmt()
             }
-            _ => Err(Error::Parse(fmt_err!(
-                "unexpected token: {}",
-                self.peek_token
-            ))),
+            Token::KeyWord(Keyword::Or)
+            | Token::KeyWord(Keyword::Materialized)
+            | Token::KeyWord(Keyword::View) => self.parse_create_view_stmt(),
+            Token::KeyWord(Keyword::Database) => {
+                // CREATE DATABASE [IF NOT EXISTS] database_name
+                self.next_token();
+                let if_not_exists = self.parse_if_not_exists()?;
+                Ok(Statement::CreateDatabase(CreateDatabaseStmt {
+                    name: self.next_ident()?,
+                    if_not_exists,
+                }))
+            }
+            _ => Err(self.unexpected_peek("TABLE, VIEW, INDEX or DATABASE")),
         }
     }
 
     fn parse_create_table_stmt(&mut self) -> Result<Statement> {
         // CREATE TABLE table_name
         //  (xxx_name xxx_addr xxx_addr xxx_addr,
-        //  xxx, xxx);;
+        //  xxx, xxx,
+        //  [CONSTRAINT name] PRIMARY KEY (xxx, xxx), ...);
         self.next_expected_keyword(Keyword::Table)?;
-        let name = self.next_ident();
-        let table_name = match name {
-            Ok(n) => n,
-            Err(e) => {
-                return Err(e);
-            }
-        };
+        let if_not_exists = self.parse_if_not_exists()?;
+        let table_name = self.next_ident()?;
         self.next_expected_token(Token::LeftParen)?;
 
-        let mut columns = Vec::new();
+        let mut columns: Vec<Column> = Vec::new();
+        let mut constraints = Vec::new();
+        let mut constraint_names: Vec<String> = Vec::new();
+        let mut has_primary_key = false;
         loop {
-            columns.push(self.parse_column()?);
-            let token = self.next_token();
+            let start = self.peek_span;
+            let (primary_key, names) = match self.peek_token {
+                Token::KeyWord(
+                    Keyword::Constraint
+                    | Keyword::Primary
+                    | Keyword::Unique
+                    | Keyword::Check
+                    | Keyword::Foreign,
+                ) => {
+                    let constraint = self.parse_table_constraint()?;
+                    let primary_key = matches!(constraint.kind, ConstraintKind::PrimaryKey(_));
+                    let names = constraint.name.iter().cloned().collect::<Vec<_>>();
+                    constraints.push(constraint);
+                    (primary_key, names)
+                }
+                _ => {
+                    let column = self.parse_column()?;
+                    if columns.iter().any(|c| c.name == column.name) {
+                        return Err(ParseError::new(
+                            format!("column {} specified more than once", column.name),
+                            start,
+                        )
+                        .into());
+                    }
+                    let primary_key = column.primary_key;
+                    let names = column
+                        .checks
+                        .iter()
+                        .filter_map(|(name, _)| name.clone())
+                        .collect::<Vec<_>>();
+                    columns.push(column);
+                    (primary_key, names)
+                }
+            };
+            for name in names {
+                if constraint_names.contains(&name) {
+                    return Err(ParseError::new(
+                        format!("constraint {} specified more than once", name),
+                        start.to(self.pre_span),
+                    )
+                    .into());
+                }
+                constraint_names.push(name);
+            }
+            if primary_key && has_primary_key {
+                return Err(self.error(format!(
+                    "multiple primary keys for table {} are not allowed",
+                    table_name
+                )));
+            }
+            has_primary_key |= primary_key;
 
-            match token {
+            match self.next_token() {
                 Token::Comma => continue,
                 Token::RightParen => break,
-                _ => {
-                    return Err(Error::Parse(fmt_err!(
-                        "unexpected token {:?}, want Comma or RightParen",
-                        token
-                    )));
-                }
+                _ => return Err(self.unexpected("Comma or RightParen")),
             }
         }
 
-        self.next_expected_token(Token::Semicolon)?;
-
         Ok(Statement::CreateTable(stmt::CreateTableStmt {
             columns,
+            constraints,
             table_name,
+            if_not_exists,
         }))
     }
 
-    fn parse_column(&mut self) -> Result<Column> {
-        let column_name = self.next_ident()?;
+    fn parse_table_constraint(&mut self) -> Result<TableConstraint> {
+        let name = if self.next_if_keyword(Keyword::Constraint) {
+            Some(self.next_ident()?)
+        } else {
+            None
+        };
 
-        let mut column = column::Column {
-            name: column_name,
-            data_type: match self.next_token() {
-                Token::KeyWord(Keyword::Bool) => DataType::Bool,
-                Token::KeyWord(Keyword::Boolean) => DataType::Bool,
-
-                Token::KeyWord(Keyword::Float) => DataType::Float32,
-                Token::KeyWord(Keyword::Double) => DataType::Float64,
-
-                Token::KeyWord(Keyword::Int) => DataType::Int32,
-                Token::KeyWord(Keyword::Integer) => DataType::Int32,
-                Token::KeyWord(Keyword::Int8) => DataType::Int8,
-                Token::KeyWord(Keyword::Int16) => DataType::Int16,
-                Token::KeyWord(Keyword::Int32) => DataType::Int32,
-                Token::KeyWord(Keyword::Int64) => DataType::Int64,
-                Token::KeyWord(Keyword::Uint8) => DataType::Uint8,
-                Token::KeyWord(Keyword::Uint16) => DataType::Uint16,
-                Token::KeyWord(Keyword::Uint32) => DataType::Uint32,
-                Token::KeyWord(Keyword::Uint64) => DataType::Uint64,
-                Token::KeyWord(Keyword::Float32) => DataType::Float32,
-                Token::KeyWord(Keyword::Float64) => DataType::Float64,
-
-                Token::KeyWord(Keyword::Text) => DataType::String,
-                Token::KeyWord(Keyword::VarChar) => {
-                    self.next_expected_token(Token::LeftParen)?;
-                    let token = self.next_token();
-                    let len = match token {
-                        Token::Number(n) => match n.parse::<usize>() {
-                            Ok(l) => l,
-                            Err(e) => {
-                                return Err(Error::Parse(fmt_err!("parse err: {}", e)));
-                            }
-                        },
-                        t => {
-                            return Err(Error::Parse(fmt_err!(
-                                "unexpected token: {} expected: Number",
-                                t
-                            )));
-                        }
-                    };
+        let kind = match self.next_token() {
+            Token::KeyWord(Keyword::Primary) => {
+                self.next_expected_keyword(Keyword::Key)?;
+                ConstraintKind::PrimaryKey(self.parse_ident_list()?)
+            }
+            Token::KeyWord(Keyword::Unique) => ConstraintKind::Unique(self.parse_ident_list()?),
+            Token::KeyWord(Keyword::Check) => ConstraintKind::Check(self.parse_check()?),
+            Token::KeyWord(Keyword::Foreign) => {
+                self.next_expected_keyword(Keyword::Key)?;
+                let columns = self.parse_ident_list()?;
+                self.next_expected_keyword(Keyword::References)?;
+                let references = self.parse_references(columns.len())?;
+                ConstraintKind::ForeignKey {
+                    columns,
+                    references,
+                }
+            }
+            _ => return Err(self.unexpected("table constraint")),
+        };
+
+        Ok(TableConstraint { name, kind })
+    }
+
+    // CHECK (expr), 进入时 pre_token 是 CHECK
+    fn parse_check(&mut self) -> Result<Expression> {
+        self.next_expected_token(Token::LeftParen)?;
+        self.next_token();
+        let expr = self.parse_expression(Precedence::Lowest)?;
+        self.next_expected_token(Token::RightParen)?;
+        Ok(expr)
+    }
 
-                    self.next_expected_token(Token::RightParen)?;
-                    DataType::Varchar(len)
+    // REFERENCES table [(column, ...)] [ON DELETE CASCADE | SET NULL | RESTRICT]
+    // 进入时 pre_token 是 REFERENCES, columns 是引用方的列数
+    fn parse_references(&mut self, columns: usize) -> Result<ForeignKey> {
+        let table = self.next_ident()?;
+        let referenced = match self.peek_token {
+            Token::LeftParen => {
+                let referenced = self.parse_ident_list()?;
+                if referenced.len() != columns {
+                    return Err(self.error(format!(
+                        "foreign key references {} columns, expected {}",
+                        referenced.len(),
+                        columns
+                    )));
                 }
-                Token::KeyWord(Keyword::Char) => DataType::Char,
-                Token::KeyWord(Keyword::String) => DataType::String,
+                Some(referenced)
+            }
+            _ => None,
+        };
 
-                t => {
-                    return Err(Error::Parse(fmt_err!("unexpected token: {}", t)));
+        let on_delete = if self.next_if_keyword(Keyword::On) {
+            self.next_expected_keyword(Keyword::Delete)?;
+            Some(match self.next_token() {
+                Token::KeyWord(Keyword::Cascade) => ReferentialAction::Cascade,
+                Token::KeyWord(Keyword::Restrict) => ReferentialAction::Restrict,
+                Token::KeyWord(Keyword::Set) => {
+                    self.next_expected_keyword(Keyword::Null)?;
+                    ReferentialAction::SetNull
                 }
-            },
+                _ => return Err(self.unexpected("CASCADE, SET NULL or RESTRICT")),
+            })
+        } else {
+            None
+        };
+
+        Ok(ForeignKey {
+            table,
+            columns: referenced,
+            on_delete,
+        })
+    }
+
+    fn parse_column(&mut self) -> Result<Column> {
+        let column_name = self.next_ident()?;
+        self.next_token();
+        let data_type = self.parse_data_type()?;
+
+        let mut column = column::Column {
+            name: column_name,
+            data_type,
             primary_key: false,
             nullable: None,
             default: None,
             unique: false,
             index: false,
+            checks: Vec::new(),
             references: None,
         };
 
@@ -656,7 +879,7 @@ impl Parser {
                 Keyword::Null => {
                     self.next_token();
                     if let Some(false) = column.nullable {
-                        return Err(Error::Parse(fmt_err!(
+                        return Err(self.error(format!(
                             "Column {} can't be both not nullable and nullable",
                             column.name
                         )));
@@ -669,9 +892,11 @@ impl Parser {
                     column.nullable = Some(false);
                 }
                 Keyword::Default => {
+                    // DEFAULT 1 NOT NULL 中的 NOT 是列约束, 所以默认值中不能直接使用
+                    // 比较运算符, IN, IS 等, 需要加括号
                     self.next_token();
                     self.next_token();
-                    column.default = Some(self.parse_expression(Precedence::Lowest)?)
+                    column.default = Some(self.parse_expression(Precedence::Equals)?)
                 }
                 Keyword::Unique => {
                     self.next_token();
@@ -681,12 +906,23 @@ impl Parser {
                     self.next_token();
                     column.index = true
                 }
+                Keyword::Check => {
+                    self.next_token();
+                    column.checks.push((None, self.parse_check()?))
+                }
+                // 列上的 CONSTRAINT name 只能用于 CHECK
+                Keyword::Constraint => {
+                    self.next_token();
+                    let name = self.next_ident()?;
+                    self.next_expected_keyword(Keyword::Check)?;
+                    column.checks.push((Some(name), self.parse_check()?))
+                }
                 Keyword::References => {
                     self.next_token();
-                    column.references = Some(self.next_ident()?)
+                    column.references = Some(self.parse_references(1)?)
                 }
-                keyword => {
-                    return Err(Error::Parse(fmt_err!("unexpected keyword: {}", keyword)));
+                _ => {
+                    return Err(self.unexpected_peek("column constraint"));
                 }
             }
         }
@@ -694,432 +930,638 @@ impl Parser {
         Ok(column)
     }
 
-    fn parse_drop_stmt(&mut self) -> Result<Statement> {
-        self.next_expected_keyword(Keyword::Table)?;
-        Ok(Statement::DropTable(DropTableStmt {
-            table_name: self.next_ident()?,
-        }))
+    // 数据类型, 进入时 pre_token 是类型名
+    // INT, VARCHAR(255), BOOLEAN ...
+    fn parse_data_type(&mut self) -> Result<DataType> {
+        Ok(match self.pre_token {
+            Token::KeyWord(Keyword::Bool) => DataType::Bool,
+            Token::KeyWord(Keyword::Boolean) => DataType::Bool,
+
+            Token::KeyWord(Keyword::Float) => DataType::Float32,
+            Token::KeyWord(Keyword::Double) => DataType::Float64,
+
+            Token::KeyWord(Keyword::Int) => DataType::Int32,
+            Token::KeyWord(Keyword::Integer) => DataType::Int32,
+            Token::KeyWord(Keyword::Int8) => DataType::Int8,
+            Token::KeyWord(Keyword::Int16) => DataType::Int16,
+            Token::KeyWord(Keyword::Int32) => DataType::Int32,
+            Token::KeyWord(Keyword::Int64) => DataType::Int64,
+            Token::KeyWord(Keyword::Uint8) => DataType::Uint8,
+            Token::KeyWord(Keyword::Uint16) => DataType::Uint16,
+            Token::KeyWord(Keyword::Uint32) => DataType::Uint32,
+            Token::KeyWord(Keyword::Uint64) => DataType::Uint64,
+            Token::KeyWord(Keyword::Float32) => DataType::Float32,
+            Token::KeyWord(Keyword::Float64) => DataType::Float64,
+
+            Token::KeyWord(Keyword::Text) => DataType::String,
+            Token::KeyWord(Keyword::VarChar) => {
+                self.next_expected_token(Token::LeftParen)?;
+                let len = match self.next_token().clone() {
+                    Token::Integer(n) => n
+                        .parse::<usize>()
+                        .map_err(|e| self.error(format!("invalid VARCHAR length {}: {}", n, e)))?,
+                    _ => return Err(self.unexpected("Integer")),
+                };
+
+                self.next_expected_token(Token::RightParen)?;
+                DataType::Varchar(len)
+            }
+            Token::KeyWord(Keyword::Char) => DataType::Char,
+            Token::KeyWord(Keyword::String) => DataType::String,
+
+            _ => return Err(self.unexpected("data type")),
+        })
     }
 
-    fn parse_select_stmt(&mut self) -> Result<Statement> {
-        // SELECT [selects] [froms] [wheres] [group_by]
-        //        [having] [order] [limit] [offset];
-        Ok(Statement::Select(SelectStmt {
-            selects: self.parse_clause_select()?,
-            froms: {
-                let froms_exprs = self.parse_clause_from()?;
-                if froms_exprs.is_empty() {
-                    None
-                } else {
-                    Some(froms_exprs)
-                }
-            },
-            wheres: self.parse_clause_where()?,
-            group_by: match self.parse_clause_group_by() {
-                Ok(group_by_expr) => {
-                    if group_by_expr.is_empty() {
-                        None
-                    } else {
-                        Some(group_by_expr)
-                    }
-                }
-                Err(e) => {
-                    return Err(e);
-                }
-            },
-            having: self.parse_clause_having()?,
-            order: {
-                let order_exprs = self.parse_clause_order()?;
-                if order_exprs.is_empty() {
-                    None
-                } else {
-                    Some(order_exprs)
-                }
-            },
-            offset: {
-                match &self.pre_token {
-                    Token::KeyWord(Keyword::Offset) => {
-                        self.next_token();
-                        Some(
-                            self.parse_expression(Precedence::Lowest)
-                                .map_err(|_| Error::Parse(fmt_err!("OFFSET exp is not valid!")))?,
-                        )
-                    }
-                    _ => None,
-                }
-            },
-            limit: {
-                match &self.peek_token {
-                    Token::KeyWord(Keyword::Limit) => {
-                        self.next_token();
-                        self.next_token();
-                        Some(
-                            self.parse_expression(Precedence::Lowest)
-                                .map_err(|_| Error::Parse(fmt_err!("LIMIT exp is not valid!")))?,
-                        )
-                    }
-                    _ => None,
+    fn parse_drop_stmt(&mut self) -> Result<Statement> {
+        // DROP TABLE [IF EXISTS] table_name [, ...] [CASCADE]
+        // DROP [MATERIALIZED] VIEW [IF EXISTS] view_name [, ...] [CASCADE]
+        // DROP INDEX [IF EXISTS] index_name
+        // DROP DATABASE [IF EXISTS] database_name
+        match self.next_token() {
+            Token::KeyWord(Keyword::Table) => {
+                let if_exists = self.parse_if_exists()?;
+                Ok(Statement::DropTable(DropTableStmt {
+                    table_names: self.parse_name_list()?,
+                    if_exists,
+                    cascade: self.next_if_keyword(Keyword::Cascade),
+                }))
+            }
+            Token::KeyWord(Keyword::View) | Token::KeyWord(Keyword::Materialized) => {
+                let materialized = self.pre_token == Token::KeyWord(Keyword::Materialized);
+                if materialized {
+                    self.next_expected_keyword(Keyword::View)?;
                 }
-            },
+                let if_exists = self.parse_if_exists()?;
+                Ok(Statement::DropView(DropViewStmt {
+                    names: self.parse_name_list()?,
+                    materialized,
+                    if_exists,
+                    cascade: self.next_if_keyword(Keyword::Cascade),
+                }))
+            }
+            Token::KeyWord(Keyword::Index) => {
+                let if_exists = self.parse_if_exists()?;
+                Ok(Statement::DropIndex(DropIndexStmt {
+                    index_name: self.next_ident()?,
+                    if_exists,
+                }))
+            }
+            Token::KeyWord(Keyword::Database) => {
+                let if_exists = self.parse_if_exists()?;
+                Ok(Statement::DropDatabase(DropDatabaseStmt {
+                    name: self.next_ident()?,
+                    if_exists,
+                }))
+            }
+            _ => Err(self.unexpected("TABLE, VIEW, INDEX or DATABASE")),
+        }
+    }
+
+    // TRUNCATE [TABLE] table_name [, ...]
+    fn parse_truncate_stmt(&mut self) -> Result<Statement> {
+        self.next_if_keyword(Keyword::Table);
+        Ok(Statement::Truncate(self.parse_name_list()?))
+    }
+
+    // 不带括号的名称列表 a, b, c
+    fn parse_name_list(&mut self) -> Result<Vec<String>> {
+        let mut names = vec![self.next_ident()?];
+        while self.next_if_token(Token::Comma) {
+            names.push(self.next_ident()?);
+        }
+
+        Ok(names)
+    }
+
+    fn parse_create_view_stmt(&mut self) -> Result<Statement> {
+        // CREATE [OR REPLACE] [MATERIALIZED] VIEW view_name
+        // [(column_1, column_2)] AS query;
+        let or_replace = if self.next_if_keyword(Keyword::Or) {
+            self.next_expected_keyword(Keyword::Replace)?;
+            true
+        } else {
+            false
+        };
+        let materialized = self.next_if_keyword(Keyword::Materialized);
+        if or_replace && materialized {
+            return Err(
+                self.error("materialized view cannot be created with OR REPLACE".to_owned())
+            );
+        }
+        self.next_expected_keyword(Keyword::View)?;
+
+        let name = self.next_ident()?;
+        let columns = match self.peek_token {
+            Token::LeftParen => Some(self.parse_ident_list()?),
+            _ => None,
+        };
+        self.next_expected_keyword(Keyword::As)?;
+
+        match self.next_token() {
+            Token::KeyWord(Keyword::Select) | Token::KeyWord(Keyword::With) | Token::LeftParen => {}
+            _ => return Err(self.unexpected("SELECT or WITH")),
+        }
+
+        Ok(Statement::CreateView(CreateViewStmt {
+            name,
+            columns,
+            query: Box::new(self.parse_query()?),
+            or_replace,
+            materialized,
         }))
     }
 
-    fn parse_clause_select(&mut self) -> Result<Vec<(Expression, Option<String>)>> {
-        // SELECT   1 + 3       AS   c1;
-        //        [expression]     [alias]
-        let mut selects = Vec::new();
+    // REFRESH MATERIALIZED VIEW view_name
+    fn parse_refresh_stmt(&mut self) -> Result<Statement> {
+        self.next_expected_keyword(Keyword::Materialized)?;
+        self.next_expected_keyword(Keyword::View)?;
+        Ok(Statement::RefreshMaterializedView(self.next_ident()?))
+    }
+
+    // 单个 SELECT 仍然解析为 Statement::Select
+    fn parse_query_stmt(&mut self) -> Result<Statement> {
+        Ok(match self.parse_query()? {
+            Query {
+                with: None,
+                body: SetExpr::Select(select),
+                ..
+            } => Statement::Select(*select),
+            query => Statement::Query(query),
+        })
+    }
+
+    // [WITH [RECURSIVE] cte [, ...]] body [ORDER BY ...] [LIMIT limit] [OFFSET offset]
+    fn parse_query(&mut self) -> Result<Query> {
+        let with = match self.pre_token {
+            Token::KeyWord(Keyword::With) => {
+                let with = self.parse_with()?;
+                self.next_token();
+                Some(with)
+            }
+            _ => None,
+        };
+        let mut body = self.parse_set_expr(0)?;
+        let order = self.parse_clause_order()?;
+
+        // LIMIT 和 OFFSET 的先后顺序不做要求
+        let mut offset = None;
+        let mut limit = None;
         loop {
-            if self.next_if_token(Token::Asterisk) && selects.is_empty() {
+            if offset.is_none() && self.next_if_keyword(Keyword::Offset) {
+                self.next_token();
+                offset = Some(self.parse_expression(Precedence::Lowest)?);
+            } else if limit.is_none() && self.next_if_keyword(Keyword::Limit) {
                 self.next_token();
+                limit = Some(self.parse_expression(Precedence::Lowest)?);
+            } else {
                 break;
             }
+        }
 
-            let expr = self
-                .parse_expression(Precedence::Lowest)
-                .map_err(|_| Error::Parse(fmt_err!("SELECT expression is not valid!")))?;
+        // 单个 SELECT 的 ORDER BY, LIMIT 和 OFFSET 保存在 SelectStmt 中
+        if let SetExpr::Select(select) = &mut body {
+            select.order = order;
+            select.offset = offset;
+            select.limit = limit;
+            return Ok(Query {
+                with,
+                body,
+                order: None,
+                offset: None,
+                limit: None,
+            });
+        }
 
-            // SELECT 1 + 2 AS c1; 1 + 2 是一个表达式, c1 是 alias 的一个名字
-            // Keyword::As 是一个可选项
+        Ok(Query {
+            with,
+            body,
+            order,
+            offset,
+            limit,
+        })
+    }
 
-            let alias = match self.peek_token.clone() {
-                Token::KeyWord(Keyword::As) => {
-                    self.next_token();
-                    match self.peek_token.clone() {
-                        Token::Ident(ident) => {
-                            self.next_token();
-                            Some(ident)
-                        }
-                        _ => {
-                            return Err(Error::Parse(fmt_err!("AS is not valid!")));
-                        }
-                    }
-                }
-                Token::Ident(ident) => {
-                    self.next_token();
-                    Some(ident)
-                }
-                _ => None,
+    // SELECT ... | (query)
+    // [UNION | INTERSECT | EXCEPT [ALL] SELECT ... | (query)] ...
+    fn parse_set_expr(&mut self, precedence: u8) -> Result<SetExpr> {
+        let mut lhs = match self.pre_token {
+            Token::KeyWord(Keyword::Select) => SetExpr::Select(Box::new(self.parse_select()?)),
+            Token::LeftParen => SetExpr::Query(Box::new(self.parse_subquery()?)),
+            _ => return Err(self.unexpected(Keyword::Select)),
+        };
+
+        loop {
+            let op = match self.peek_token {
+                Token::KeyWord(Keyword::Union) => SetOperator::Union,
+                Token::KeyWord(Keyword::Intersect) => SetOperator::Intersect,
+                Token::KeyWord(Keyword::Except) => SetOperator::Except,
+                _ => break,
             };
+            if op.precedence() <= precedence {
+                break;
+            }
+            self.next_token();
+            let all = self.next_if_keyword(Keyword::All);
 
-            selects.push((expr, alias));
+            self.next_token();
+            let rhs = self.parse_set_expr(op.precedence())?;
+            lhs = SetExpr::SetOperation {
+                op,
+                all,
+                left: Box::new(lhs),
+                right: Box::new(rhs),
+            };
+        }
+
+        Ok(lhs)
+    }
 
+    // WITH [RECURSIVE] name [(column, ...)] AS (query) [, ...]
+    fn parse_with(&mut self) -> Result<With> {
+        let recursive = self.next_if_keyword(Keyword::Recursive);
+
+        let mut ctes = Vec::new();
+        loop {
+            let name = self.next_ident()?;
+            let columns = match self.peek_token {
+                Token::LeftParen => Some(self.parse_ident_list()?),
+                _ => None,
+            };
+            self.next_expected_keyword(Keyword::As)?;
+            self.next_expected_token(Token::LeftParen)?;
             self.next_token();
-            match &self.pre_token {
-                Token::Comma => continue,
-                _ => break,
+            let query = self.parse_query()?;
+            self.next_expected_token(Token::RightParen)?;
+
+            ctes.push(Cte {
+                name,
+                columns,
+                query,
+            });
+
+            if !self.next_if_token(Token::Comma) {
+                break;
             }
         }
 
-        Ok(selects)
+        Ok(With { recursive, ctes })
     }
 
-    fn parse_clause_from(&mut self) -> Result<Vec<FromItem>> {
-        let mut froms = Vec::new();
-        // select expression_list FROM
-        match &self.pre_token {
-            Token::KeyWord(Keyword::From) => {}
-            _ => {
-                return Ok(froms);
-            }
+    // ORDER BY, LIMIT 和 OFFSET 由 parse_query 解析
+    fn parse_select(&mut self) -> Result<SelectStmt> {
+        // SELECT [distinct] [selects] [from] [wheres] [group_by] [having] [windows]
+        let distinct = self.parse_clause_distinct()?;
+        let selects = self.parse_clause_select()?;
+        let from = self.parse_clause_from()?;
+        let wheres = self.parse_clause_where()?;
+        let group_by = self.parse_clause_group_by()?;
+        let having = self.parse_clause_having()?;
+        let windows = self.parse_clause_window()?;
+
+        Ok(SelectStmt {
+            distinct,
+            selects,
+            from,
+            wheres,
+            group_by,
+            having,
+            windows,
+            order: None,
+            offset: None,
+            limit: None,
+        })
+    }
+
+    // SELECT [ALL | DISTINCT | DISTINCT ON (a, b)]
+    fn parse_clause_distinct(&mut self) -> Result<Option<Distinct>> {
+        if self.next_if_keyword(Keyword::All) || !self.next_if_keyword(Keyword::Distinct) {
+            return Ok(None);
+        }
+        if !self.next_if_keyword(Keyword::On) {
+            return Ok(Some(Distinct::Distinct));
         }
 
-        loop {
-            // FROM table_name as alias_table_name
-            let mut item = self.parse_clause_from_table()?;
-            loop {
-                // SELECT t1.xxx, t2.xxx FROM t1 AS t3
-                //   LEFT JOIN t2 ON t1.xxx = t2.xxx;
-                let join_type = self.parse_clause_from_jointype()?;
-                if join_type.is_none() {
-                    break;
-                }
-                let join_type = join_type.unwrap();
-
-                let left_exp = Box::new(item);
-                let right_exp = Box::new(self.parse_clause_from_table()?);
-                // 谓词, On 之后的条件,
-                let predicate = match join_type {
-                    JoinType::Outer => None,
-                    _ => {
-                        self.next_expected_keyword(Keyword::On)?;
-                        self.next_token();
-
-                        Some(self.parse_expression(Precedence::Lowest).map_err(|_| {
-                            Error::Parse(fmt_err!("ON Predicate expression is not valid!"))
-                        })?)
-                    }
-                };
+        self.next_expected_token(Token::LeftParen)?;
+        let exprs = self.parse_expression_list()?;
+        if exprs.is_empty() {
+            return Err(self.unexpected("expression"));
+        }
 
-                item = FromItem::Join {
-                    left: left_exp,
-                    right: right_exp,
-                    join_type,
-                    predicate,
-                };
-            }
-            froms.push(item);
+        Ok(Some(Distinct::On(exprs)))
+    }
 
-            match &self.pre_token {
-                Token::KeyWord(k) => match k {
-                    Keyword::Where
-                    | Keyword::Group
-                    | Keyword::Having
-                    | Keyword::Order
-                    | Keyword::Limit
-                    | Keyword::Offset => {
-                        break;
+    fn parse_clause_select(&mut self) -> Result<Vec<SelectItem>> {
+        // SELECT   1 + 3       AS   c1;
+        //        [expression]     [alias]
+        let mut selects = Vec::new();
+        loop {
+            self.next_token();
+            let item = match (&self.pre_token, self.pre_ident(), &self.peek_token) {
+                (Token::Asterisk, _, _) => SelectItem::Wildcard,
+                // t.* 或者 t.column, 需要看到 . 之后的 Token 才能区分
+                (_, Some(table), Token::Period) => {
+                    let start = self.pre_span;
+                    self.next_token();
+                    if self.next_if_token(Token::Asterisk) {
+                        SelectItem::QualifiedWildcard(table)
+                    } else {
+                        let field = Expression::Field(Some(table), self.next_ident()?);
+                        let field = self.spanned(field, start);
+                        let expr = self.parse_infix_exprs(field, Precedence::Lowest)?;
+                        SelectItem::Expr(expr, self.parse_alias()?)
                     }
-                    _ => {}
-                },
+                }
                 _ => {
-                    self.next_token();
-                    break;
+                    let expr = self.parse_expression(Precedence::Lowest)?;
+
+                    // SELECT 1 + 2 AS c1; 1 + 2 是一个表达式, c1 是 alias 的一个名字
+                    // Keyword::As 是一个可选项
+                    SelectItem::Expr(expr, self.parse_alias()?)
                 }
+            };
+            selects.push(item);
+
+            if !self.next_if_token(Token::Comma) {
+                break;
             }
         }
 
-        Ok(froms)
+        Ok(selects)
     }
 
-    fn parse_clause_from_table(&mut self) -> Result<FromItem> {
-        let name = match self.peek_token.clone() {
+    // [AS] alias_name
+    fn parse_alias(&mut self) -> Result<Option<String>> {
+        if self.next_if_keyword(Keyword::As) {
+            return Ok(Some(self.next_ident()?));
+        }
+
+        match self.peek_token.clone() {
             Token::Ident(ident) => {
                 self.next_token();
-                ident
-            }
-            _ => {
-                return Err(Error::Parse(fmt_err!("FROM table_name is not valid!")));
+                Ok(Some(ident))
             }
-        };
+            _ => Ok(None),
+        }
+    }
 
-        let alias = match self.peek_token.clone() {
-            Token::KeyWord(Keyword::As) => {
-                self.next_token();
-                match self.peek_token.clone() {
-                    Token::Ident(ident) => {
-                        self.next_token();
-                        Some(ident)
-                    }
-                    _ => {
-                        return Err(Error::Parse(fmt_err!("FROM AS is not valid!")));
-                    }
-                }
-            }
-            Token::Ident(ident) => {
-                self.next_token();
-                Some(ident)
+    fn parse_clause_from(&mut self) -> Result<Option<FromItem>> {
+        // select expression_list FROM
+        if !self.next_if_keyword(Keyword::From) {
+            return Ok(None);
+        }
+
+        Ok(Some(self.parse_from_list()?))
+    }
+
+    // FROM 或者 DELETE ... USING 之后逗号分隔的表
+    // 逗号的优先级低于 JOIN, FROM a, b JOIN c ON ...
+    // 相当于 FROM a CROSS JOIN (b JOIN c ON ...)
+    fn parse_from_list(&mut self) -> Result<FromItem> {
+        let mut from = self.parse_clause_from_item()?;
+        while self.next_if_token(Token::Comma) {
+            from = FromItem::Join {
+                left: Box::new(from),
+                right: Box::new(self.parse_clause_from_item()?),
+                join_type: JoinType::Cross,
+                constraint: JoinConstraint::None,
+            };
+        }
+
+        Ok(from)
+    }
+
+    fn parse_clause_from_item(&mut self) -> Result<FromItem> {
+        // FROM table_name as alias_table_name
+        let mut item = self.parse_clause_from_table()?;
+
+        // SELECT t1.xxx, t2.xxx FROM t1 AS t3
+        //   LEFT JOIN t2 ON t1.xxx = t2.xxx;
+        while let Some((join_type, natural)) = self.parse_clause_from_jointype()? {
+            let right = self.parse_clause_from_table()?;
+            let constraint = if natural {
+                JoinConstraint::Natural
+            } else if join_type == JoinType::Cross {
+                JoinConstraint::None
+            } else {
+                self.parse_clause_join_constraint()?
+            };
+
+            item = FromItem::Join {
+                left: Box::new(item),
+                right: Box::new(right),
+                join_type,
+                constraint,
+            };
+        }
+
+        Ok(item)
+    }
+
+    fn parse_clause_from_table(&mut self) -> Result<FromItem> {
+        if self.next_if_token(Token::LeftParen) {
+            // FROM (SELECT ...) AS alias
+            if self.is_peek_query() {
+                let query = Box::new(self.parse_subquery()?);
+                let alias = self.parse_alias()?;
+
+                return Ok(FromItem::Subquery { query, alias });
             }
-            _ => None,
-        };
+
+            // FROM (a JOIN b ON ...) JOIN c ON ...
+            let item = self.parse_clause_from_item()?;
+            self.next_expected_token(Token::RightParen)?;
+
+            return Ok(item);
+        }
+
+        let name = self.next_ident()?;
+        let alias = self.parse_alias()?;
 
         Ok(FromItem::Table { name, alias })
     }
 
-    fn parse_clause_from_jointype(&mut self) -> Result<Option<JoinType>> {
-        match &self.peek_token {
-            Token::KeyWord(Keyword::Outer) => {
+    // [NATURAL] [INNER | {LEFT | RIGHT | FULL} [OUTER]] JOIN 或者 CROSS JOIN
+    // 返回的 bool 表示是否是 NATURAL JOIN
+    fn parse_clause_from_jointype(&mut self) -> Result<Option<(JoinType, bool)>> {
+        let natural = self.next_if_keyword(Keyword::Natural);
+        let join_type = match self.peek_token {
+            // postgresql 和 sqlite 默认join 都是 inner join
+            Token::KeyWord(Keyword::Join) => JoinType::Inner,
+            Token::KeyWord(Keyword::Inner) => {
                 self.next_token();
-                match self.peek_token.clone() {
-                    Token::KeyWord(Keyword::Join) => {
-                        self.next_token();
-
-                        Ok(Some(JoinType::Outer))
-                    }
-                    _ => Ok(None),
-                }
+                JoinType::Inner
             }
-            Token::KeyWord(Keyword::Inner) => {
+            Token::KeyWord(Keyword::Cross) if !natural => {
                 self.next_token();
-                match self.peek_token.clone() {
-                    Token::KeyWord(Keyword::Join) => {
-                        self.next_token();
-
-                        Ok(Some(JoinType::Inner))
-                    }
-                    _ => Ok(None),
-                }
+                JoinType::Cross
             }
             Token::KeyWord(Keyword::Left) => {
                 self.next_token();
-                match self.peek_token.clone() {
-                    Token::KeyWord(Keyword::Outer) => {
-                        self.next_token();
-                        match self.peek_token.clone() {
-                            Token::KeyWord(Keyword::Join) => {
-                                self.next_token();
-
-                                Ok(Some(JoinType::Left))
-                            }
-                            _ => Ok(None),
-                        }
-                    }
-                    Token::KeyWord(Keyword::Join) => {
-                        self.next_token();
-                        Ok(Some(JoinType::Left))
-                    }
-                    _ => Ok(None),
-                }
+                self.next_if_keyword(Keyword::Outer);
+                JoinType::Left
             }
             Token::KeyWord(Keyword::Right) => {
                 self.next_token();
-                match self.peek_token.clone() {
-                    Token::KeyWord(Keyword::Outer) => {
-                        self.next_token();
-                        match self.peek_token.clone() {
-                            Token::KeyWord(Keyword::Join) => {
-                                self.next_token();
-
-                                Ok(Some(JoinType::Right))
-                            }
-                            _ => Ok(None),
-                        }
-                    }
-                    Token::KeyWord(Keyword::Join) => {
-                        self.next_token();
-                        Ok(Some(JoinType::Right))
-                    }
-                    _ => Ok(None),
-                }
+                self.next_if_keyword(Keyword::Outer);
+                JoinType::Right
             }
-            // postgresql 和 sqlite 默认join 都是 inner join
-            Token::KeyWord(Keyword::Join) => {
+            Token::KeyWord(Keyword::Full) => {
                 self.next_token();
-                Ok(Some(JoinType::Inner))
+                self.next_if_keyword(Keyword::Outer);
+                JoinType::Full
             }
-            _ => Ok(None),
-        }
-    }
+            _ if natural => return Err(self.unexpected_peek(Keyword::Join)),
+            _ => return Ok(None),
+        };
+        self.next_expected_keyword(Keyword::Join)?;
 
-    fn parse_clause_group_by(&mut self) -> Result<Vec<Expression>> {
-        let mut exprs = Vec::new();
+        Ok(Some((join_type, natural)))
+    }
 
-        match &self.pre_token {
-            Token::KeyWord(Keyword::Group) => {}
-            _ => {
-                return Ok(exprs);
-            }
+    // ON predicate 或者 USING (a, b)
+    fn parse_clause_join_constraint(&mut self) -> Result<JoinConstraint> {
+        if self.next_if_keyword(Keyword::On) {
+            self.next_token();
+            return Ok(JoinConstraint::On(
+                self.parse_expression(Precedence::Lowest)?,
+            ));
+        }
+        if self.next_if_keyword(Keyword::Using) {
+            return Ok(JoinConstraint::Using(self.parse_ident_list()?));
         }
 
+        Err(self.unexpected_peek("ON or USING"))
+    }
+
+    fn parse_clause_group_by(&mut self) -> Result<Option<Vec<Expression>>> {
+        if !self.next_if_keyword(Keyword::Group) {
+            return Ok(None);
+        }
         self.next_expected_keyword(Keyword::By)?;
-        self.next_token();
 
+        let mut exprs = Vec::new();
         loop {
-            exprs.push(
-                self.parse_expression(Precedence::Lowest)
-                    .map_err(|_| Error::Parse(fmt_err!("GROUP BY exp is not valid!")))?,
-            );
-
             self.next_token();
-            match self.peek_token {
-                Token::Comma => continue,
-                _ => break,
+            exprs.push(self.parse_expression(Precedence::Lowest)?);
+
+            if !self.next_if_token(Token::Comma) {
+                break;
             }
         }
 
-        Ok(exprs)
+        Ok(Some(exprs))
+    }
+
+    fn parse_clause_where(&mut self) -> Result<Option<Expression>> {
+        if !self.next_if_keyword(Keyword::Where) {
+            return Ok(None);
+        }
+        self.next_token();
+
+        Ok(Some(self.parse_expression(Precedence::Lowest)?))
     }
 
-    fn parse_clause_where(&mut self) -> Result<Option<Expression>> {
-        match &self.pre_token {
-            Token::KeyWord(Keyword::Where) => {}
-            _ => {
-                return Ok(None);
-            }
+    fn parse_clause_having(&mut self) -> Result<Option<Expression>> {
+        if !self.next_if_keyword(Keyword::Having) {
+            return Ok(None);
         }
         self.next_token();
 
-        return Ok(Some({
-            let exp = self
-                .parse_expression(Precedence::Lowest)
-                .map_err(|_| Error::Parse(fmt_err!("WHERE exp is not valid!")))?;
-            self.next_token();
-            exp
-        }));
+        Ok(Some(self.parse_expression(Precedence::Lowest)?))
     }
 
-    fn parse_clause_having(&mut self) -> Result<Option<Expression>> {
-        match self.pre_token {
-            Token::KeyWord(Keyword::Having) => {
-                self.next_token();
-                Ok(Some({
-                    let exp = self
-                        .parse_expression(Precedence::Lowest)
-                        .map_err(|_| Error::Parse(fmt_err!("HAVING exp is not valid!")))?;
+    // WINDOW w1 AS (window_spec), w2 AS (...)
+    fn parse_clause_window(&mut self) -> Result<Option<Vec<(String, WindowSpec)>>> {
+        if !self.next_if_keyword(Keyword::Window) {
+            return Ok(None);
+        }
 
-                    self.next_token();
-                    exp
-                }))
+        let mut windows = Vec::new();
+        loop {
+            let name = self.next_ident()?;
+            self.next_expected_keyword(Keyword::As)?;
+            self.next_expected_token(Token::LeftParen)?;
+            windows.push((name, self.parse_window_spec()?));
+
+            if !self.next_if_token(Token::Comma) {
+                break;
             }
-            _ => Ok(None),
         }
+
+        Ok(Some(windows))
     }
 
-    fn parse_clause_order(&mut self) -> Result<Vec<(Expression, OrderByType)>> {
-        match &self.pre_token {
-            Token::KeyWord(Keyword::Order) => {}
-            _ => {
-                return Ok(Vec::new());
-            }
+    fn parse_clause_order(&mut self) -> Result<Option<Vec<(Expression, OrderByType)>>> {
+        if !self.next_if_keyword(Keyword::Order) {
+            return Ok(None);
         }
         self.next_expected_keyword(Keyword::By)?;
-        self.next_token();
         let mut orders = Vec::new();
 
         loop {
-            orders.push((
-                {
-                    let exp = self
-                        .parse_expression(Precedence::Lowest)
-                        .map_err(|_| Error::Parse(fmt_err!("ORDER BY exp is not valid!")))?;
-                    self.next_token();
-                    exp
-                },
-                match self.pre_token {
-                    Token::KeyWord(Keyword::Asc) => OrderByType::Asc,
-                    Token::KeyWord(Keyword::Desc) => OrderByType::Desc,
-                    _ => OrderByType::Asc,
-                },
-            ));
+            self.next_token();
+            let exp = self.parse_expression(Precedence::Lowest)?;
+            let order_type = if self.next_if_keyword(Keyword::Desc) {
+                OrderByType::Desc
+            } else {
+                self.next_if_keyword(Keyword::Asc);
+                OrderByType::Asc
+            };
+            orders.push((exp, order_type));
+
             if !self.next_if_token(Token::Comma) {
                 break;
             }
         }
 
-        Ok(orders)
+        Ok(Some(orders))
     }
 
     fn parse_update_stmt(&mut self) -> Result<Statement> {
+        // UPDATE table_name SET column = expr [, ...] [FROM t1, t2] [WHERE expr]
+        // [ORDER BY expr] [LIMIT n] [RETURNING expr_list];
         let table_name = self.next_ident()?;
 
         self.next_expected_keyword(Keyword::Set)?;
+        let set = self.parse_assignments()?;
 
-        let mut set = BTreeMap::new();
+        Ok(Statement::Update(UpdateStmt {
+            table_name,
+            set,
+            from: self.parse_clause_from()?,
+            wheres: self.parse_clause_where()?,
+            order: self.parse_clause_order()?,
+            limit: self.parse_clause_limit()?,
+            returning: self.parse_clause_returning()?,
+        }))
+    }
+
+    // SET 之后的 column_1 = expr_1, column_2 = expr_2
+    fn parse_assignments(&mut self) -> Result<Vec<(String, Expression)>> {
+        let mut set: Vec<(String, Expression)> = Vec::new();
 
         loop {
             let column = self.next_ident()?;
+            let column_span = self.pre_span;
             self.next_expected_token(Token::Equal)?;
             self.next_token();
-            let expr = self
-                .parse_expression(Precedence::Lowest)
-                .map_err(|_| Error::Parse(fmt_err!("expr is not valid!")))?;
-
-            if set.contains_key(&column) {
-                return Err(Error::Parse(fmt_err!(
-                    "Duplicate values given for column {}",
-                    column
-                )));
+            let expr = self.parse_expression(Precedence::Lowest)?;
+
+            if set.iter().any(|(c, _)| *c == column) {
+                return Err(ParseError::new(
+                    format!("Duplicate values given for column {}", column),
+                    column_span,
+                )
+                .into());
             }
-            set.insert(column, expr);
-            if self.peek_token != Token::Comma {
-                self.next_token();
+            set.push((column, expr));
+            if !self.next_if_token(Token::Comma) {
                 break;
             }
         }
-        Ok(Statement::Update(UpdateStmt {
-            table_name,
-            set,
-            wheres: self.parse_clause_where()?,
-        }))
+
+        Ok(set)
     }
 
     fn parse_explain_stmt(&mut self) -> Result<Statement> {
@@ -1129,18 +1571,62 @@ impl Parser {
         }))
     }
 
+    // PREPARE name AS statement
+    fn parse_prepare_stmt(&mut self) -> Result<Statement> {
+        let name = self.next_ident()?;
+        self.next_expected_keyword(Keyword::As)?;
+
+        // 只有查询和 DML 语句可以预编译
+        match self.next_token() {
+            Token::KeyWord(
+                Keyword::Select
+                | Keyword::With
+                | Keyword::Insert
+                | Keyword::Update
+                | Keyword::Delete,
+            )
+            | Token::LeftParen => {}
+            _ => return Err(self.unexpected("SELECT, INSERT, UPDATE or DELETE")),
+        }
+
+        Ok(Statement::Prepare(PrepareStmt {
+            name,
+            statement: Box::new(self.parse_stmt()?),
+        }))
+    }
+
+    // EXECUTE name [(param, ...)]
+    fn parse_execute_stmt(&mut self) -> Result<Statement> {
+        let name = self.next_ident()?;
+        let params = if self.next_if_token(Token::LeftParen) {
+            self.parse_expression_list()?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Statement::Execute(ExecuteStmt { name, params }))
+    }
+
+    // DEALLOCATE [PREPARE] {name | ALL}
+    fn parse_deallocate_stmt(&mut self) -> Result<Statement> {
+        self.next_if_keyword(Keyword::Prepare);
+        if self.next_if_keyword(Keyword::All) {
+            return Ok(Statement::Deallocate(None));
+        }
+
+        Ok(Statement::Deallocate(Some(self.next_ident()?)))
+    }
+
     fn next_token(&mut self) -> &Token {
-        self.pre_token = self.peek_token.clone();
-        self.peek_token = self.lexer.next_token();
+        let SpannedToken { token, span } = self.lexer.next_token();
+        self.pre_token = std::mem::replace(&mut self.peek_token, token);
+        self.pre_span = std::mem::replace(&mut self.peek_span, span);
 
         &self.pre_token
     }
 
+    // 如果 peek_token 是 t 则前进一个 Token
     fn next_if_token(&mut self, t: Token) -> bool {
-        *self.next_token() == t
-    }
-
-    fn peek_if_token(&mut self, t: Token) -> bool {
         if self.peek_token == t {
             self.next_token();
             return true;
@@ -1150,403 +1636,598 @@ impl Parser {
     }
 
     fn next_if_keyword(&mut self, k: Keyword) -> bool {
-        *self.next_token() == Token::KeyWord(k)
+        self.next_if_token(Token::KeyWord(k))
     }
 
     fn next_expected_keyword(&mut self, k: Keyword) -> Result<()> {
-        let t = self.next_token();
-
-        if *t == Token::KeyWord(k) {
+        if *self.next_token() == Token::KeyWord(k) {
             Ok(())
         } else {
-            Err(Error::Parse(fmt_err!(
-                "unexpected keyword: {} want: {}",
-                t,
-                k
-            )))
+            Err(self.unexpected(k))
         }
     }
 
     fn next_expected_token(&mut self, t: Token) -> Result<()> {
-        let token = self.next_token();
-
-        if *token == t {
+        if *self.next_token() == t {
             Ok(())
         } else {
-            Err(Error::Parse(fmt_err!(
-                "unexpected token: {} want: {}",
-                token,
-                t
-            )))
+            Err(self.unexpected(t))
         }
     }
 
     fn next_ident(&mut self) -> Result<String> {
-        match self.next_token() {
-            Token::Ident(ident) => Ok(ident.clone()),
-            t => Err(Error::Parse(fmt_err!(
-                "expected: Token::Ident but get: {}",
-                t
-            ))),
+        self.next_token();
+        self.pre_ident().ok_or_else(|| self.unexpected("Ident"))
+    }
+
+    // peek_token 是 Ident 或者非保留关键字
+    fn peek_is_ident(&self) -> bool {
+        match &self.peek_token {
+            Token::Ident(_) => true,
+            Token::KeyWord(k) => !k.is_reserved(),
+            _ => false,
+        }
+    }
+
+    // pre_token 作为标识符的名称
+    // 关键字不区分大小写, 非保留关键字作为标识符时统一使用小写,
+    // 这样格式化时改变关键字的大小写不会改变语句的含义
+    fn pre_ident(&self) -> Option<String> {
+        match &self.pre_token {
+            Token::Ident(ident) => Some(ident.clone()),
+            Token::KeyWord(k) if !k.is_reserved() => {
+                Some(self.lexer.source(self.pre_span).to_lowercase())
+            }
+            _ => None,
+        }
+    }
+
+    // (column_1, column_2, ...), 同一个名称不能出现两次
+    fn parse_ident_list(&mut self) -> Result<Vec<String>> {
+        self.next_expected_token(Token::LeftParen)?;
+
+        let mut idents: Vec<String> = Vec::new();
+        loop {
+            let ident = self.next_ident()?;
+            if idents.contains(&ident) {
+                return Err(self.error(format!("column {} specified more than once", ident)));
+            }
+            idents.push(ident);
+
+            match self.next_token() {
+                Token::Comma => continue,
+                Token::RightParen => break,
+                _ => return Err(self.unexpected("Comma or RightParen")),
+            }
         }
+
+        Ok(idents)
+    }
+
+    // pre_token 不是期望的 Token
+    fn unexpected(&self, expected: impl std::fmt::Display) -> Error {
+        unexpected_token(expected, &self.pre_token, self.pre_span)
+    }
+
+    // peek_token 不是期望的 Token
+    fn unexpected_peek(&self, expected: impl std::fmt::Display) -> Error {
+        unexpected_token(expected, &self.peek_token, self.peek_span)
+    }
+
+    // 在 pre_token 的位置报错
+    fn error(&self, message: String) -> Error {
+        ParseError::new(message, self.pre_span).into()
     }
 
     // (1 + 2)
     fn parse_expression(&mut self, precedence: Precedence) -> Result<Expression> {
         if !is_prefix_oper(&self.pre_token) {
-            return Err(Error::Parse(fmt_err!(
-                "No prefix Operator Func for: {:?}",
-                &self.pre_token
-            )));
+            return Err(self.unexpected("expression"));
         }
 
-        let mut lhs = match self.parse_prefix_expr()? {
-            Some(exp) => exp,
-            None => {
-                return Err(Error::Parse(fmt_err!("ParsePrefixExpression exp is None")));
-            }
-        };
+        let start = self.pre_span;
+        let lhs = self.parse_prefix_expr()?;
+        let lhs = self.spanned(lhs, start);
+        self.parse_infix_exprs(lhs, precedence)
+    }
 
+    // 以 lhs 作为左边的表达式, 继续解析优先级比 precedence 高的中缀运算符
+    fn parse_infix_exprs(
+        &mut self,
+        mut lhs: Expression,
+        precedence: Precedence,
+    ) -> Result<Expression> {
+        let start = lhs.span().unwrap_or(self.pre_span);
         while self.pre_token != Token::Semicolon && precedence < self.peek_token_predence() {
             if !is_infix_oper(&self.peek_token) {
                 return Ok(lhs);
             }
             self.next_token();
             lhs = self.parse_infix_expr(lhs)?;
+            lhs = self.spanned(lhs, start);
         }
 
         Ok(lhs)
     }
 
-    fn parse_prefix_expr(&mut self) -> Result<Option<Expression>> {
+    // 给表达式加上从 start 到 pre_token 的位置
+    // (a + b) 这样已经有位置的表达式只扩大位置, 不再包一层
+    fn spanned(&self, expr: Expression, start: Span) -> Expression {
+        let span = start.to(self.pre_span);
+        match expr {
+            Expression::Spanned(mut spanned) => {
+                spanned.span = span;
+                Expression::Spanned(spanned)
+            }
+            node => Expression::Spanned(Box::new(Spanned { node, span })),
+        }
+    }
+
+    fn parse_prefix_expr(&mut self) -> Result<Expression> {
         // 1 + 2 + 3
-        match self.pre_token.clone() {
+        // 非保留关键字在表达式中和 Ident 一样, 是列名或者函数名
+        let token = match self.pre_ident() {
+            Some(ident) => Token::Ident(ident),
+            None => self.pre_token.clone(),
+        };
+        match token {
             Token::Exclamation => {
                 self.next_token();
-
-                Ok(Some(Expression::Operation(Operation::Not(Box::new(
-                    self.parse_expression(Precedence::Prefix)
-                        .map_err(|_| Error::Parse(fmt_err!("Operation::Not exp is not valid!")))?,
-                )))))
+                Ok(Expression::Operation(Operation::Not(Box::new(
+                    self.parse_expression(Precedence::Prefix)?,
+                ))))
+            }
+            Token::Tilde => {
+                self.next_token();
+                Ok(Expression::Operation(Operation::BitWiseNot(Box::new(
+                    self.parse_expression(Precedence::Prefix)?,
+                ))))
+            }
+            // NOT 的优先级比比较运算符低, NOT a = b 等价于 NOT (a = b)
+            Token::KeyWord(Keyword::Not) => {
+                self.next_token();
+                Ok(Expression::Operation(Operation::Not(Box::new(
+                    self.parse_expression(Precedence::Not)?,
+                ))))
             }
             Token::Add => {
                 self.next_token();
-                Ok(Some(Expression::Operation(Operation::Assert(Box::new(
-                    self.parse_expression(Precedence::Prefix).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::Assert exp is not valid!"))
-                    })?,
-                )))))
+                Ok(Expression::Operation(Operation::Assert(Box::new(
+                    self.parse_expression(Precedence::Prefix)?,
+                ))))
             }
             Token::Minus => {
                 self.next_token();
-                Ok(Some(Expression::Operation(Operation::Negate(Box::new(
-                    self.parse_expression(Precedence::Prefix).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::Negate exp is not valid!"))
-                    })?,
-                )))))
-            }
-            Token::Number(n) => {
-                // 如果包含 '.' 则说明是一个浮点数
-                if n.contains('.') {
-                    Ok(Some(Expression::Literal(Literal::Float(
-                        match n.parse::<f64>() {
-                            Ok(n) => n,
-                            Err(e) => {
-                                return Err(Error::Parse(fmt_err!("{}", e)));
-                            }
-                        },
-                    ))))
-                } else {
-                    Ok(Some(Expression::Literal(Literal::Int(
-                        match n.parse::<i64>() {
-                            Ok(n) => n,
-                            Err(e) => {
-                                return Err(Error::Parse(fmt_err!("{}", e)));
-                            }
-                        },
-                    ))))
-                }
+                Ok(Expression::Operation(Operation::Negate(Box::new(
+                    self.parse_expression(Precedence::Prefix)?,
+                ))))
+            }
+            Token::Question => self.parse_parameter(Parameter::Anonymous),
+            Token::Parameter(p) => {
+                let parameter = match p.strip_prefix(':') {
+                    Some(name) => Parameter::Named(name.to_owned()),
+                    // $0 以及超出范围的下标都是非法的
+                    None => match p[1..].parse::<usize>() {
+                        Ok(index) if index > 0 => Parameter::Indexed(index),
+                        _ => return Err(self.error(format!("invalid parameter {}", p))),
+                    },
+                };
+                self.parse_parameter(parameter)
+            }
+            Token::Integer(n) => match n.parse::<i64>() {
+                Ok(i) => Ok(Expression::Literal(Literal::Int(i))),
+                // 超出 i64 范围的整数提升为浮点数
+                Err(_) => Ok(Expression::Literal(Literal::Float(
+                    n.parse::<f64>()
+                        .map_err(|e| self.error(format!("invalid number {}: {}", n, e)))?,
+                ))),
+            },
+            Token::HexInteger(h) => match i64::from_str_radix(&h, 16) {
+                Ok(i) => Ok(Expression::Literal(Literal::Int(i))),
+                Err(_) => Ok(Expression::Literal(Literal::Float(
+                    h.chars().fold(0.0, |n, ch| {
+                        n * 16.0 + ch.to_digit(16).unwrap_or_default() as f64
+                    }),
+                ))),
+            },
+            Token::Float(f) => Ok(Expression::Literal(Literal::Float(
+                f.parse::<f64>()
+                    .map_err(|e| self.error(format!("invalid number {}: {}", f, e)))?,
+            ))),
+            // (SELECT ...) 是标量子查询
+            Token::LeftParen if self.is_peek_query() => {
+                Ok(Expression::Subquery(Box::new(self.parse_subquery()?)))
             }
             Token::LeftParen => {
                 self.next_token();
                 let exp = self.parse_expression(Precedence::Lowest)?;
-                if !self.peek_if_token(Token::RightParen) {
-                    return Ok(None);
-                }
+                self.next_expected_token(Token::RightParen)?;
 
-                Ok(Some(exp))
+                Ok(exp)
             }
             Token::Ident(i) => match &self.peek_token {
-                Token::LeftParen => Ok(Some(Expression::Literal(Literal::String(i)))),
+                Token::LeftParen => Ok(Expression::Literal(Literal::String(i))),
                 Token::Period => {
                     self.next_token();
-                    Ok(Some(Expression::Field(
-                        Some(i),
-                        match self.peek_token.clone() {
-                            Token::Ident(i) => {
-                                self.next_token();
-                                i
-                            }
-                            _ => {
-                                return Err(Error::Parse(fmt_err!("expected: Token::Ident")));
-                            }
-                        },
-                    )))
+                    Ok(Expression::Field(Some(i), self.next_ident()?))
                 }
-                _ => Ok(Some(Expression::Field(None, i))),
-            },
-            Token::String(s) => Ok(Some(Expression::Literal(Literal::String(s)))),
-            Token::KeyWord(k) => match k {
-                Keyword::True => Ok(Some(Expression::Literal(Literal::Bool(true)))),
-                Keyword::False => Ok(Some(Expression::Literal(Literal::Bool(false)))),
-                Keyword::Null => Ok(Some(Expression::Literal(Literal::Null))),
-                _ => Err(Error::Parse(fmt_err!(
-                    "No prefixOperatorFunc for {}",
-                    self.pre_token
-                ))),
+                _ => Ok(Expression::Field(None, i)),
             },
+            Token::String(s) => Ok(Expression::Literal(Literal::String(s))),
+            Token::KeyWord(Keyword::True) => Ok(Expression::Literal(Literal::Bool(true))),
+            Token::KeyWord(Keyword::False) => Ok(Expression::Literal(Literal::Bool(false))),
+            Token::KeyWord(Keyword::Null) => Ok(Expression::Literal(Literal::Null)),
+            Token::KeyWord(Keyword::Infinity) => {
+                Ok(Expression::Literal(Literal::Float(f64::INFINITY)))
+            }
+            Token::KeyWord(Keyword::NaN) => Ok(Expression::Literal(Literal::Float(f64::NAN))),
+            Token::KeyWord(Keyword::Case) => self.parse_case_expr(),
+            Token::KeyWord(Keyword::Exists) => {
+                self.next_expected_token(Token::LeftParen)?;
+                Ok(Expression::Exists(Box::new(self.parse_subquery()?)))
+            }
+            Token::KeyWord(Keyword::Cast) => {
+                // CAST(expr AS data_type)
+                self.next_expected_token(Token::LeftParen)?;
+                self.next_token();
+                let exp = self.parse_expression(Precedence::Lowest)?;
+                self.next_expected_keyword(Keyword::As)?;
+                self.next_token();
+                let data_type = self.parse_data_type()?;
+                self.next_expected_token(Token::RightParen)?;
 
-            _ => Err(Error::Parse(fmt_err!(
-                "No prefixOperatorFunc for {}",
-                self.pre_token
-            ))),
+                Ok(Expression::Cast(Box::new(exp), data_type))
+            }
+
+            _ => Err(self.unexpected("expression")),
         }
     }
 
     fn parse_infix_expr(&mut self, exp: Expression) -> Result<Expression> {
-        match self.pre_token {
-            Token::Add => {
-                let precedence = match_precedence(&self.pre_token);
+        let operation: fn(Box<Expression>, Box<Expression>) -> Operation = match self.pre_token {
+            Token::Add => Operation::Add,
+            Token::Equal => Operation::Equal,
+            Token::GreaterThan => Operation::GreaterThan,
+            Token::GreaterThanOrEqual => Operation::GreaterThanOrEqual,
+            Token::LessThan => Operation::LessThan,
+            Token::LessThanOrEqual => Operation::LessThanOrEqual,
+            Token::Minus => Operation::Subtract,
+            Token::NotEqual => Operation::NotEqual,
+            Token::KeyWord(Keyword::And) => Operation::And,
+            Token::KeyWord(Keyword::Or) => Operation::Or,
+            Token::KeyWord(Keyword::Like) => Operation::Like,
+            Token::Percent => Operation::Modulo,
+            Token::Asterisk => Operation::Multiply,
+            Token::Slash => Operation::Divide,
+            // 如果 ( 是一个中缀运算符, 则是一个函数
+            Token::LeftParen => return self.parse_function_expr(exp),
+            Token::KeyWord(Keyword::Is) => return self.parse_is_expr(exp),
+            // expr::data_type 是 CAST(expr AS data_type) 的简写
+            Token::DoubleColon => {
                 self.next_token();
-                Ok(Expression::Operation(Operation::Add(
-                    Box::new(exp),
-                    Box::new(
-                        self.parse_expression(precedence).map_err(|_| {
-                            Error::Parse(fmt_err!("Operation::Add exp is not valid!"))
-                        })?,
-                    ),
-                )))
+                return Ok(Expression::Cast(Box::new(exp), self.parse_data_type()?));
             }
-            Token::Equal => {
-                let precedence = match_precedence(&self.pre_token);
+            Token::KeyWord(Keyword::Not) => {
                 self.next_token();
-
-                Ok(Expression::Operation(Operation::Equal(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::Equal exp is not valid!"))
-                    })?),
-                )))
+                return self.parse_predicate_expr(exp, true);
             }
-            Token::GreaterThan => {
-                let precedence = match_precedence(&self.pre_token);
-                self.next_token();
-
-                Ok(Expression::Operation(Operation::GreaterThan(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::GreaterThan exp is not valid!"))
-                    })?),
-                )))
+            Token::KeyWord(Keyword::In) | Token::KeyWord(Keyword::Between) => {
+                return self.parse_predicate_expr(exp, false)
             }
-            Token::GreaterThanOrEqual => {
-                let precedence = match_precedence(&self.pre_token);
-                self.next_token();
+            _ => return Err(self.unexpected("infix operator")),
+        };
 
-                Ok(Expression::Operation(Operation::GreaterThanOrEqual(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::GreaterThanOrEqual is not valid!"))
-                    })?),
-                )))
-            }
-            Token::LessThan => {
-                let precedence = match_precedence(&self.pre_token);
-                self.next_token();
+        let precedence = match_precedence(&self.pre_token);
+        self.next_token();
+        let rhs = self.parse_expression(precedence)?;
 
-                Ok(Expression::Operation(Operation::LessThan(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::LessThan exp is not valid!"))
-                    })?),
-                )))
-            }
-            Token::LessThanOrEqual => {
-                let precedence = match_precedence(&self.pre_token);
-                self.next_token();
+        Ok(Expression::Operation(operation(
+            Box::new(exp),
+            Box::new(rhs),
+        )))
+    }
 
-                Ok(Expression::Operation(Operation::LessThanOrEqual(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::LessThanOrEqual exp is not valid!"))
-                    })?),
-                )))
-            }
-            Token::Minus => {
-                let precedence = match_precedence(&self.pre_token);
+    // CASE [operand]
+    //     WHEN condition THEN result
+    //     [WHEN ...]
+    //     [ELSE result]
+    // END
+    fn parse_case_expr(&mut self) -> Result<Expression> {
+        let operand = match self.peek_token {
+            Token::KeyWord(Keyword::When) => None,
+            _ => {
                 self.next_token();
+                Some(Box::new(self.parse_expression(Precedence::Lowest)?))
+            }
+        };
+
+        let mut branches = Vec::new();
+        while self.next_if_keyword(Keyword::When) {
+            self.next_token();
+            let condition = self.parse_expression(Precedence::Lowest)?;
+            self.next_expected_keyword(Keyword::Then)?;
+            self.next_token();
+            branches.push((condition, self.parse_expression(Precedence::Lowest)?));
+        }
+        if branches.is_empty() {
+            return Err(self.unexpected_peek(Keyword::When));
+        }
+
+        let else_result = if self.next_if_keyword(Keyword::Else) {
+            self.next_token();
+            Some(Box::new(self.parse_expression(Precedence::Lowest)?))
+        } else {
+            None
+        };
+        self.next_expected_keyword(Keyword::End)?;
+
+        Ok(Expression::Case {
+            operand,
+            branches,
+            else_result,
+        })
+    }
+
+    // x IS [NOT] NULL
+    fn parse_is_expr(&mut self, exp: Expression) -> Result<Expression> {
+        let is_not = self.next_if_keyword(Keyword::Not);
+        self.next_expected_keyword(Keyword::Null)?;
+
+        Ok(Expression::Operation(if is_not {
+            Operation::IsNotNull(Box::new(exp))
+        } else {
+            Operation::IsNull(Box::new(exp))
+        }))
+    }
+
+    // x [NOT] IN (a, b, c)
+    // x [NOT] BETWEEN low AND high
+    // x [NOT] LIKE pattern
+    fn parse_predicate_expr(&mut self, exp: Expression, is_not: bool) -> Result<Expression> {
+        let exp = Box::new(exp);
+        let operation = match self.pre_token {
+            Token::KeyWord(Keyword::In) => {
+                self.next_expected_token(Token::LeftParen)?;
+                if self.is_peek_query() {
+                    let query = Box::new(self.parse_subquery()?);
+                    return Ok(Expression::Operation(if is_not {
+                        Operation::NotInSubquery(exp, query)
+                    } else {
+                        Operation::InSubquery(exp, query)
+                    }));
+                }
+
+                let list = self.parse_expression_list()?;
+                if list.is_empty() {
+                    return Err(self.unexpected("expression"));
+                }
 
-                Ok(Expression::Operation(Operation::Subtract(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::Minus exp is not valid!"))
-                    })?),
-                )))
+                if is_not {
+                    Operation::NotIn(exp, list)
+                } else {
+                    Operation::In(exp, list)
+                }
             }
-            Token::NotEqual => {
-                let precedence = match_precedence(&self.pre_token);
+            Token::KeyWord(Keyword::Between) => {
+                // 上下界的优先级要高于 AND, x BETWEEN 1 AND 2 AND y 中第二个 AND 是逻辑运算符
                 self.next_token();
+                let low = Box::new(self.parse_expression(Precedence::Equals)?);
+                self.next_expected_keyword(Keyword::And)?;
+                self.next_token();
+                let high = Box::new(self.parse_expression(Precedence::Equals)?);
 
-                Ok(Expression::Operation(Operation::NotEqual(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::NotEqual exp is not valid!"))
-                    })?),
-                )))
+                if is_not {
+                    Operation::NotBetween(exp, low, high)
+                } else {
+                    Operation::Between(exp, low, high)
+                }
             }
-            Token::KeyWord(Keyword::And) => {
-                let precedence = match_precedence(&self.pre_token);
+            Token::KeyWord(Keyword::Like) if is_not => {
                 self.next_token();
-
-                Ok(Expression::Operation(Operation::And(
-                    Box::new(exp),
-                    Box::new(
-                        self.parse_expression(precedence).map_err(|_| {
-                            Error::Parse(fmt_err!("Operation::And exp is not valid!"))
-                        })?,
-                    ),
-                )))
+                Operation::NotLike(exp, Box::new(self.parse_expression(Precedence::Equals)?))
             }
-            Token::KeyWord(Keyword::Or) => {
-                let precedence = match_precedence(&self.pre_token);
+            _ => return Err(self.unexpected("IN, BETWEEN or LIKE")),
+        };
+
+        Ok(Expression::Operation(operation))
+    }
+
+    // ( 之后是否是一个子查询
+    fn is_peek_query(&self) -> bool {
+        matches!(
+            self.peek_token,
+            Token::KeyWord(Keyword::Select) | Token::KeyWord(Keyword::With)
+        )
+    }
+
+    // 进入时 pre_token 是 (, 解析 query ) 并停在 ) 上
+    fn parse_subquery(&mut self) -> Result<Query> {
+        self.next_token();
+        let query = self.parse_query()?;
+        self.next_expected_token(Token::RightParen)?;
+
+        Ok(query)
+    }
+
+    fn parse_function_expr(&mut self, exp: Expression) -> Result<Expression> {
+        let name = match exp.unspanned() {
+            Expression::Literal(Literal::String(s)) => s.clone(),
+            _ => return Err(self.unexpected("function name before LeftParen")),
+        };
+
+        // COUNT(DISTINCT a)
+        let distinct = self.next_if_keyword(Keyword::Distinct);
+        let args = match self.peek_token {
+            // SELECT FUNCTION_NAME(*)
+            Token::Asterisk if !distinct => {
                 self.next_token();
+                self.next_expected_token(Token::RightParen)?;
+                vec![Expression::Literal(Literal::All)]
+            }
+            // empty function args, like SUM(), NOW()
+            _ => self.parse_expression_list()?,
+        };
+        if distinct && args.is_empty() {
+            return Err(self.unexpected("expression"));
+        }
 
-                Ok(Expression::Operation(Operation::Or(
-                    Box::new(exp),
-                    Box::new(
-                        self.parse_expression(precedence).map_err(|_| {
-                            Error::Parse(fmt_err!("Operation::Or exp is not valid!"))
-                        })?,
-                    ),
-                )))
+        // SUM(a) FILTER (WHERE b > 1)
+        let filter = if self.next_if_keyword(Keyword::Filter) {
+            self.next_expected_token(Token::LeftParen)?;
+            self.next_expected_keyword(Keyword::Where)?;
+            self.next_token();
+            let filter = self.parse_expression(Precedence::Lowest)?;
+            self.next_expected_token(Token::RightParen)?;
+            Some(Box::new(filter))
+        } else {
+            None
+        };
+
+        // OVER w 或者 OVER (window_spec)
+        let over = if self.next_if_keyword(Keyword::Over) {
+            let over = match self.next_token().clone() {
+                Token::Ident(name) => WindowType::Named(name),
+                Token::LeftParen => WindowType::Spec(self.parse_window_spec()?),
+                _ => return Err(self.unexpected("window name or LeftParen")),
+            };
+            Some(Box::new(over))
+        } else {
+            None
+        };
+
+        Ok(Expression::Function(Function {
+            name,
+            args,
+            distinct,
+            filter,
+            over,
+        }))
+    }
+
+    // 进入时 pre_token 是 (, 解析到 ) 为止
+    // ([name] [PARTITION BY a, b] [ORDER BY c] [frame])
+    fn parse_window_spec(&mut self) -> Result<WindowSpec> {
+        let name = match self.peek_token.clone() {
+            Token::Ident(name) => {
+                self.next_token();
+                Some(name)
             }
-            Token::KeyWord(Keyword::Like) => {
-                let precedence = match_precedence(&self.pre_token);
+            _ => None,
+        };
+
+        let partition_by = if self.next_if_keyword(Keyword::Partition) {
+            self.next_expected_keyword(Keyword::By)?;
+            let mut exprs = Vec::new();
+            loop {
                 self.next_token();
+                exprs.push(self.parse_expression(Precedence::Lowest)?);
 
-                Ok(Expression::Operation(Operation::Like(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::Like exp is not valid!"))
-                    })?),
-                )))
+                if !self.next_if_token(Token::Comma) {
+                    break;
+                }
             }
-            Token::Percent => {
-                let precedence = match_precedence(&self.pre_token);
-                self.next_token();
-                Ok(Expression::Operation(Operation::Modulo(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::Percent exp is not valid!"))
-                    })?),
-                )))
-            }
-            Token::Asterisk => {
-                let precedence = match_precedence(&self.pre_token);
-                self.next_token();
-                Ok(Expression::Operation(Operation::Multiply(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::Asterisk exp is not valid!"))
-                    })?),
-                )))
-            }
-            Token::Slash => {
-                let precedence = match_precedence(&self.pre_token);
-                self.next_token();
-                Ok(Expression::Operation(Operation::Divide(
-                    Box::new(exp),
-                    Box::new(self.parse_expression(precedence).map_err(|_| {
-                        Error::Parse(fmt_err!("Operation::Slash exp is not valid!"))
-                    })?),
-                )))
+            Some(exprs)
+        } else {
+            None
+        };
+        let order = self.parse_clause_order()?;
+        let frame = self.parse_window_frame()?;
+        self.next_expected_token(Token::RightParen)?;
+
+        Ok(WindowSpec {
+            name,
+            partition_by,
+            order,
+            frame,
+        })
+    }
+
+    // {ROWS | RANGE} start
+    // {ROWS | RANGE} BETWEEN start AND end
+    fn parse_window_frame(&mut self) -> Result<Option<WindowFrame>> {
+        let units = match self.peek_token {
+            Token::KeyWord(Keyword::Rows) => WindowFrameUnits::Rows,
+            Token::KeyWord(Keyword::Range) => WindowFrameUnits::Range,
+            _ => return Ok(None),
+        };
+        self.next_token();
+
+        let (start, end) = if self.next_if_keyword(Keyword::Between) {
+            let start = self.parse_window_frame_bound()?;
+            self.next_expected_keyword(Keyword::And)?;
+            (start, Some(self.parse_window_frame_bound()?))
+        } else {
+            (self.parse_window_frame_bound()?, None)
+        };
+
+        if start == WindowFrameBound::Following(None) {
+            return Err(self.error("frame start cannot be UNBOUNDED FOLLOWING".to_owned()));
+        }
+        if end == Some(WindowFrameBound::Preceding(None)) {
+            return Err(self.error("frame end cannot be UNBOUNDED PRECEDING".to_owned()));
+        }
+
+        Ok(Some(WindowFrame { units, start, end }))
+    }
+
+    // UNBOUNDED PRECEDING | n PRECEDING | CURRENT ROW | n FOLLOWING | UNBOUNDED FOLLOWING
+    fn parse_window_frame_bound(&mut self) -> Result<WindowFrameBound> {
+        if self.next_if_keyword(Keyword::Current) {
+            self.next_expected_keyword(Keyword::Row)?;
+            return Ok(WindowFrameBound::CurrentRow);
+        }
+
+        // 偏移量的优先级要高于 AND, 与 BETWEEN 的上下界相同
+        let offset = if self.next_if_keyword(Keyword::Unbounded) {
+            None
+        } else {
+            self.next_token();
+            Some(Box::new(self.parse_expression(Precedence::Equals)?))
+        };
+
+        match self.next_token() {
+            Token::KeyWord(Keyword::Preceding) => Ok(WindowFrameBound::Preceding(offset)),
+            Token::KeyWord(Keyword::Following) => Ok(WindowFrameBound::Following(offset)),
+            _ => Err(self.unexpected("PRECEDING or FOLLOWING")),
+        }
+    }
+
+    // $1 / :name / ?, 一条语句中只能使用同一种形式
+    fn parse_parameter(&mut self, parameter: Parameter) -> Result<Expression> {
+        match &self.parameter {
+            Some(first) if std::mem::discriminant(first) != std::mem::discriminant(&parameter) => {
+                return Err(self.error(format!(
+                    "cannot mix parameter {} with {} in one statement",
+                    parameter, first
+                )));
             }
-            // 如果 ( 是一个中缀运算符, 则是一个函数
-            Token::LeftParen => Ok(Expression::Function(
-                match exp {
-                    Expression::Literal(Literal::String(s)) => s,
-                    _ => {
-                        return Err(Error::Parse(fmt_err!(
-                            "Operation::LeftParen exp is not Literal::String"
-                        )));
-                    }
-                },
-                match self.peek_token {
-                    // SELECT FUNCTION_NAME(*)
-                    Token::Asterisk => {
-                        self.next_token();
-                        match self.peek_token {
-                            Token::RightParen => {
-                                self.next_token();
-                                vec![Expression::Literal(Literal::All)]
-                            }
-                            _ => {
-                                return Err(Error::Parse(fmt_err!(
-                                    "Operation::LeftParen exp is not Literal::String"
-                                )));
-                            }
-                        }
-                    }
-                    Token::RightParen => {
-                        // empty function args, like SUM(), NOW()
-                        self.next_token();
-                        vec![]
-                    }
-                    _ => match self.parse_expression_list()? {
-                        Some(exprs) => exprs,
-                        None => {
-                            return Err(Error::Parse(fmt_err!("Operation::LeftParen exp is None")));
-                        }
-                    },
-                },
-            )),
-            _ => Err(Error::Parse(fmt_err!(
-                "No infixOperatorFunc for {}",
-                self.pre_token
-            ))),
+            Some(_) => {}
+            None => self.parameter = Some(parameter.clone()),
         }
+
+        Ok(Expression::Parameter(parameter))
     }
 
     // (1, 3, 4)
-    fn parse_expression_list(&mut self) -> Result<Option<Vec<Expression>>> {
+    fn parse_expression_list(&mut self) -> Result<Vec<Expression>> {
         let mut exprs = Vec::new();
 
-        if self.peek_if_token(Token::RightParen) {
-            self.next_token();
-            return Ok(Some(exprs));
+        if self.next_if_token(Token::RightParen) {
+            return Ok(exprs);
         }
 
-        self.next_token();
-        exprs.push(
-            self.parse_expression(Precedence::Lowest)
-                .map_err(|_| Error::Parse(fmt_err!("Operation::LeftParen exp is not valid!")))?,
-        );
-
-        while self.peek_if_token(Token::Comma) {
+        loop {
             self.next_token();
+            exprs.push(self.parse_expression(Precedence::Lowest)?);
 
-            exprs.push(
-                self.parse_expression(Precedence::Lowest).map_err(|_| {
-                    Error::Parse(fmt_err!("parse_expression_list exp is not valid!"))
-                })?,
-            );
-        }
-
-        if !self.peek_if_token(Token::RightParen) {
-            return Ok(None);
+            match self.next_token() {
+                Token::Comma => continue,
+                Token::RightParen => break,
+                _ => return Err(self.unexpected("Comma or RightParen")),
+            }
         }
 
-        Ok(Some(exprs))
+        Ok(exprs)
     }
 
     fn peek_token_predence(&self) -> Precedence {
@@ -1554,6 +2235,14 @@ impl Parser {
     }
 }
 
+// 如果是 lexer 产生的非法 Token, 直接使用 lexer 的错误信息
+fn unexpected_token(expected: impl std::fmt::Display, found: &Token, span: Span) -> Error {
+    match found {
+        Token::Illegal(message) => ParseError::new(message.clone(), span).into(),
+        _ => ParseError::unexpected(expected, found, span).into(),
+    }
+}
+
 #[cfg(test)]
 pub mod test {
 
@@ -1566,6 +2255,12 @@ pub mod test {
                     let mut parser = Parser::new_parser($sql.to_owned());
                     let result = parser.parse_stmt();
                     assert_eq!(result, $except);
+
+                    // 输出的 SQL 重新解析后得到相同的语法树
+                    if let Ok(stmt) = result {
+                        let sql = stmt.to_string();
+                        assert_eq!(parser.update(&sql).parse_stmt(), Ok(stmt), "{}", sql);
+                    }
                 }
             )*
         };
@@ -1600,43 +2295,1239 @@ pub mod test {
         });
     }
 
+    // 每条 SQL 都解析失败, 并且错误信息与预期一致
+    fn assert_parse_errors(cases: &[(&str, &str)]) {
+        let mut parser = Parser::new_parser("".to_owned());
+        for (sql, message) in cases {
+            match parser.update(sql).parse_stmt() {
+                Err(Error::Parse(e)) => assert_eq!(e.message, *message, "{}", sql),
+                r => panic!("expected parse error for {} but get: {:?}", sql, r),
+            }
+        }
+    }
+
+    // 输出的 SQL 与预期一致, 并且重新解析后得到相同的语法树
+    fn assert_unparse(cases: &[(&str, &str)]) {
+        let mut parser = Parser::new_parser("".to_owned());
+        for (sql, expected) in cases {
+            let stmt = parser.update(sql).parse_stmt().unwrap();
+            assert_eq!(stmt.to_string(), *expected);
+            assert_eq!(parser.update(expected).parse_stmt(), Ok(stmt));
+        }
+    }
+
+    #[test]
+    fn parse_error_location_test() {
+        init();
+        let sql = "SELECT id, name\nFROM person\nWHERE id = = 1;";
+        let mut parser = Parser::new_parser(sql.to_owned());
+        let err = match parser.parse_stmt() {
+            Err(Error::Parse(e)) => e,
+            r => panic!("expected parse error but get: {:?}", r),
+        };
+
+        assert_eq!(err.span.start.line, 3);
+        assert_eq!(err.span.start.column, 12);
+        assert_eq!(err.span.start.offset, 39);
+        assert_eq!(
+            err.to_string(),
+            "expected expression, found Token: Equal at line 3 col 12"
+        );
+        assert_eq!(
+            err.render(sql),
+            "error: expected expression, found Token: Equal at line 3 col 12\n  \
+             |\n3 | WHERE id = = 1;\n  |            ^"
+        );
+
+        let sql = "CREATE TABLE person (id int,\n    name strin);";
+        let err = match parser.update(sql).parse_stmt() {
+            Err(Error::Parse(e)) => e,
+            r => panic!("expected parse error but get: {:?}", r),
+        };
+        assert_eq!(
+            err.render(sql),
+            "error: expected data type, found Token: Ident: strin at line 2 col 10\n  \
+             |\n2 |     name strin);\n  |          ^^^^^"
+        );
+
+        // 整行 VALUES 被标出
+        let sql = "INSERT INTO t (a, b) VALUES (1, 2), (3)";
+        let err = match parser.update(sql).parse_stmt() {
+            Err(Error::Parse(e)) => e,
+            r => panic!("expected parse error but get: {:?}", r),
+        };
+        assert_eq!(
+            err.render(sql),
+            "error: VALUES row has 1 values, expected 2 at line 1 col 37\n  \
+             |\n1 | INSERT INTO t (a, b) VALUES (1, 2), (3)\n  |                                     ^^^"
+        );
+    }
+
+    #[test]
+    fn parse_lexer_error_test() {
+        init();
+        let sql = "SELECT name FROM person\nWHERE name = 'tang;";
+        let err = match Parser::new_parser(sql.to_owned()).parse_stmt() {
+            Err(Error::Parse(e)) => e,
+            r => panic!("expected parse error but get: {:?}", r),
+        };
+        assert_eq!(
+            err.render(sql),
+            "error: unterminated string literal at line 2 col 14\n  \
+             |\n2 | WHERE name = 'tang;\n  |              ^^^^^^"
+        );
+    }
+
+    #[test]
+    fn parse_number_literal_test() {
+        init();
+        let mut parser = Parser::new_parser("".to_owned());
+        let result = vec![
+            ("SELECT 1_000", Literal::Int(1000)),
+            ("SELECT 0x1F", Literal::Int(31)),
+            ("SELECT .5", Literal::Float(0.5)),
+            ("SELECT 1e3", Literal::Float(1000.0)),
+            ("SELECT 9223372036854775807", Literal::Int(i64::MAX)),
+            // 超出 i64 范围的整数会被提升为浮点数
+            (
+                "SELECT 9223372036854775808",
+                Literal::Float(9223372036854775808.0),
+            ),
+            (
+                "SELECT 0xFFFFFFFFFFFFFFFF",
+                Literal::Float(18446744073709551615.0),
+            ),
+            ("SELECT infinity", Literal::Float(f64::INFINITY)),
+        ];
+
+        for (sql, literal) in result {
+            match parser.update(sql).parse_stmt() {
+                Ok(Statement::Select(s)) => {
+                    assert_eq!(
+                        s.selects,
+                        vec![SelectItem::Expr(Expression::Literal(literal), None)]
+                    )
+                }
+                r => panic!("{}: unexpected result {:?}", sql, r),
+            }
+        }
+
+        match parser.update("SELECT NaN").parse_stmt() {
+            Ok(Statement::Select(s)) => match &s.selects[0] {
+                SelectItem::Expr(expr, _) => match expr.unspanned() {
+                    Expression::Literal(Literal::Float(f)) => assert!(f.is_nan()),
+                    e => panic!("expected NaN but get: {:?}", e),
+                },
+                e => panic!("expected NaN but get: {:?}", e),
+            },
+            r => panic!("unexpected result {:?}", r),
+        }
+
+        match parser.update("SELECT 1.2.3").parse_stmt() {
+            Err(Error::Parse(e)) => {
+                assert_eq!(
+                    e.to_string(),
+                    "invalid number literal 1.2.3 at line 1 col 8"
+                )
+            }
+            r => panic!("expected parse error but get: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn parse_statements_test() {
+        init();
+        let sql = r#"
+            ;; -- 空语句会被忽略
+            BEGIN;
+            DROP TABLE person;;
+            COMMIT;
+            SHOW TABLES
+        "#;
+        let (stmts, spans): (Vec<_>, Vec<_>) = Parser::new_parser(sql.to_owned())
+            .parse_statements()
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.node, s.span))
+            .unzip();
+        assert_eq!(
+            stmts,
+            vec![
+                Statement::Begin(BeginStmt {
+                    is_readonly: false,
+                    isolation_level: None,
+                    version: None,
+                }),
+                Statement::DropTable(DropTableStmt {
+                    table_names: vec!["person".to_owned()],
+                    if_exists: false,
+                    cascade: false,
+                }),
+                Statement::Commit,
+                Statement::ShowTables,
+            ]
+        );
+
+        // 语句的位置不包括结尾的 ;
+        let texts: Vec<&str> = spans
+            .iter()
+            .map(|span| &sql[span.start.offset..span.end.offset])
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["BEGIN", "DROP TABLE person", "COMMIT", "SHOW TABLES"]
+        );
+        assert_eq!((spans[1].start.line, spans[1].start.column), (4, 13));
+
+        let mut parser = Parser::new_parser(" ;  ".to_owned());
+        assert_eq!(parser.parse_statements(), Ok(vec![]));
+
+        let sql = "BEGIN;\nDROP TABLE person;\nDROP TABLE;\nCOMMIT;";
+        match parser.update(sql).parse_statements() {
+            Err(Error::Parse(e)) => {
+                assert_eq!(e.statement_index, Some(2));
+                assert_eq!(
+                    e.to_string(),
+                    "expected Ident, found Token: Semicolon at line 3 col 11 (statement index 2)"
+                );
+            }
+            r => panic!("expected parse error but get: {:?}", r),
+        }
+
+        // 语句之间必须用 ; 分隔
+        match parser.update("COMMIT ROLLBACK").parse_statements() {
+            Err(Error::Parse(e)) => {
+                assert_eq!(e.statement_index, Some(0));
+                assert_eq!(e.span.start.column, 8);
+            }
+            r => panic!("expected parse error but get: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn parse_predicate_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "SELECT a IN ()",
+                "expected expression, found Token: RightParen",
+            ),
+            (
+                "SELECT a NOT 1",
+                "expected IN, BETWEEN or LIKE, found Token: Integer 1",
+            ),
+            (
+                "SELECT a IS 1",
+                "expected keyword: Null, found Token: Integer 1",
+            ),
+            (
+                "SELECT a BETWEEN 1 OR 2",
+                "expected keyword: And, found Token: keyword: Or",
+            ),
+        ]);
+
+        // DEFAULT 后面的 NOT NULL 是列约束
+        let stmt = Parser::new_parser("".to_owned())
+            .update("CREATE TABLE t (a BOOL DEFAULT TRUE NOT NULL, b BOOL DEFAULT (1 = 1))")
+            .parse_stmt()
+            .unwrap();
+        assert_eq!(
+            stmt.to_string(),
+            "CREATE TABLE t (a BOOLEAN NOT NULL DEFAULT TRUE, b BOOLEAN DEFAULT (1 = 1))"
+        );
+    }
+
+    #[test]
+    fn parse_case_cast_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "SELECT CASE a END",
+                "expected keyword: When, found Token: keyword: End",
+            ),
+            (
+                "SELECT CASE WHEN 1 THEN 2",
+                "expected keyword: End, found Token: Eof",
+            ),
+            (
+                "SELECT CAST(1 AS foo)",
+                "expected data type, found Token: Ident: foo",
+            ),
+            ("SELECT 1::", "expected data type, found Token: Eof"),
+        ]);
+    }
+
+    #[test]
+    fn parse_subquery_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "SELECT (SELECT 1",
+                "expected Token: RightParen, found Token: Eof",
+            ),
+            (
+                "SELECT EXISTS a",
+                "expected Token: LeftParen, found Token: Ident: a",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_with_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "WITH a AS SELECT 1",
+                "expected Token: LeftParen, found Token: keyword: Select",
+            ),
+            (
+                "WITH a AS (DELETE FROM t) SELECT 1",
+                "expected keyword: Select, found Token: keyword: Delete",
+            ),
+            (
+                "WITH a AS (SELECT 1)",
+                "expected keyword: Select, found Token: Eof",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_set_operation_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "SELECT 1 UNION",
+                "expected keyword: Select, found Token: Eof",
+            ),
+            (
+                "SELECT 1 LIMIT 1 UNION SELECT 2",
+                "expected end of statement, found Token: keyword: Union",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_distinct_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "SELECT DISTINCT ON () a",
+                "expected expression, found Token: RightParen",
+            ),
+            (
+                "SELECT COUNT(DISTINCT)",
+                "expected expression, found Token: RightParen",
+            ),
+            (
+                "SELECT COUNT(DISTINCT *)",
+                "expected expression, found Token: Asterisk",
+            ),
+            (
+                "SELECT SUM(a) FILTER (a > 1)",
+                "expected keyword: Where, found Token: Ident: a",
+            ),
+            ("SELECT t.", "expected Ident, found Token: Eof"),
+        ]);
+    }
+
+    #[test]
+    fn parse_window_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "SELECT RANK() OVER",
+                "expected window name or LeftParen, found Token: Eof",
+            ),
+            (
+                "SELECT RANK() OVER (PARTITION a)",
+                "expected keyword: By, found Token: Ident: a",
+            ),
+            (
+                "SELECT SUM(a) OVER (ROWS 1)",
+                "expected PRECEDING or FOLLOWING, found Token: RightParen",
+            ),
+            (
+                "SELECT SUM(a) OVER (ROWS UNBOUNDED FOLLOWING)",
+                "frame start cannot be UNBOUNDED FOLLOWING",
+            ),
+            (
+                "SELECT SUM(a) OVER (ROWS BETWEEN CURRENT ROW AND UNBOUNDED PRECEDING)",
+                "frame end cannot be UNBOUNDED PRECEDING",
+            ),
+            (
+                "SELECT SUM(a) OVER w FROM t WINDOW w (ORDER BY a)",
+                "expected keyword: As, found Token: LeftParen",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_join_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "SELECT * FROM (t, u)",
+                "expected Token: RightParen, found Token: Comma",
+            ),
+            (
+                "SELECT * FROM a JOIN b",
+                "expected ON or USING, found Token: Eof",
+            ),
+            (
+                "SELECT * FROM a JOIN b USING a",
+                "expected Token: LeftParen, found Token: Ident: a",
+            ),
+            (
+                "SELECT * FROM a NATURAL CROSS JOIN b",
+                "expected keyword: Join, found Token: keyword: Cross",
+            ),
+            (
+                "SELECT * FROM a CROSS JOIN b ON a.id = b.id",
+                "expected end of statement, found Token: keyword: On",
+            ),
+            (
+                "SELECT * FROM a OUTER JOIN b",
+                "expected end of statement, found Token: keyword: Outer",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_parameter_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "SELECT * FROM t WHERE a = $1 AND b = ?",
+                "cannot mix parameter ? with $1 in one statement",
+            ),
+            (
+                "SELECT :a, $1",
+                "cannot mix parameter $1 with :a in one statement",
+            ),
+            ("SELECT $0", "invalid parameter $0"),
+            (
+                "SELECT ? ?",
+                "expected end of statement, found Token: Question",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_prepare_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "PREPARE q SELECT 1",
+                "expected keyword: As, found Token: keyword: Select",
+            ),
+            (
+                "PREPARE q AS CREATE TABLE t (a INT)",
+                "expected SELECT, INSERT, UPDATE or DELETE, found Token: keyword: Create",
+            ),
+            (
+                "PREPARE q AS SELECT ?, $1",
+                "cannot mix parameter $1 with ? in one statement",
+            ),
+            (
+                "EXECUTE q (1",
+                "expected Comma or RightParen, found Token: Eof",
+            ),
+            (
+                "EXECUTE q 1",
+                "expected end of statement, found Token: Integer 1",
+            ),
+            ("DEALLOCATE", "expected Ident, found Token: Eof"),
+        ]);
+    }
+
+    #[test]
+    fn parse_insert_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "INSERT INTO t (a) SET a = 1",
+                "expected VALUES, SELECT or DEFAULT VALUES, found Token: keyword: Set",
+            ),
+            (
+                "INSERT INTO t DEFAULT",
+                "expected keyword: Values, found Token: Eof",
+            ),
+            (
+                "INSERT INTO t VALUES (1, DEFAULT + 1)",
+                "expected Comma or RightParen, found Token: Add",
+            ),
+            (
+                "INSERT INTO t VALUES (1) ON CONFLICT DO UPDATE SET a = 1",
+                "ON CONFLICT DO UPDATE requires conflict columns",
+            ),
+            (
+                "INSERT INTO t VALUES (1) ON CONFLICT (a) DO DELETE",
+                "expected NOTHING or UPDATE, found Token: keyword: Delete",
+            ),
+            (
+                "INSERT INTO t VALUES (1) ON CONFLICT (a) DO UPDATE SET a = 1, a = 2",
+                "Duplicate values given for column a",
+            ),
+            (
+                "INSERT INTO t (a, b) VALUES (1)",
+                "VALUES row has 1 values, expected 2",
+            ),
+            (
+                "INSERT INTO t VALUES (1), (1, 2)",
+                "VALUES row has 2 values, expected 1",
+            ),
+            (
+                "INSERT INTO t (a, b, a) VALUES (1, 2, 3)",
+                "column a specified more than once",
+            ),
+            (
+                "INSERT INTO t VALUES (1) RETURNING",
+                "expected expression, found Token: Eof",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_update_delete_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "UPDATE t SET a = 1, b = 2, a = 3",
+                "Duplicate values given for column a",
+            ),
+            ("DELETE FROM t USING", "expected Ident, found Token: Eof"),
+            (
+                "DELETE FROM t LIMIT 1 OFFSET 1",
+                "expected end of statement, found Token: keyword: Offset",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_table_constraint_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "CREATE TABLE t (a INT PRIMARY KEY, b INT, PRIMARY KEY (a, b))",
+                "multiple primary keys for table t are not allowed",
+            ),
+            (
+                "CREATE TABLE t (a INT, b INT, a TEXT)",
+                "column a specified more than once",
+            ),
+            (
+                "CREATE TABLE t (a INT CONSTRAINT c CHECK (a > 0), CONSTRAINT c UNIQUE (a))",
+                "constraint c specified more than once",
+            ),
+            (
+                "CREATE TABLE t (a INT CONSTRAINT c UNIQUE)",
+                "expected keyword: Check, found Token: keyword: Unique",
+            ),
+            (
+                "CREATE TABLE t (a INT, FOREIGN KEY (a) REFERENCES u (x, y))",
+                "foreign key references 2 columns, expected 1",
+            ),
+            (
+                "CREATE TABLE t (a INT REFERENCES u ON DELETE NOTHING)",
+                "expected CASCADE, SET NULL or RESTRICT, found Token: keyword: Nothing",
+            ),
+            (
+                "CREATE TABLE t (a INT, CONSTRAINT c)",
+                "expected table constraint, found Token: RightParen",
+            ),
+            (
+                "CREATE TABLE t (a INT CHECK a > 0)",
+                "expected Token: LeftParen, found Token: Ident: a",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_drop_index_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "CREATE INDEX IF NOT EXISTS ON t (a)",
+                "expected Ident, found Token: keyword: On",
+            ),
+            (
+                "CREATE INDEX i ON t (a) WHERE",
+                "expected expression, found Token: Eof",
+            ),
+            (
+                "DROP TABLE IF a",
+                "expected keyword: Exists, found Token: Ident: a",
+            ),
+            (
+                "DROP SEQUENCE s",
+                "expected TABLE, VIEW, INDEX or DATABASE, found Token: Ident: SEQUENCE",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_view_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "CREATE OR REPLACE MATERIALIZED VIEW v AS SELECT 1",
+                "materialized view cannot be created with OR REPLACE",
+            ),
+            (
+                "CREATE VIEW v AS VALUES (1)",
+                "expected SELECT or WITH, found Token: keyword: Values",
+            ),
+            (
+                "CREATE VIEW v (a, b SELECT 1",
+                "expected Comma or RightParen, found Token: keyword: Select",
+            ),
+            (
+                "REFRESH VIEW v",
+                "expected keyword: Materialized, found Token: keyword: View",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_database_show_error_test() {
+        init();
+        assert_parse_errors(&[
+            ("USE", "expected Ident, found Token: Eof"),
+            ("TRUNCATE TABLE a,", "expected Ident, found Token: Eof"),
+            ("SHOW COLUMNS t", "expected keyword: From, found Token: Ident: t"),
+            (
+                "SHOW VARIABLES LIKE tx",
+                "expected String, found Token: Ident: tx",
+            ),
+            (
+                "SHOW GLOBAL TABLES",
+                "expected keyword: Variables, found Token: keyword: Tables",
+            ),
+            (
+                "SHOW USERS",
+                "expected TABLES, DATABASES, COLUMNS, INDEXES, CREATE or VARIABLES, found Token: Ident: USERS",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parse_transaction_error_test() {
+        init();
+        assert_parse_errors(&[
+            (
+                "START READ ONLY",
+                "expected keyword: Transaction, found Token: keyword: Read",
+            ),
+            (
+                "BEGIN READ ONLY READ WRITE",
+                "conflicting or repeated READ ONLY / READ WRITE",
+            ),
+            (
+                "BEGIN READ ONLY, READ ONLY",
+                "conflicting or repeated READ ONLY / READ WRITE",
+            ),
+            (
+                "BEGIN ISOLATION LEVEL SERIALIZABLE ISOLATION LEVEL READ COMMITTED",
+                "ISOLATION LEVEL specified more than once",
+            ),
+            (
+                "BEGIN AS OF SYSTEM TIME 1 AS OF SYSTEM TIME 2",
+                "AS OF SYSTEM TIME specified more than once",
+            ),
+            (
+                "BEGIN AS OF SYSTEM TIME 'garbage'",
+                "invalid system time 'garbage'",
+            ),
+            (
+                "BEGIN ISOLATION SERIALIZABLE",
+                "expected keyword: Level, found Token: keyword: Serializable",
+            ),
+            (
+                "BEGIN AS OF SYSTEM TIME now",
+                "expected Integer or String, found Token: Ident: now",
+            ),
+            ("ROLLBACK TO", "expected Ident, found Token: Eof"),
+            ("SAVEPOINT", "expected Ident, found Token: Eof"),
+        ]);
+    }
+
+    #[test]
+    fn parse_trailing_garbage_test() {
+        init();
+        let mut parser = Parser::new_parser("".to_owned());
+        for (sql, column) in [
+            ("SELECT 1 FOO BAR", 14),
+            ("SELECT a FROM t WHERE a = 1 b", 29),
+            ("DROP TABLE a b;", 14),
+            ("SHOW TABLES x", 13),
+            ("CREATE TABLE t (id int) x", 25),
+        ] {
+            match parser.update(sql).parse_stmt() {
+                Err(Error::Parse(e)) => {
+                    assert!(
+                        e.message.starts_with("expected end of statement"),
+                        "{}",
+                        sql
+                    );
+                    assert_eq!(e.span.start.column, column, "{}", sql);
+                }
+                r => panic!("{}: expected parse error but get: {:?}", sql, r),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_spanned_stmt_test() {
+        init();
+        let sql = "  DROP TABLE person;";
+        let stmt = Parser::new_parser(sql.to_owned())
+            .parse_spanned_stmt()
+            .unwrap();
+
+        assert_eq!(
+            stmt.node,
+            Statement::DropTable(DropTableStmt {
+                table_names: vec!["person".to_owned()],
+                if_exists: false,
+                cascade: false,
+            })
+        );
+        assert_eq!(stmt.span.start.offset, 2);
+        assert_eq!(stmt.span.end.offset, 19);
+        assert_eq!(
+            &sql[stmt.span.start.offset..stmt.span.end.offset],
+            "DROP TABLE person"
+        );
+    }
+
+    #[test]
+    fn unparse_expression_test() {
+        init();
+        // 按优先级补上必要的括号
+        assert_unparse(&[
+            ("select 1 + 2 * 3", "SELECT 1 + 2 * 3"),
+            ("select (1 + 2) * 3", "SELECT (1 + 2) * 3"),
+            (
+                "select 1 - (2 - 3), (1 - 2) - 3",
+                "SELECT 1 - (2 - 3), 1 - 2 - 3",
+            ),
+            ("select a = 1 and b = 2 or c", "SELECT a = 1 AND b = 2 OR c"),
+            ("select a and (b or c)", "SELECT a AND (b OR c)"),
+            ("select not a = 1, !a = 1", "SELECT NOT a = 1, (NOT a) = 1"),
+            ("select not (a and b)", "SELECT NOT (a AND b)"),
+            (
+                "select -(-1), -(1 + 2), 7 % 3",
+                "SELECT -(-1), -(1 + 2), 7 % 3",
+            ),
+            (
+                "select ~a, ~ (1 + 2), -~1, ~-a",
+                "SELECT ~a, ~(1 + 2), -~1, ~-a",
+            ),
+            (
+                "select 1.0, 1e20, 0x10, 'it''s'",
+                "SELECT 1.0, 1e20, 16, 'it''s'",
+            ),
+            ("select name like 'a%'", "SELECT name LIKE 'a%'"),
+        ]);
+    }
+
+    #[test]
+    fn unparse_stmt_test() {
+        init();
+        assert_unparse(&[
+            (
+                r#"select "from", "a b" as "select", t.id from "order" t"#,
+                r#"SELECT "from", "a b" AS "select", t.id FROM "order" AS t"#,
+            ),
+            (
+                "select COUNT(*) from a left outer join b on a.id = b.id order by 1 asc, 2 desc offset 1 limit 2",
+                "SELECT COUNT(*) FROM a LEFT JOIN b ON a.id = b.id ORDER BY 1, 2 DESC LIMIT 2 OFFSET 1",
+            ),
+            (
+                "create table t (id int primary key, name varchar(10) not null default 'x')",
+                "CREATE TABLE t (id INT PRIMARY KEY, name VARCHAR(10) NOT NULL DEFAULT 'x')",
+            ),
+            (
+                "set global transaction isolation level repeatable read",
+                "SET GLOBAL TRANSACTION ISOLATION LEVEL REPEATABLE READ",
+            ),
+            ("alter table t add index (a, b)", "ALTER TABLE t ADD INDEX (a, b)"),
+            ("explain delete from t where a > 1", "EXPLAIN DELETE FROM t WHERE a > 1"),
+        ]);
+    }
+
+    #[test]
+    fn unparse_predicate_test() {
+        init();
+        assert_unparse(&[(
+            "select a not in (1, 2), (a = b) is not null, a between -1 and (b and c)",
+            "SELECT a NOT IN (1, 2), a = b IS NOT NULL, a BETWEEN -1 AND (b AND c)",
+        )]);
+    }
+
+    #[test]
+    fn unparse_case_cast_test() {
+        init();
+        assert_unparse(&[(
+            "select case when a then b end, a::int, (a + 1)::double",
+            "SELECT CASE WHEN a THEN b END, CAST(a AS INT), CAST(a + 1 AS DOUBLE)",
+        )]);
+    }
+
+    #[test]
+    fn unparse_subquery_test() {
+        init();
+        assert_unparse(&[
+            (
+                "select * from (select a from t) x where a in (select b from u) and not exists (select 1)",
+                "SELECT * FROM (SELECT a FROM t) AS x WHERE a IN (SELECT b FROM u) AND NOT EXISTS (SELECT 1)",
+            ),
+            (
+                "select ((select 1)) + 1, a not in (select b from u)",
+                "SELECT (SELECT 1) + 1, a NOT IN (SELECT b FROM u)",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn unparse_with_test() {
+        init();
+        assert_unparse(&[
+            (
+                "with recursive a(x, \"y z\") as (select 1, 2), b as (with c as (select 3) select * from c) select * from a",
+                "WITH RECURSIVE a (x, \"y z\") AS (SELECT 1, 2), b AS (WITH c AS (SELECT 3) SELECT * FROM c) SELECT * FROM a",
+            ),
+            (
+                "with recursive r (n) as (select 1 union all select n + 1 from r where n < 10) select * from r",
+                "WITH RECURSIVE r (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM r WHERE n < 10) SELECT * FROM r",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn unparse_set_operation_test() {
+        init();
+        assert_unparse(&[
+            (
+                "select 1 union all select 2 except select 3 intersect select 4",
+                "SELECT 1 UNION ALL SELECT 2 EXCEPT SELECT 3 INTERSECT SELECT 4",
+            ),
+            (
+                "(select 1 union select 2) intersect select 3 order by 1 desc limit 1",
+                "(SELECT 1 UNION SELECT 2) INTERSECT SELECT 3 ORDER BY 1 DESC LIMIT 1",
+            ),
+            (
+                "select 1 except (select 2 order by 1 limit 1)",
+                "SELECT 1 EXCEPT (SELECT 2 ORDER BY 1 LIMIT 1)",
+            ),
+            ("(select 1)", "(SELECT 1)"),
+            (
+                "select * from t where a in (select 1 union select 2)",
+                "SELECT * FROM t WHERE a IN (SELECT 1 UNION SELECT 2)",
+            ),
+        ]);
+
+        // 手动构造的语法树输出时需要补上括号
+        let select = |n| SelectStmt {
+            distinct: None,
+            selects: vec![SelectItem::Expr(Expression::Literal(Literal::Int(n)), None)],
+            from: None,
+            wheres: None,
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        };
+        let union = |left, right| SetExpr::SetOperation {
+            op: SetOperator::Union,
+            all: false,
+            left: Box::new(left),
+            right: Box::new(right),
+        };
+        let mut limited = select(3);
+        limited.limit = Some(Expression::Literal(Literal::Int(1)));
+        let body = union(
+            SetExpr::Select(Box::new(select(1))),
+            union(
+                SetExpr::Select(Box::new(select(2))),
+                SetExpr::Select(Box::new(limited)),
+            ),
+        );
+        assert_eq!(
+            Query {
+                with: None,
+                body,
+                order: None,
+                offset: None,
+                limit: None,
+            }
+            .to_string(),
+            "SELECT 1 UNION (SELECT 2 UNION (SELECT 3 LIMIT 1))"
+        );
+    }
+
+    #[test]
+    fn unparse_distinct_test() {
+        init();
+        assert_unparse(&[
+            ("select all a, *, t.*", "SELECT a, *, t.*"),
+            (
+                "select distinct \"my t\".*, t.a + 1 b from t",
+                "SELECT DISTINCT \"my t\".*, t.a + 1 AS b FROM t",
+            ),
+            (
+                "select distinct on (a, b + 1) count(distinct a), sum(b) filter (where b > 0 and c)",
+                "SELECT DISTINCT ON (a, b + 1) count(DISTINCT a), sum(b) FILTER (WHERE b > 0 AND c)",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn unparse_window_test() {
+        init();
+        assert_unparse(&[
+            (
+                "select lag(a, 1) over (w range between (1 and 2) preceding and unbounded following) from t window w as (partition by b, c)",
+                "SELECT lag(a, 1) OVER (w RANGE BETWEEN (1 AND 2) PRECEDING AND UNBOUNDED FOLLOWING) FROM t WINDOW w AS (PARTITION BY b, c)",
+            ),
+            (
+                "select sum(a) filter (where a > 0) over w, count(*) over () from t window w as (order by b desc rows 2 preceding), x as (w)",
+                "SELECT sum(a) FILTER (WHERE a > 0) OVER w, count(*) OVER () FROM t WINDOW w AS (ORDER BY b DESC ROWS 2 PRECEDING), x AS (w)",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn unparse_join_test() {
+        init();
+        assert_unparse(&[
+            (
+                "select * from a, b join c using (id, \"my id\"), d",
+                "SELECT * FROM a CROSS JOIN (b JOIN c USING (id, \"my id\")) CROSS JOIN d",
+            ),
+            (
+                "select * from (a natural left outer join b) full outer join c on a.id = c.id cross join d",
+                "SELECT * FROM a NATURAL LEFT JOIN b FULL JOIN c ON a.id = c.id CROSS JOIN d",
+            ),
+            (
+                "select * from a inner join (b right join (select 1) x on true) on a.id = b.id",
+                "SELECT * FROM a JOIN (b RIGHT JOIN (SELECT 1) AS x ON TRUE) ON a.id = b.id",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn unparse_parameter_test() {
+        init();
+        assert_unparse(&[
+            (
+                "select * from t where a = ? and b in (?, ?) limit ?",
+                "SELECT * FROM t WHERE a = ? AND b IN (?, ?) LIMIT ?",
+            ),
+            (
+                "select :id, -:Name::int from t where id = :id",
+                "SELECT :id, -CAST(:Name AS INT) FROM t WHERE id = :id",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn unparse_prepare_test() {
+        init();
+        assert_unparse(&[
+            (
+                "prepare q as select * from t where id = $1 and name = $2",
+                "PREPARE q AS SELECT * FROM t WHERE id = $1 AND name = $2",
+            ),
+            ("prepare q as (select ?)", "PREPARE q AS (SELECT ?)"),
+            ("execute q (1 + 1, 'a')", "EXECUTE q(1 + 1, 'a')"),
+            ("execute q ()", "EXECUTE q"),
+            ("deallocate prepare q", "DEALLOCATE q"),
+            ("deallocate all", "DEALLOCATE ALL"),
+        ]);
+    }
+
+    #[test]
+    fn unparse_insert_test() {
+        init();
+        assert_unparse(&[
+            (
+                "insert into t (a, b) values (1, 'x'), (2, null)",
+                "INSERT INTO t (a, b) VALUES (1, 'x'), (2, NULL)",
+            ),
+            (
+                "insert into t values (default, 1), (2, default) returning *, a + 1 as b",
+                "INSERT INTO t VALUES (DEFAULT, 1), (2, DEFAULT) RETURNING *, a + 1 AS b",
+            ),
+            ("insert into t default values returning id", "INSERT INTO t DEFAULT VALUES RETURNING id"),
+            (
+                "insert into t (a) with s as (select 1) select * from s union select 2 on conflict do nothing",
+                "INSERT INTO t (a) WITH s AS (SELECT 1) SELECT * FROM s UNION SELECT 2 ON CONFLICT DO NOTHING",
+            ),
+            (
+                "insert into t select * from u on conflict (a, b) do update set c = excluded.c + t.c, d = 1 where t.d < 1",
+                "INSERT INTO t SELECT * FROM u ON CONFLICT (a, b) DO UPDATE SET c = excluded.c + t.c, d = 1 WHERE t.d < 1",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn unparse_update_delete_test() {
+        init();
+        assert_unparse(&[
+            ("update t set b = 1, a = 2", "UPDATE t SET b = 1, a = 2"),
+            (
+                "update t set b = u.b from u join v on u.id = v.id where t.id = u.id returning t.*",
+                "UPDATE t SET b = u.b FROM u JOIN v ON u.id = v.id WHERE t.id = u.id RETURNING t.*",
+            ),
+            (
+                "delete from t using u, v where t.id = u.id order by id desc limit 100 returning id",
+                "DELETE FROM t USING u CROSS JOIN v WHERE t.id = u.id ORDER BY id DESC LIMIT 100 RETURNING id",
+            ),
+            ("update t set a = 1 order by b limit 1", "UPDATE t SET a = 1 ORDER BY b LIMIT 1"),
+        ]);
+    }
+
+    #[test]
+    fn unparse_table_constraint_test() {
+        init();
+        assert_unparse(&[
+            ("create table t (primary key (a))", "CREATE TABLE t (PRIMARY KEY (a))"),
+            (
+                "create table t (a int check (a > 0) constraint lt check (a < 10))",
+                "CREATE TABLE t (a INT CHECK (a > 0) CONSTRAINT lt CHECK (a < 10))",
+            ),
+            (
+                "create table t (a int check (a > 0 and a < 10) references u (id) on delete set null, b int references v)",
+                "CREATE TABLE t (a INT CHECK (a > 0 AND a < 10) REFERENCES u (id) ON DELETE SET NULL, b INT REFERENCES v)",
+            ),
+            (
+                "create table t (primary key (a, b), a int, b int, constraint u_b unique (b), check (a != b), \
+                 constraint fk foreign key (a, b) references u (x, y) on delete cascade)",
+                "CREATE TABLE t (a INT, b INT, PRIMARY KEY (a, b), CONSTRAINT u_b UNIQUE (b), CHECK (a != b), \
+                 CONSTRAINT fk FOREIGN KEY (a, b) REFERENCES u (x, y) ON DELETE CASCADE)",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn unparse_drop_index_test() {
+        init();
+        assert_unparse(&[
+            (
+                "create table if not exists t (a int)",
+                "CREATE TABLE IF NOT EXISTS t (a INT)",
+            ),
+            (
+                "drop table if exists a, \"b c\" cascade",
+                "DROP TABLE IF EXISTS a, \"b c\" CASCADE",
+            ),
+            ("drop index i", "DROP INDEX i"),
+            (
+                "create unique index if not exists i on t (a, lower(b), a * 2, (c)) where a > 0",
+                "CREATE UNIQUE INDEX IF NOT EXISTS i ON t (a, lower(b), (a * 2), c) WHERE a > 0",
+            ),
+            ("create index on t (a)", "CREATE INDEX ON t (a)"),
+        ]);
+    }
+
+    #[test]
+    fn unparse_view_test() {
+        init();
+        assert_unparse(&[
+            (
+                "create or replace view v (a, \"b c\") as select x, y from t where x > 1",
+                "CREATE OR REPLACE VIEW v (a, \"b c\") AS SELECT x, y FROM t WHERE x > 1",
+            ),
+            (
+                "create materialized view v as with s as (select 1) select * from s union select 2",
+                "CREATE MATERIALIZED VIEW v AS WITH s AS (SELECT 1) SELECT * FROM s UNION SELECT 2",
+            ),
+            (
+                "create view v as (select 1) order by 1",
+                "CREATE VIEW v AS (SELECT 1) ORDER BY 1",
+            ),
+            ("refresh materialized view v", "REFRESH MATERIALIZED VIEW v"),
+            (
+                "drop materialized view if exists v, w cascade",
+                "DROP MATERIALIZED VIEW IF EXISTS v, w CASCADE",
+            ),
+            ("drop view v", "DROP VIEW v"),
+        ]);
+    }
+
+    #[test]
+    fn unparse_database_show_test() {
+        init();
+        assert_unparse(&[
+            (
+                "create database if not exists db",
+                "CREATE DATABASE IF NOT EXISTS db",
+            ),
+            ("drop database \"my db\"", "DROP DATABASE \"my db\""),
+            ("use db", "USE db"),
+            ("truncate a, b", "TRUNCATE TABLE a, b"),
+            ("show columns from t", "SHOW COLUMNS FROM t"),
+            ("show index from t", "SHOW INDEXES FROM t"),
+            ("show create table t", "SHOW CREATE TABLE t"),
+            (
+                "show session variables like 'tx%'",
+                "SHOW VARIABLES LIKE 'tx%'",
+            ),
+            ("show global variables", "SHOW GLOBAL VARIABLES"),
+        ]);
+    }
+
+    #[test]
+    fn non_reserved_keyword_test() {
+        init();
+        // 非保留关键字可以作为表名, 列名和函数名, 输出时加上双引号
+        assert_unparse(&[
+            (
+                "select start, Release + 1 from t where start > 0",
+                "SELECT \"start\", \"release\" + 1 FROM t WHERE \"start\" > 0",
+            ),
+            (
+                "select start.a, release.* from start, release",
+                "SELECT \"start\".a, \"release\".* FROM \"start\" CROSS JOIN \"release\"",
+            ),
+            (
+                "create table release (start int, savepoint text)",
+                "CREATE TABLE \"release\" (\"start\" INT, \"savepoint\" STRING)",
+            ),
+            (
+                "alter table t add start int",
+                "ALTER TABLE t ADD COLUMN \"start\" INT",
+            ),
+            ("savepoint start", "SAVEPOINT \"start\""),
+            ("release savepoint release", "RELEASE SAVEPOINT \"release\""),
+            (
+                "select rows, range, row, current, over, partition, unbounded, preceding, following from t",
+                "SELECT \"rows\", \"range\", \"row\", \"current\", \"over\", \"partition\", \"unbounded\", \"preceding\", \"following\" FROM t",
+            ),
+            // REPLACE 只有在 CREATE OR 之后才是关键字
+            (
+                "select replace(replace, 'x', 'y') from t",
+                "SELECT \"replace\"(\"replace\", 'x', 'y') FROM t",
+            ),
+            (
+                "create or replace view replace as select 1",
+                "CREATE OR REPLACE VIEW \"replace\" AS SELECT 1",
+            ),
+            // END 只有在 CASE 中才是关键字
+            (
+                "select start, end, case when end > 0 then end else start end from t",
+                "SELECT \"start\", \"end\", CASE WHEN \"end\" > 0 THEN \"end\" ELSE \"start\" END FROM t",
+            ),
+            // FILTER 只有在聚合函数之后才是关键字
+            (
+                "select filter, count(*) filter (where filter) from t",
+                "SELECT \"filter\", count(*) FILTER (WHERE \"filter\") FROM t",
+            ),
+            // COLUMNS 和 VARIABLES 只有在 SHOW 之后才是关键字
+            (
+                "select columns, variables from t",
+                "SELECT \"columns\", \"variables\" FROM t",
+            ),
+            // NOTHING 只有在 ON CONFLICT DO 之后才是关键字
+            (
+                "insert into t (nothing) values (1) on conflict (nothing) do nothing",
+                "INSERT INTO t (\"nothing\") VALUES (1) ON CONFLICT (\"nothing\") DO NOTHING",
+            ),
+            // RECURSIVE 只有在 WITH 之后才是关键字
+            (
+                "with recursive r as (select recursive from t) select * from r",
+                "WITH RECURSIVE r AS (SELECT \"recursive\" FROM t) SELECT * FROM r",
+            ),
+            // 窗口函数中它们仍然是关键字
+            (
+                "select sum(rows) over (partition by partition order by range rows between current row and unbounded following) \
+                 from window window w as (rows 1 preceding)",
+                "SELECT sum(\"rows\") OVER (PARTITION BY \"partition\" ORDER BY \"range\" ROWS BETWEEN CURRENT ROW AND UNBOUNDED FOLLOWING) \
+                 FROM \"window\" WINDOW w AS (ROWS 1 PRECEDING)",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn unparse_transaction_test() {
+        init();
+        assert_unparse(&[
+            ("begin transaction read only as of system time 10", "BEGIN READ ONLY AS OF SYSTEM TIME 10"),
+            (
+                "begin read write isolation level repeatable read as of system time '2024-01-01 10:00:00.5'",
+                "BEGIN ISOLATION LEVEL REPEATABLE READ AS OF SYSTEM TIME '2024-01-01 10:00:00.5'",
+            ),
+            (
+                "begin transaction as of system time 10 read only",
+                "BEGIN READ ONLY AS OF SYSTEM TIME 10",
+            ),
+            (
+                "begin as of system time '2024-01-01T00:00:00Z', isolation level serializable",
+                "BEGIN ISOLATION LEVEL SERIALIZABLE AS OF SYSTEM TIME '2024-01-01T00:00:00Z'",
+            ),
+            ("start transaction read only", "BEGIN READ ONLY"),
+            ("savepoint \"my sp\"", "SAVEPOINT \"my sp\""),
+            ("release savepoint a", "RELEASE SAVEPOINT a"),
+            ("rollback to a", "ROLLBACK TO SAVEPOINT a"),
+        ]);
+    }
+
     test_parser! {
         explain_base_sql: "explain drop table person" => Ok(Statement::Explain(ExplainStmt {
             statement: Box::new(Statement::DropTable(DropTableStmt {
-                table_name: "person".to_owned(),
+                table_names: vec!["person".to_owned()],
+                if_exists: false,
+                cascade: false,
             })),
         })),
         update_table_base: "update person set name = 'tangruilin' where id = 1;" => Ok(Statement::Update(UpdateStmt {
             table_name: "person".to_owned(),
-            set: BTreeMap::from([(
+            set: vec![(
                 "name".to_owned(),
                 Expression::Literal(Literal::String(
                     "tangruilin".to_owned(),
                 )),
-            )]),
+            )],
+            from: None,
             wheres: Some(Expression::Operation(Operation::Equal(
                 Box::new(Expression::Field(None, "id".to_owned())),
                 Box::new(Expression::Literal(Literal::Int(1))),
             ))),
+            order: None,
+            limit: None,
+            returning: None,
         })),
         drop_table_base: "drop table person;" => Ok(Statement::DropTable(DropTableStmt {
-            table_name: "person".to_owned(),
+            table_names: vec!["person".to_owned()],
+            if_exists: false,
+            cascade: false,
         })),
         delete_table_base: "delete from person where id = 1;" => Ok(Statement::Delete(DeleteTableStmt {
             table_name: "person".to_owned(),
+            using: None,
             r#where: Some(Expression::Operation(Operation::Equal(
                 Box::new(Expression::Field(None, "id".to_owned())),
                 Box::new(Expression::Literal(Literal::Int(1))),
             ))),
+            order: None,
+            limit: None,
+            returning: None,
         })),
         delete_table_without_where: "delete from person;" => Ok(Statement::Delete(DeleteTableStmt {
             table_name: "person".to_owned(),
+            using: None,
             r#where: None,
+            order: None,
+            limit: None,
+            returning: None,
         })),
         insert_table_base: "insert into person (id, name, age) values (1, 'tangruilin', 14)" => Ok(Statement::Insert(InsertStmt {
                 table_name: "person".to_owned(),
                 columns: Some(["id".to_owned(), "name".to_owned(), "age".to_owned()].to_vec()),
-                values: [[
+                source: InsertSource::Values([[
                     Some(Expression::Literal(Literal::Int(1))),
                     Some(Expression::Literal(Literal::String(
                         "tangruilin".to_owned()
@@ -1644,13 +3535,15 @@ pub mod test {
                     Some(Expression::Literal(Literal::Int(14))),
                 ]
                 .to_vec()]
-                .to_vec(),
+                .to_vec()),
+                on_conflict: None,
+                returning: None,
             })),
 
         insert_without_column_name: "insert into person values (1, 'tangruilin', 14)" => Ok(Statement::Insert(InsertStmt {
                 table_name: "person".to_owned(),
                 columns: None,
-                values: [[
+                source: InsertSource::Values([[
                     Some(Expression::Literal(Literal::Int(1))),
                     Some(Expression::Literal(Literal::String(
                         "tangruilin".to_owned()
@@ -1658,9 +3551,37 @@ pub mod test {
                     Some(Expression::Literal(Literal::Int(14))),
                 ]
                 .to_vec()]
-                .to_vec(),
+                .to_vec()),
+                on_conflict: None,
+                returning: None,
             })),
 
+        insert_on_conflict_test: "INSERT INTO t (id, n) VALUES (1, DEFAULT) ON CONFLICT (id) DO UPDATE SET n = t.n + 1 RETURNING n;" => Ok(Statement::Insert(InsertStmt {
+            table_name: "t".to_owned(),
+            columns: Some(vec!["id".to_owned(), "n".to_owned()]),
+            source: InsertSource::Values(vec![vec![
+                Some(Expression::Literal(Literal::Int(1))),
+                None,
+            ]]),
+            on_conflict: Some(OnConflict {
+                columns: Some(vec!["id".to_owned()]),
+                action: ConflictAction::Update {
+                    set: vec![(
+                        "n".to_owned(),
+                        Expression::Operation(Operation::Add(
+                            Box::new(Expression::Field(Some("t".to_owned()), "n".to_owned())),
+                            Box::new(Expression::Literal(Literal::Int(1))),
+                        )),
+                    )],
+                    wheres: None,
+                },
+            }),
+            returning: Some(vec![SelectItem::Expr(
+                Expression::Field(None, "n".to_owned()),
+                None,
+            )]),
+        })),
+
         create_table_success: "create table person (id int primary key, name string not null default 'tangruilin', age int unique, class int index references country);" => Ok(Statement::CreateTable(stmt::CreateTableStmt {
             columns: vec![
                 column::Column {
@@ -1671,6 +3592,7 @@ pub mod test {
                     default: None,
                     unique: false,
                     index: false,
+                    checks: vec![],
                     references: None,
                 },
                 column::Column {
@@ -1683,65 +3605,140 @@ pub mod test {
                     ))),
                     unique: false,
                     index: false,
+                    checks: vec![],
+                    references: None,
+                },
+                column::Column {
+                    name: "age".to_string(),
+                    data_type: DataType::Int32,
+                    primary_key: false,
+                    nullable: None,
+                    default: None,
+                    unique: true,
+                    index: false,
+                    checks: vec![],
                     references: None,
                 },
                 column::Column {
-                    name: "age".to_string(),
+                    name: "class".to_string(),
+                    data_type: DataType::Int32,
+                    primary_key: false,
+                    nullable: None,
+                    default: None,
+                    unique: false,
+                    index: true,
+                    checks: vec![],
+                    references: Some(ForeignKey {
+                        table: "country".to_owned(),
+                        columns: None,
+                        on_delete: None,
+                    }),
+                },
+            ],
+            constraints: vec![],
+            table_name: "person".to_string(),
+            if_not_exists: false,
+        })),
+        create_table_constraints: "create table t (a int, b int check (b > 0), constraint pk primary key (a), foreign key (b) references u on delete restrict);" => Ok(Statement::CreateTable(stmt::CreateTableStmt {
+            columns: vec![
+                column::Column {
+                    name: "a".to_string(),
                     data_type: DataType::Int32,
                     primary_key: false,
                     nullable: None,
                     default: None,
-                    unique: true,
+                    unique: false,
                     index: false,
+                    checks: vec![],
                     references: None,
                 },
                 column::Column {
-                    name: "class".to_string(),
+                    name: "b".to_string(),
                     data_type: DataType::Int32,
                     primary_key: false,
                     nullable: None,
                     default: None,
                     unique: false,
-                    index: true,
-                    references: Some("country".to_owned()),
+                    index: false,
+                    checks: vec![(
+                        None,
+                        Expression::Operation(Operation::GreaterThan(
+                            Box::new(Expression::Field(None, "b".to_owned())),
+                            Box::new(Expression::Literal(Literal::Int(0))),
+                        )),
+                    )],
+                    references: None,
                 },
             ],
-            table_name: "person".to_string(),
+            constraints: vec![
+                TableConstraint {
+                    name: Some("pk".to_owned()),
+                    kind: ConstraintKind::PrimaryKey(vec!["a".to_owned()]),
+                },
+                TableConstraint {
+                    name: None,
+                    kind: ConstraintKind::ForeignKey {
+                        columns: vec!["b".to_owned()],
+                        references: ForeignKey {
+                            table: "u".to_owned(),
+                            columns: None,
+                            on_delete: Some(ReferentialAction::Restrict),
+                        },
+                    },
+                },
+            ],
+            table_name: "t".to_string(),
+            if_not_exists: false,
         })),
         transaction_begin_transaction: "begin transaction;" => Ok(Statement::Begin(BeginStmt {
             is_readonly: false,
+            isolation_level: None,
             version: None,
         })),
         transaction_read_only_begin: "begin transaction read only;" => Ok(Statement::Begin(BeginStmt {
             is_readonly: true,
+            isolation_level: None,
             version: None,
         })),
         transaction_begin: "begin;" => Ok(Statement::Begin(BeginStmt {
             is_readonly: false,
+            isolation_level: None,
             version: None,
         })),
         transaction_commit: "commit;" => Ok(Statement::Commit),
         transaction_rollback: "rollback" => Ok(Statement::Rollback),
         transaction_read_write: "BEGIN TRANSACTION READ WRITE" => Ok(Statement::Begin(BeginStmt {
             is_readonly: false,
+            isolation_level: None,
             version: None,
         })),
         transaction_read_only_with_version: "BEGIN TRANSACTION READ ONLY AS OF SYSTEM TIME 129012313;" => Ok( Statement::Begin(BeginStmt {
             is_readonly: true,
-            version: Some(129012313),
+            isolation_level: None,
+            version: Some(SystemTime::Version(129012313)),
+        })),
+        transaction_start_isolation_level: "START TRANSACTION ISOLATION LEVEL SERIALIZABLE, READ ONLY AS OF SYSTEM TIME '2024-01-01 00:00:00';" => Ok(Statement::Begin(BeginStmt {
+            is_readonly: true,
+            isolation_level: Some(TransactionIsolationLevel::Serializable),
+            version: Some(SystemTime::Timestamp("2024-01-01 00:00:00".to_owned())),
         })),
+        transaction_savepoint: "SAVEPOINT s1;" => Ok(Statement::Savepoint("s1".to_owned())),
+        transaction_release_savepoint: "RELEASE s1;" => Ok(Statement::ReleaseSavepoint("s1".to_owned())),
+        transaction_rollback_to_savepoint: "ROLLBACK TO SAVEPOINT s1;" => Ok(Statement::RollbackTo("s1".to_owned())),
         select_base: "SELECT c1 AS c2 FROM table_1;" => Ok(Statement::Select(SelectStmt {
-            selects: vec![(
+            distinct: None,
+            selects: vec![SelectItem::Expr(
                 Expression::Field(None, "c1".to_owned()),
                 Some("c2".to_owned()),
             )],
-            froms: Some(vec![FromItem::Table {
+            from: Some(FromItem::Table {
                 name: "table_1".to_owned(),
                 alias: None,
-            }]),
+            }),
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             limit: None,
             offset: None,
@@ -1750,20 +3747,21 @@ pub mod test {
                                 LEFT JOIN table_3 AS table_4
                                 ON table_2.id = table_4.id
                                 ORDER BY table_2.id ASC OFFSET 10;"# => Ok(Statement::Select(SelectStmt {
+            distinct: None,
             selects: vec![
-                ((
+                SelectItem::Expr(
                     Expression::Operation(Operation::Add(
                         Box::new(Expression::Literal(Literal::Int(1))),
                         Box::new(Expression::Literal(Literal::Int(2))),
                     )),
                     Some("c1".to_owned()),
-                )),
-                (
+                ),
+                SelectItem::Expr(
                     Expression::Field(Some("user".to_owned()), "id".to_owned()),
                     None,
                 ),
             ],
-            froms: Some(vec![FromItem::Join {
+            from: Some(FromItem::Join {
                 left: Box::new(FromItem::Table {
                     name: "table_1".to_owned(),
                     alias: Some("table_2".to_owned()),
@@ -1773,7 +3771,7 @@ pub mod test {
                     alias: Some("table_4".to_owned()),
                 }),
                 join_type: JoinType::Left,
-                predicate: Some(Expression::Operation(Operation::Equal(
+                constraint: JoinConstraint::On(Expression::Operation(Operation::Equal(
                     Box::new(Expression::Field(
                         Some("table_2".to_owned()),
                         "id".to_owned(),
@@ -1783,10 +3781,11 @@ pub mod test {
                         "id".to_owned(),
                     )),
                 ))),
-            }]),
+            }),
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: Some(vec![(
                 Expression::Field(Some("table_2".to_owned()), "id".to_owned()),
                 OrderByType::Asc,
@@ -1802,33 +3801,40 @@ pub mod test {
                  GROUP BY c.category_name
                  HAVING COUNT(p.product_id) >= 5
                  ORDER BY avg_price DESC OFFSET 4 + 10 * 10.1 LIMIT 3;"# => Ok(Statement::Select(SelectStmt {
+            distinct: None,
             selects: vec![
-                (
+                SelectItem::Expr(
                     Expression::Field(Some("c".to_owned()), "category_name".to_owned()),
                     None,
                 ),
-                (
-                    Expression::Function(
-                        "COUNT".to_owned(),
-                        vec![Expression::Field(
+                SelectItem::Expr(
+                    Expression::Function(Function {
+                        name: "COUNT".to_owned(),
+                        args: vec![Expression::Field(
                             Some("p".to_owned()),
                             "product_id".to_owned(),
                         )],
-                    ),
+                        distinct: false,
+                        filter: None,
+                        over: None,
+                    }),
                     Some("product_count".to_owned()),
                 ),
-                (
-                    Expression::Function(
-                        "AVG".to_owned(),
-                        vec![Expression::Field(
+                SelectItem::Expr(
+                    Expression::Function(Function {
+                        name: "AVG".to_owned(),
+                        args: vec![Expression::Field(
                             Some("p".to_owned()),
                             "unit_price".to_owned(),
                         )],
-                    ),
+                        distinct: false,
+                        filter: None,
+                        over: None,
+                    }),
                     Some("avg_price".to_owned()),
                 ),
             ],
-            froms: Some(vec![FromItem::Join {
+            from: Some(FromItem::Join {
                 left: Box::new(FromItem::Join {
                     left: Box::new(FromItem::Table {
                         name: "categories".to_owned(),
@@ -1839,7 +3845,7 @@ pub mod test {
                         alias: Some("p".to_owned()),
                     }),
                     join_type: JoinType::Left,
-                    predicate: Some(Expression::Operation(Operation::Equal(
+                    constraint: JoinConstraint::On(Expression::Operation(Operation::Equal(
                         Box::new(Expression::Field(
                             Some("c".to_owned()),
                             "category_id".to_owned(),
@@ -1855,7 +3861,7 @@ pub mod test {
                     alias: Some("o".to_owned()),
                 }),
                 join_type: JoinType::Right,
-                predicate: Some(Expression::Operation(Operation::Equal(
+                constraint: JoinConstraint::On(Expression::Operation(Operation::Equal(
                     Box::new(Expression::Field(
                         Some("p".to_owned()),
                         "product_id".to_owned(),
@@ -1865,7 +3871,7 @@ pub mod test {
                         "product_id".to_owned(),
                     )),
                 ))),
-            }]),
+            }),
             wheres: Some(Expression::Operation(Operation::And(
                 Box::new(Expression::Operation(Operation::GreaterThanOrEqual(
                     Box::new(Expression::Field(
@@ -1891,15 +3897,19 @@ pub mod test {
                 "category_name".to_owned(),
             )]),
             having: Some(Expression::Operation(Operation::GreaterThanOrEqual(
-                Box::new(Expression::Function(
-                    "COUNT".to_owned(),
-                    vec![Expression::Field(
+                Box::new(Expression::Function(Function {
+                    name: "COUNT".to_owned(),
+                    args: vec![Expression::Field(
                         Some("p".to_owned()),
                         "product_id".to_owned(),
                     )],
-                )),
+                    distinct: false,
+                    filter: None,
+                    over: None,
+                })),
                 Box::new(Expression::Literal(Literal::Int(5))),
             ))),
+            windows: None,
             order: Some(vec![(
                 Expression::Field(None, "avg_price".to_owned()),
                 OrderByType::Desc,
@@ -1918,26 +3928,28 @@ pub mod test {
                  OFFSET TRUE AND FALSE
                  LIMIT 10;
                 "# => Ok(Statement::Select(SelectStmt {
+            distinct: None,
             selects: vec![
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::Add(
                         Box::new(Expression::Literal(Literal::Int(1))),
                         Box::new(Expression::Literal(Literal::Int(2))),
                     )),
                     Some("c1".to_owned()),
                 ),
-                (
+                SelectItem::Expr(
                     Expression::Field(Some("account".to_owned()), "id".to_owned()),
                     None,
                 ),
             ],
-            froms: Some(vec![FromItem::Table {
+            from: Some(FromItem::Table {
                 name: "table_1".to_owned(),
                 alias: None,
-            }]),
+            }),
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: Some(Expression::Operation(Operation::And(
                 Box::new(Expression::Literal(Literal::Bool(true))),
@@ -1946,17 +3958,19 @@ pub mod test {
             limit: Some(Expression::Literal(Literal::Int(10))),
         })),
         select_with_alias: r#"SELECT c1.id FROM b2 AS c1 ORDER BY c1.id;"# => Ok(Statement::Select(SelectStmt {
-            selects: vec![(
+            distinct: None,
+            selects: vec![SelectItem::Expr(
                 Expression::Field(Some("c1".to_owned()), "id".to_owned()),
                 None,
             )],
-            froms: Some(vec![FromItem::Table {
+            from: Some(FromItem::Table {
                 name: "b2".to_owned(),
                 alias: Some("c1".to_owned()),
-            }]),
+            }),
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: Some(vec![(
                 Expression::Field(Some("c1".to_owned()), "id".to_owned()),
                 OrderByType::Asc,
@@ -1965,39 +3979,48 @@ pub mod test {
             limit: None,
         })),
         select_with_aggression: r#"SELECT COUNT(*) FROM user WHERE user.id != NULL;"# => Ok(Statement::Select(SelectStmt {
-            selects: vec![(
-                Expression::Function("COUNT".to_owned(), vec![Expression::Literal(Literal::All)]),
+            distinct: None,
+            selects: vec![SelectItem::Expr(
+                Expression::Function(Function {
+                    name: "COUNT".to_owned(),
+                    args: vec![Expression::Literal(Literal::All)],
+                    distinct: false,
+                    filter: None,
+                    over: None,
+                }),
                 None,
             )],
-            froms: Some(vec![FromItem::Table {
+            from: Some(FromItem::Table {
                 name: "user".to_owned(),
                 alias: None,
-            }]),
+            }),
             wheres: Some(Expression::Operation(Operation::NotEqual(
                 Box::new(Expression::Field(Some("user".to_owned()), "id".to_owned())),
                 Box::new(Expression::Literal(Literal::Null)),
             ))),
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
         })),
         select_with_join: "SELECT 1 + 2 AS c1, c3.id FROM c5 JOIN c6 ON c5.id = c6.id;" => Ok( Statement::Select(SelectStmt {
+            distinct: None,
             selects: vec![
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::Add(
                         Box::new(Expression::Literal(Literal::Int(1))),
                         Box::new(Expression::Literal(Literal::Int(2))),
                     )),
                     Some("c1".to_owned()),
                 ),
-                (
+                SelectItem::Expr(
                     Expression::Field(Some("c3".to_owned()), "id".to_owned()),
                     None,
                 ),
             ],
-            froms: Some(vec![FromItem::Join {
+            from: Some(FromItem::Join {
                 left: Box::new(FromItem::Table {
                     name: "c5".to_owned(),
                     alias: None,
@@ -2007,39 +4030,47 @@ pub mod test {
                     alias: None,
                 }),
                 join_type: JoinType::Inner,
-                predicate: Some(Expression::Operation(Operation::Equal(
+                constraint: JoinConstraint::On(Expression::Operation(Operation::Equal(
                     Box::new(Expression::Field(Some("c5".to_owned()), "id".to_owned())),
                     Box::new(Expression::Field(Some("c6".to_owned()), "id".to_owned())),
                 ))),
-            }]),
+            }),
             wheres: None,
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
         })),
         select_with_aggression_and_alias: r#"SELECT COUNT(*) AS c1, AVG(test_1.id) AS c2, 1 + 2 * (-10) AS c3
                  FROM test_1 WHERE c1.id = -10;"# => Ok(Statement::Select(SelectStmt {
+            distinct: None,
             selects: vec![
-                (
-                    Expression::Function(
-                        "COUNT".to_owned(),
-                        vec![Expression::Literal(Literal::All)],
-                    ),
+                SelectItem::Expr(
+                    Expression::Function(Function {
+                        name: "COUNT".to_owned(),
+                        args: vec![Expression::Literal(Literal::All)],
+                        distinct: false,
+                        filter: None,
+                        over: None,
+                    }),
                     Some("c1".to_owned()),
                 ),
-                (
-                    Expression::Function(
-                        "AVG".to_owned(),
-                        vec![Expression::Field(
+                SelectItem::Expr(
+                    Expression::Function(Function {
+                        name: "AVG".to_owned(),
+                        args: vec![Expression::Field(
                             Some("test_1".to_owned()),
                             "id".to_owned(),
                         )],
-                    ),
+                        distinct: false,
+                        filter: None,
+                        over: None,
+                    }),
                     Some("c2".to_owned()),
                 ),
-                (
+                SelectItem::Expr(
                     Expression::Operation(Operation::Add(
                         Box::new(Expression::Literal(Literal::Int(1))),
                         Box::new(Expression::Operation(Operation::Multiply(
@@ -2052,10 +4083,10 @@ pub mod test {
                     Some("c3".to_owned()),
                 ),
             ],
-            froms: Some(vec![FromItem::Table {
+            from: Some(FromItem::Table {
                 name: "test_1".to_owned(),
                 alias: None,
-            }]),
+            }),
             wheres: Some(Expression::Operation(Operation::Equal(
                 Box::new(Expression::Field(Some("c1".to_owned()), "id".to_owned())),
                 Box::new(Expression::Operation(Operation::Negate(Box::new(
@@ -2064,6 +4095,7 @@ pub mod test {
             ))),
             group_by: None,
             having: None,
+            windows: None,
             order: None,
             offset: None,
             limit: None,
@@ -2082,6 +4114,7 @@ pub mod test {
                 ))),
                 unique: false,
                 index: false,
+                checks: vec![],
                 references: None,
             }),
             table_name: "user".to_owned(),
@@ -2114,6 +4147,7 @@ pub mod test {
                     default: None,
                     unique: false,
                     index: false,
+                    checks: vec![],
                     references: None,
                 }),
                 table_name: "user".to_owned(),
@@ -2134,16 +4168,61 @@ pub mod test {
         create_index_test_1: r#"CREATE INDEX xxx_name ON table_name (id, password, account);"# =>
             Ok(Statement::CreateIndex(CreateIndexStmt {
                 is_unique: false,
-                index_name: "xxx_name".to_owned(),
+                if_not_exists: false,
+                index_name: Some("xxx_name".to_owned()),
                 table_name: "table_name".to_owned(),
-                columns: vec!["id".to_owned(), "password".to_owned(), "account".to_owned()],
+                columns: vec![
+                    Expression::Field(None, "id".to_owned()),
+                    Expression::Field(None, "password".to_owned()),
+                    Expression::Field(None, "account".to_owned()),
+                ],
+                wheres: None,
             })),
         create_unique_index_test2: r#"CREATE UNIQUE INDEX index_name ON table_name (id, password);"# =>
             Ok(Statement::CreateIndex(CreateIndexStmt {
                 is_unique: true,
-                index_name: "index_name".to_owned(),
+                if_not_exists: false,
+                index_name: Some("index_name".to_owned()),
                 table_name: "table_name".to_owned(),
-                columns: vec!["id".to_owned(), "password".to_owned()],
+                columns: vec![
+                    Expression::Field(None, "id".to_owned()),
+                    Expression::Field(None, "password".to_owned()),
+                ],
+                wheres: None,
+            })),
+        create_expression_index_test: r#"CREATE INDEX ON t (lower(name), (a + 1)) WHERE deleted_at IS NULL;"# =>
+            Ok(Statement::CreateIndex(CreateIndexStmt {
+                is_unique: false,
+                if_not_exists: false,
+                index_name: None,
+                table_name: "t".to_owned(),
+                columns: vec![
+                    Expression::Function(Function {
+                        name: "lower".to_owned(),
+                        args: vec![Expression::Field(None, "name".to_owned())],
+                        distinct: false,
+                        filter: None,
+                        over: None,
+                    }),
+                    Expression::Operation(Operation::Add(
+                        Box::new(Expression::Field(None, "a".to_owned())),
+                        Box::new(Expression::Literal(Literal::Int(1))),
+                    )),
+                ],
+                wheres: Some(Expression::Operation(Operation::IsNull(
+                    Box::new(Expression::Field(None, "deleted_at".to_owned())),
+                ))),
+            })),
+        drop_tables_test: r#"DROP TABLE IF EXISTS a, b CASCADE;"# =>
+            Ok(Statement::DropTable(DropTableStmt {
+                table_names: vec!["a".to_owned(), "b".to_owned()],
+                if_exists: true,
+                cascade: true,
+            })),
+        drop_index_test: r#"DROP INDEX IF EXISTS idx;"# =>
+            Ok(Statement::DropIndex(DropIndexStmt {
+                index_name: "idx".to_owned(),
+                if_exists: true,
             })),
         set_transaction_test_1: r#"SET SESSION TRANSACTION ISOLATION LEVEL READ UNCOMMITTED;"# =>
             Ok(Statement::Set(SetStmt {
@@ -2166,9 +4245,624 @@ pub mod test {
                 is_session: true,
             })),
 
+        select_unicode_test: "SELECT 名字 AS 姓名 FROM 用户 WHERE 城市 = '北京🏙';" => Ok(Statement::Select(SelectStmt {
+            distinct: None,
+            selects: vec![SelectItem::Expr(
+                Expression::Field(None, "名字".to_owned()),
+                Some("姓名".to_owned()),
+            )],
+            from: Some(FromItem::Table {
+                name: "用户".to_owned(),
+                alias: None,
+            }),
+            wheres: Some(Expression::Operation(Operation::Equal(
+                Box::new(Expression::Field(None, "城市".to_owned())),
+                Box::new(Expression::Literal(Literal::String("北京🏙".to_owned()))),
+            ))),
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        select_with_in_test: "SELECT a FROM t WHERE a IN (1, 2) AND b NOT IN ('x');" => Ok(Statement::Select(SelectStmt {
+            distinct: None,
+            selects: vec![SelectItem::Expr(Expression::Field(None, "a".to_owned()), None)],
+            from: Some(FromItem::Table {
+                name: "t".to_owned(),
+                alias: None,
+            }),
+            wheres: Some(Expression::Operation(Operation::And(
+                Box::new(Expression::Operation(Operation::In(
+                    Box::new(Expression::Field(None, "a".to_owned())),
+                    vec![
+                        Expression::Literal(Literal::Int(1)),
+                        Expression::Literal(Literal::Int(2)),
+                    ],
+                ))),
+                Box::new(Expression::Operation(Operation::NotIn(
+                    Box::new(Expression::Field(None, "b".to_owned())),
+                    vec![Expression::Literal(Literal::String("x".to_owned()))],
+                ))),
+            ))),
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        // BETWEEN 中的 AND 比逻辑运算符 AND 优先
+        select_with_between_test: "SELECT a BETWEEN 1 AND 2 + 3 AND b NOT BETWEEN c AND d;" => Ok(Statement::Select(SelectStmt {
+            distinct: None,
+            selects: vec![SelectItem::Expr(
+                Expression::Operation(Operation::And(
+                    Box::new(Expression::Operation(Operation::Between(
+                        Box::new(Expression::Field(None, "a".to_owned())),
+                        Box::new(Expression::Literal(Literal::Int(1))),
+                        Box::new(Expression::Operation(Operation::Add(
+                            Box::new(Expression::Literal(Literal::Int(2))),
+                            Box::new(Expression::Literal(Literal::Int(3))),
+                        ))),
+                    ))),
+                    Box::new(Expression::Operation(Operation::NotBetween(
+                        Box::new(Expression::Field(None, "b".to_owned())),
+                        Box::new(Expression::Field(None, "c".to_owned())),
+                        Box::new(Expression::Field(None, "d".to_owned())),
+                    ))),
+                )),
+                None,
+            )],
+            from: None,
+            wheres: None,
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        select_with_is_null_test: "SELECT a IS NULL, b IS NOT NULL OR name NOT LIKE 'a%';" => Ok(Statement::Select(SelectStmt {
+            distinct: None,
+            selects: vec![
+                SelectItem::Expr(
+                    Expression::Operation(Operation::IsNull(Box::new(Expression::Field(
+                        None,
+                        "a".to_owned(),
+                    )))),
+                    None,
+                ),
+                SelectItem::Expr(
+                    Expression::Operation(Operation::Or(
+                        Box::new(Expression::Operation(Operation::IsNotNull(Box::new(
+                            Expression::Field(None, "b".to_owned()),
+                        )))),
+                        Box::new(Expression::Operation(Operation::NotLike(
+                            Box::new(Expression::Field(None, "name".to_owned())),
+                            Box::new(Expression::Literal(Literal::String("a%".to_owned()))),
+                        ))),
+                    )),
+                    None,
+                ),
+            ],
+            from: None,
+            wheres: None,
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        select_with_case_test: "SELECT CASE WHEN a > 1 THEN 'big' ELSE 'small' END, CASE a WHEN 1 THEN 2 WHEN 3 THEN 4 END;" => Ok(Statement::Select(SelectStmt {
+            distinct: None,
+            selects: vec![
+                SelectItem::Expr(
+                    Expression::Case {
+                        operand: None,
+                        branches: vec![(
+                            Expression::Operation(Operation::GreaterThan(
+                                Box::new(Expression::Field(None, "a".to_owned())),
+                                Box::new(Expression::Literal(Literal::Int(1))),
+                            )),
+                            Expression::Literal(Literal::String("big".to_owned())),
+                        )],
+                        else_result: Some(Box::new(Expression::Literal(Literal::String(
+                            "small".to_owned(),
+                        )))),
+                    },
+                    None,
+                ),
+                SelectItem::Expr(
+                    Expression::Case {
+                        operand: Some(Box::new(Expression::Field(None, "a".to_owned()))),
+                        branches: vec![
+                            (
+                                Expression::Literal(Literal::Int(1)),
+                                Expression::Literal(Literal::Int(2)),
+                            ),
+                            (
+                                Expression::Literal(Literal::Int(3)),
+                                Expression::Literal(Literal::Int(4)),
+                            ),
+                        ],
+                        else_result: None,
+                    },
+                    None,
+                ),
+            ],
+            from: None,
+            wheres: None,
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        select_with_subquery_test: "SELECT (SELECT 1) FROM (SELECT * FROM t) AS a WHERE id IN (SELECT id FROM u) OR EXISTS (SELECT * FROM v);" => Ok(Statement::Select(SelectStmt {
+            distinct: None,
+            selects: vec![SelectItem::Expr(
+                Expression::Subquery(Box::new(Query::from(SelectStmt {
+                    distinct: None,
+                    selects: vec![SelectItem::Expr(Expression::Literal(Literal::Int(1)), None)],
+                    from: None,
+                    wheres: None,
+                    group_by: None,
+                    having: None,
+                    windows: None,
+                    order: None,
+                    offset: None,
+                    limit: None,
+                }))),
+                None,
+            )],
+            from: Some(FromItem::Subquery {
+                query: Box::new(Query::from(SelectStmt {
+                    distinct: None,
+                    selects: vec![SelectItem::Wildcard],
+                    from: Some(FromItem::Table {
+                        name: "t".to_owned(),
+                        alias: None,
+                    }),
+                    wheres: None,
+                    group_by: None,
+                    having: None,
+                    windows: None,
+                    order: None,
+                    offset: None,
+                    limit: None,
+                })),
+                alias: Some("a".to_owned()),
+            }),
+            wheres: Some(Expression::Operation(Operation::Or(
+                Box::new(Expression::Operation(Operation::InSubquery(
+                    Box::new(Expression::Field(None, "id".to_owned())),
+                    Box::new(Query::from(SelectStmt {
+                        distinct: None,
+                        selects: vec![SelectItem::Expr(Expression::Field(None, "id".to_owned()), None)],
+                        from: Some(FromItem::Table {
+                            name: "u".to_owned(),
+                            alias: None,
+                        }),
+                        wheres: None,
+                        group_by: None,
+                        having: None,
+                        windows: None,
+                        order: None,
+                        offset: None,
+                        limit: None,
+                    })),
+                ))),
+                Box::new(Expression::Exists(Box::new(Query::from(SelectStmt {
+                    distinct: None,
+                    selects: vec![SelectItem::Wildcard],
+                    from: Some(FromItem::Table {
+                        name: "v".to_owned(),
+                        alias: None,
+                    }),
+                    wheres: None,
+                    group_by: None,
+                    having: None,
+                    windows: None,
+                    order: None,
+                    offset: None,
+                    limit: None,
+                })))),
+            ))),
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        select_with_cte_test: "WITH RECURSIVE t (n) AS (SELECT 1), u AS (SELECT * FROM t) SELECT n FROM u;" => Ok(Statement::Query(Query {
+            with: Some(With {
+                recursive: true,
+                ctes: vec![
+                    Cte {
+                        name: "t".to_owned(),
+                        columns: Some(vec!["n".to_owned()]),
+                        query: Query::from(SelectStmt {
+                                distinct: None,
+                                selects: vec![SelectItem::Expr(Expression::Literal(Literal::Int(1)), None)],
+                                from: None,
+                                wheres: None,
+                                group_by: None,
+                                having: None,
+                                windows: None,
+                                order: None,
+                                offset: None,
+                                limit: None,
+                            }),
+                    },
+                    Cte {
+                        name: "u".to_owned(),
+                        columns: None,
+                        query: Query::from(SelectStmt {
+                                distinct: None,
+                                selects: vec![SelectItem::Wildcard],
+                                from: Some(FromItem::Table {
+                                    name: "t".to_owned(),
+                                    alias: None,
+                                }),
+                                wheres: None,
+                                group_by: None,
+                                having: None,
+                                windows: None,
+                                order: None,
+                                offset: None,
+                                limit: None,
+                            }),
+                    },
+                ],
+            }),
+            body: SetExpr::Select(Box::new(SelectStmt {
+                distinct: None,
+                selects: vec![SelectItem::Expr(Expression::Field(None, "n".to_owned()), None)],
+                from: Some(FromItem::Table {
+                    name: "u".to_owned(),
+                    alias: None,
+                }),
+                wheres: None,
+                group_by: None,
+                having: None,
+                windows: None,
+                order: None,
+                offset: None,
+                limit: None,
+            })),
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        // INTERSECT 的优先级更高, ORDER BY 和 LIMIT 属于整个查询
+        select_with_set_operation_test: "SELECT a FROM t UNION ALL SELECT b FROM u INTERSECT SELECT c FROM v ORDER BY a LIMIT 2;" => Ok(Statement::Query(Query {
+            with: None,
+            body: SetExpr::SetOperation {
+                op: SetOperator::Union,
+                all: true,
+                left: Box::new(SetExpr::Select(Box::new(SelectStmt {
+                    distinct: None,
+                    selects: vec![SelectItem::Expr(Expression::Field(None, "a".to_owned()), None)],
+                    from: Some(FromItem::Table {
+                        name: "t".to_owned(),
+                        alias: None,
+                    }),
+                    wheres: None,
+                    group_by: None,
+                    having: None,
+                    windows: None,
+                    order: None,
+                    offset: None,
+                    limit: None,
+                }))),
+                right: Box::new(SetExpr::SetOperation {
+                    op: SetOperator::Intersect,
+                    all: false,
+                    left: Box::new(SetExpr::Select(Box::new(SelectStmt {
+                        distinct: None,
+                        selects: vec![SelectItem::Expr(Expression::Field(None, "b".to_owned()), None)],
+                        from: Some(FromItem::Table {
+                            name: "u".to_owned(),
+                            alias: None,
+                        }),
+                        wheres: None,
+                        group_by: None,
+                        having: None,
+                        windows: None,
+                        order: None,
+                        offset: None,
+                        limit: None,
+                    }))),
+                    right: Box::new(SetExpr::Select(Box::new(SelectStmt {
+                        distinct: None,
+                        selects: vec![SelectItem::Expr(Expression::Field(None, "c".to_owned()), None)],
+                        from: Some(FromItem::Table {
+                            name: "v".to_owned(),
+                            alias: None,
+                        }),
+                        wheres: None,
+                        group_by: None,
+                        having: None,
+                        windows: None,
+                        order: None,
+                        offset: None,
+                        limit: None,
+                    }))),
+                }),
+            },
+            order: Some(vec![(Expression::Field(None, "a".to_owned()), OrderByType::Asc)]),
+            offset: None,
+            limit: Some(Expression::Literal(Literal::Int(2))),
+        })),
+
+        // 逗号的优先级低于 JOIN
+        select_with_parameter_test: "SELECT * FROM t WHERE a = $2 AND b LIKE $1;" => Ok(Statement::Select(SelectStmt {
+            distinct: None,
+            selects: vec![SelectItem::Wildcard],
+            from: Some(FromItem::Table {
+                name: "t".to_owned(),
+                alias: None,
+            }),
+            wheres: Some(Expression::Operation(Operation::And(
+                Box::new(Expression::Operation(Operation::Equal(
+                    Box::new(Expression::Field(None, "a".to_owned())),
+                    Box::new(Expression::Parameter(Parameter::Indexed(2))),
+                ))),
+                Box::new(Expression::Operation(Operation::Like(
+                    Box::new(Expression::Field(None, "b".to_owned())),
+                    Box::new(Expression::Parameter(Parameter::Indexed(1))),
+                ))),
+            ))),
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        select_with_join_test: "SELECT * FROM a NATURAL JOIN b, c FULL OUTER JOIN d USING (id);" => Ok(Statement::Select(SelectStmt {
+            distinct: None,
+            selects: vec![SelectItem::Wildcard],
+            from: Some(FromItem::Join {
+                left: Box::new(FromItem::Join {
+                    left: Box::new(FromItem::Table {
+                        name: "a".to_owned(),
+                        alias: None,
+                    }),
+                    right: Box::new(FromItem::Table {
+                        name: "b".to_owned(),
+                        alias: None,
+                    }),
+                    join_type: JoinType::Inner,
+                    constraint: JoinConstraint::Natural,
+                }),
+                right: Box::new(FromItem::Join {
+                    left: Box::new(FromItem::Table {
+                        name: "c".to_owned(),
+                        alias: None,
+                    }),
+                    right: Box::new(FromItem::Table {
+                        name: "d".to_owned(),
+                        alias: None,
+                    }),
+                    join_type: JoinType::Full,
+                    constraint: JoinConstraint::Using(vec!["id".to_owned()]),
+                }),
+                join_type: JoinType::Cross,
+                constraint: JoinConstraint::None,
+            }),
+            wheres: None,
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        select_with_distinct_test: "SELECT DISTINCT ON (a) t.*, *, COUNT(DISTINCT b) FILTER (WHERE b > 1) AS c FROM t;" => Ok(Statement::Select(SelectStmt {
+            distinct: Some(Distinct::On(vec![Expression::Field(None, "a".to_owned())])),
+            selects: vec![
+                SelectItem::QualifiedWildcard("t".to_owned()),
+                SelectItem::Wildcard,
+                SelectItem::Expr(
+                    Expression::Function(Function {
+                        name: "COUNT".to_owned(),
+                        args: vec![Expression::Field(None, "b".to_owned())],
+                        distinct: true,
+                        filter: Some(Box::new(Expression::Operation(Operation::GreaterThan(
+                            Box::new(Expression::Field(None, "b".to_owned())),
+                            Box::new(Expression::Literal(Literal::Int(1))),
+                        )))),
+                        over: None,
+                    }),
+                    Some("c".to_owned()),
+                ),
+            ],
+            from: Some(FromItem::Table {
+                name: "t".to_owned(),
+                alias: None,
+            }),
+            wheres: None,
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        select_with_window_test: "SELECT ROW_NUMBER() OVER (PARTITION BY a ORDER BY b DESC ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW), RANK() OVER w FROM t WINDOW w AS (ORDER BY a);" => Ok(Statement::Select(SelectStmt {
+            distinct: None,
+            selects: vec![
+                SelectItem::Expr(
+                    Expression::Function(Function {
+                        name: "ROW_NUMBER".to_owned(),
+                        args: vec![],
+                        distinct: false,
+                        filter: None,
+                        over: Some(Box::new(WindowType::Spec(WindowSpec {
+                            name: None,
+                            partition_by: Some(vec![Expression::Field(None, "a".to_owned())]),
+                            order: Some(vec![(Expression::Field(None, "b".to_owned()), OrderByType::Desc)]),
+                            frame: Some(WindowFrame {
+                                units: WindowFrameUnits::Rows,
+                                start: WindowFrameBound::Preceding(None),
+                                end: Some(WindowFrameBound::CurrentRow),
+                            }),
+                        }))),
+                    }),
+                    None,
+                ),
+                SelectItem::Expr(
+                    Expression::Function(Function {
+                        name: "RANK".to_owned(),
+                        args: vec![],
+                        distinct: false,
+                        filter: None,
+                        over: Some(Box::new(WindowType::Named("w".to_owned()))),
+                    }),
+                    None,
+                ),
+            ],
+            from: Some(FromItem::Table {
+                name: "t".to_owned(),
+                alias: None,
+            }),
+            wheres: None,
+            group_by: None,
+            having: None,
+            windows: Some(vec![(
+                "w".to_owned(),
+                WindowSpec {
+                    name: None,
+                    partition_by: None,
+                    order: Some(vec![(Expression::Field(None, "a".to_owned()), OrderByType::Asc)]),
+                    frame: None,
+                },
+            )]),
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
+        // -1::INT 等价于 -(1::INT)
+        select_with_cast_test: "SELECT CAST(a + 1 AS INT64), -1::VARCHAR(10)::TEXT;" => Ok(Statement::Select(SelectStmt {
+            distinct: None,
+            selects: vec![
+                SelectItem::Expr(
+                    Expression::Cast(
+                        Box::new(Expression::Operation(Operation::Add(
+                            Box::new(Expression::Field(None, "a".to_owned())),
+                            Box::new(Expression::Literal(Literal::Int(1))),
+                        ))),
+                        DataType::Int64,
+                    ),
+                    None,
+                ),
+                SelectItem::Expr(
+                    Expression::Operation(Operation::Negate(Box::new(Expression::Cast(
+                        Box::new(Expression::Cast(
+                            Box::new(Expression::Literal(Literal::Int(1))),
+                            DataType::Varchar(10),
+                        )),
+                        DataType::String,
+                    )))),
+                    None,
+                ),
+            ],
+            from: None,
+            wheres: None,
+            group_by: None,
+            having: None,
+            windows: None,
+            order: None,
+            offset: None,
+            limit: None,
+        })),
+
         show_databases_test: r#"SHOW DATABASES;"# =>
             Ok(Statement::ShowDatabase),
         show_tables_test: r#"SHOW TABLES;"# =>
             Ok(Statement::ShowTables),
+        show_variables_test: r#"SHOW GLOBAL VARIABLES LIKE 'auto%';"# =>
+            Ok(Statement::ShowVariables(ShowVariablesStmt {
+                is_session: false,
+                like: Some("auto%".to_owned()),
+            })),
+        show_create_table_test: r#"SHOW CREATE TABLE person;"# =>
+            Ok(Statement::ShowCreateTable("person".to_owned())),
+        truncate_test: r#"TRUNCATE TABLE a, b;"# =>
+            Ok(Statement::Truncate(vec!["a".to_owned(), "b".to_owned()])),
+        create_database_test: r#"CREATE DATABASE IF NOT EXISTS shaun;"# =>
+            Ok(Statement::CreateDatabase(CreateDatabaseStmt {
+                name: "shaun".to_owned(),
+                if_not_exists: true,
+            })),
+
+        create_view_test: "CREATE VIEW adult (id) AS SELECT id FROM person WHERE age >= 18;" => Ok(Statement::CreateView(CreateViewStmt {
+            name: "adult".to_owned(),
+            columns: Some(vec!["id".to_owned()]),
+            query: Box::new(Query::from(SelectStmt {
+                distinct: None,
+                selects: vec![SelectItem::Expr(Expression::Field(None, "id".to_owned()), None)],
+                from: Some(FromItem::Table {
+                    name: "person".to_owned(),
+                    alias: None,
+                }),
+                wheres: Some(Expression::Operation(Operation::GreaterThanOrEqual(
+                    Box::new(Expression::Field(None, "age".to_owned())),
+                    Box::new(Expression::Literal(Literal::Int(18))),
+                ))),
+                group_by: None,
+                having: None,
+                windows: None,
+                order: None,
+                offset: None,
+                limit: None,
+            })),
+            or_replace: false,
+            materialized: false,
+        })),
+        drop_view_test: "DROP VIEW IF EXISTS adult;" => Ok(Statement::DropView(DropViewStmt {
+            names: vec!["adult".to_owned()],
+            materialized: false,
+            if_exists: true,
+            cascade: false,
+        })),
+        prepare_test: "PREPARE q AS DELETE FROM t WHERE id = $1;" => Ok(Statement::Prepare(PrepareStmt {
+            name: "q".to_owned(),
+            statement: Box::new(Statement::Delete(DeleteTableStmt {
+                table_name: "t".to_owned(),
+                using: None,
+                r#where: Some(Expression::Operation(Operation::Equal(
+                    Box::new(Expression::Field(None, "id".to_owned())),
+                    Box::new(Expression::Parameter(Parameter::Indexed(1))),
+                ))),
+                order: None,
+                limit: None,
+                returning: None,
+            })),
+        })),
+        execute_test: "EXECUTE q (1, 'a');" => Ok(Statement::Execute(ExecuteStmt {
+            name: "q".to_owned(),
+            params: vec![
+                Expression::Literal(Literal::Int(1)),
+                Expression::Literal(Literal::String("a".to_owned())),
+            ],
+        })),
+        deallocate_test: "DEALLOCATE q;" => Ok(Statement::Deallocate(Some("q".to_owned()))),
     }
 }
@@ -1,4 +1,10 @@
+use std::fmt;
+
+use crate::parser::display::CommaSeparated;
 use crate::parser::expression::Expression;
+use crate::parser::operator::Precedence;
+use crate::parser::stmt::Query;
+
 #[derive(PartialEq, Debug, Clone)]
 pub enum Operation {
     And(Box<Expression>, Box<Expression>),
@@ -13,6 +19,17 @@ pub enum Operation {
     LessThanOrEqual(Box<Expression>, Box<Expression>),
 
     IsNull(Box<Expression>),
+    IsNotNull(Box<Expression>),
+
+    // x IN (a, b), x NOT IN (a, b)
+    In(Box<Expression>, Vec<Expression>),
+    NotIn(Box<Expression>, Vec<Expression>),
+    // x IN (SELECT ...), x NOT IN (SELECT ...)
+    InSubquery(Box<Expression>, Box<Query>),
+    NotInSubquery(Box<Expression>, Box<Query>),
+    // x BETWEEN low AND high
+    Between(Box<Expression>, Box<Expression>, Box<Expression>),
+    NotBetween(Box<Expression>, Box<Expression>, Box<Expression>),
 
     // + - * !
     Add(Box<Expression>, Box<Expression>),
@@ -22,6 +39,7 @@ pub enum Operation {
 
     Assert(Box<Expression>),
     Like(Box<Expression>, Box<Expression>),
+    NotLike(Box<Expression>, Box<Expression>),
 
     Negate(Box<Expression>),
     BitWiseNot(Box<Expression>),
@@ -34,3 +52,153 @@ impl From<Operation> for Expression {
         Self::Operation(op)
     }
 }
+
+impl Operation {
+    pub fn precedence(&self) -> Precedence {
+        match self {
+            Self::Or(_, _) => Precedence::Or,
+            Self::And(_, _) => Precedence::And,
+            Self::Not(_) => Precedence::Not,
+            Self::Equal(_, _)
+            | Self::NotEqual(_, _)
+            | Self::Like(_, _)
+            | Self::NotLike(_, _)
+            | Self::IsNull(_)
+            | Self::IsNotNull(_)
+            | Self::In(_, _)
+            | Self::NotIn(_, _)
+            | Self::InSubquery(_, _)
+            | Self::NotInSubquery(_, _)
+            | Self::Between(_, _, _)
+            | Self::NotBetween(_, _, _) => Precedence::Equals,
+            Self::GreaterThan(_, _)
+            | Self::GreaterThanOrEqual(_, _)
+            | Self::LessThan(_, _)
+            | Self::LessThanOrEqual(_, _) => Precedence::LessGreater,
+            Self::Add(_, _) | Self::Subtract(_, _) => Precedence::Sum,
+            Self::Multiply(_, _) | Self::Divide(_, _) | Self::Modulo(_, _) => Precedence::Product,
+            Self::Assert(_) | Self::Negate(_) | Self::BitWiseNot(_) => Precedence::Prefix,
+        }
+    }
+
+    // 二元运算符都是左结合的, 所以右边优先级相同的子表达式也需要加括号
+    // (1 - 2) - 3 => 1 - 2 - 3
+    // 1 - (2 - 3) => 1 - (2 - 3)
+    fn fmt_infix(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        lhs: &Expression,
+        op: &str,
+        rhs: &Expression,
+    ) -> fmt::Result {
+        let precedence = self.precedence();
+        fmt_operand(f, lhs, lhs.precedence() < precedence)?;
+        write!(f, " {} ", op)?;
+        fmt_operand(f, rhs, rhs.precedence() <= precedence)
+    }
+
+    // x IS NULL, x IN (a, b) 左边的表达式和二元运算符的左边一样处理
+    fn fmt_postfix(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        operand: &Expression,
+        op: &str,
+    ) -> fmt::Result {
+        fmt_operand(f, operand, operand.precedence() < self.precedence())?;
+        write!(f, " {}", op)
+    }
+
+    // BETWEEN 的上下界只能是比 AND 和比较运算符优先级更高的表达式
+    fn fmt_between(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        operand: &Expression,
+        op: &str,
+        low: &Expression,
+        high: &Expression,
+    ) -> fmt::Result {
+        let precedence = self.precedence();
+        fmt_operand(f, operand, operand.precedence() < precedence)?;
+        write!(f, " {} ", op)?;
+        fmt_operand(f, low, low.precedence() <= precedence)?;
+        write!(f, " AND ")?;
+        fmt_operand(f, high, high.precedence() <= precedence)
+    }
+
+    fn fmt_prefix(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        op: &str,
+        operand: &Expression,
+    ) -> fmt::Result {
+        let operand_str = operand.to_string();
+        // --1 会被当成注释, 所以 -(-1) 的括号不能省略
+        let is_sign = matches!(self, Self::Negate(_) | Self::Assert(_));
+        let paren = operand.precedence() < self.precedence()
+            || (is_sign && (operand_str.starts_with('-') || operand_str.starts_with('+')));
+
+        if paren {
+            write!(f, "{}({})", op, operand_str)
+        } else {
+            write!(f, "{}{}", op, operand_str)
+        }
+    }
+}
+
+fn fmt_operand(f: &mut fmt::Formatter<'_>, operand: &Expression, paren: bool) -> fmt::Result {
+    if paren {
+        write!(f, "({})", operand)
+    } else {
+        write!(f, "{}", operand)
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::And(lhs, rhs) => self.fmt_infix(f, lhs, "AND", rhs),
+            Self::Or(lhs, rhs) => self.fmt_infix(f, lhs, "OR", rhs),
+            Self::Not(operand) => self.fmt_prefix(f, "NOT ", operand),
+
+            Self::NotEqual(lhs, rhs) => self.fmt_infix(f, lhs, "!=", rhs),
+            Self::Equal(lhs, rhs) => self.fmt_infix(f, lhs, "=", rhs),
+            Self::GreaterThan(lhs, rhs) => self.fmt_infix(f, lhs, ">", rhs),
+            Self::GreaterThanOrEqual(lhs, rhs) => self.fmt_infix(f, lhs, ">=", rhs),
+            Self::LessThan(lhs, rhs) => self.fmt_infix(f, lhs, "<", rhs),
+            Self::LessThanOrEqual(lhs, rhs) => self.fmt_infix(f, lhs, "<=", rhs),
+
+            Self::IsNull(operand) => self.fmt_postfix(f, operand, "IS NULL"),
+            Self::IsNotNull(operand) => self.fmt_postfix(f, operand, "IS NOT NULL"),
+
+            Self::In(operand, list) => {
+                self.fmt_postfix(f, operand, &format!("IN ({})", CommaSeparated(list)))
+            }
+            Self::NotIn(operand, list) => {
+                self.fmt_postfix(f, operand, &format!("NOT IN ({})", CommaSeparated(list)))
+            }
+            Self::InSubquery(operand, query) => {
+                self.fmt_postfix(f, operand, &format!("IN ({})", query))
+            }
+            Self::NotInSubquery(operand, query) => {
+                self.fmt_postfix(f, operand, &format!("NOT IN ({})", query))
+            }
+            Self::Between(operand, low, high) => self.fmt_between(f, operand, "BETWEEN", low, high),
+            Self::NotBetween(operand, low, high) => {
+                self.fmt_between(f, operand, "NOT BETWEEN", low, high)
+            }
+
+            Self::Add(lhs, rhs) => self.fmt_infix(f, lhs, "+", rhs),
+            Self::Subtract(lhs, rhs) => self.fmt_infix(f, lhs, "-", rhs),
+            Self::Multiply(lhs, rhs) => self.fmt_infix(f, lhs, "*", rhs),
+            Self::Divide(lhs, rhs) => self.fmt_infix(f, lhs, "/", rhs),
+
+            Self::Assert(operand) => self.fmt_prefix(f, "+", operand),
+            Self::Like(lhs, rhs) => self.fmt_infix(f, lhs, "LIKE", rhs),
+            Self::NotLike(lhs, rhs) => self.fmt_infix(f, lhs, "NOT LIKE", rhs),
+
+            Self::Negate(operand) => self.fmt_prefix(f, "-", operand),
+            Self::BitWiseNot(operand) => self.fmt_prefix(f, "~", operand),
+            Self::Modulo(lhs, rhs) => self.fmt_infix(f, lhs, "%", rhs),
+        }
+    }
+}
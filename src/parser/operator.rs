@@ -1,32 +1,53 @@
 use super::keyword::Keyword;
 use super::token::{self, Token};
 
-#[derive(Eq, PartialEq, Debug, PartialOrd, Ord)]
+// 优先级从低到高, 和标准 SQL 一致: OR < AND < NOT < 比较运算符
+#[derive(Eq, PartialEq, Debug, PartialOrd, Ord, Clone, Copy)]
 pub enum Precedence {
     Lowest,      // 最低优先级
-    Equals,      // ==
-    Logical,     // AND OR NOT 逻辑运算符
+    Or,          // OR
+    And,         // AND
+    Not,         // NOT X
+    Equals,      // = != LIKE IN BETWEEN IS
     LessGreater, // > or < or >= or <=
-    Sum,         // +
-    Product,     // * /
+    Sum,         // + -
+    Product,     // * / %
     Prefix,      // -X or !X
-    Call,        // function(x)
+    Call,        // function(x) x::INT
 }
 
 #[warn(clippy::match_like_matches_macro)]
 pub fn is_prefix_oper(t: &token::Token) -> bool {
+    // 非保留关键字可以作为列名和函数名
+    if let Token::KeyWord(k) = t {
+        if !k.is_reserved() {
+            return true;
+        }
+    }
+
     matches!(
         t,
         Token::Exclamation
+            | Token::Tilde
             | Token::Minus
             | Token::Add
             | Token::LeftParen
-            | Token::Number(_)
+            | Token::Integer(_)
+            | Token::Float(_)
+            | Token::HexInteger(_)
             | Token::Ident(_)
+            | Token::Question
+            | Token::Parameter(_)
             | Token::KeyWord(Keyword::True)
             | Token::KeyWord(Keyword::False)
             | Token::String(_)
             | Token::KeyWord(Keyword::Null)
+            | Token::KeyWord(Keyword::Not)
+            | Token::KeyWord(Keyword::Infinity)
+            | Token::KeyWord(Keyword::NaN)
+            | Token::KeyWord(Keyword::Case)
+            | Token::KeyWord(Keyword::Cast)
+            | Token::KeyWord(Keyword::Exists)
     )
 }
 
@@ -48,24 +69,35 @@ pub fn is_infix_oper(t: &token::Token) -> bool {
             | Token::KeyWord(Keyword::And)
             | Token::KeyWord(Keyword::Like)
             | Token::KeyWord(Keyword::Or)
+            | Token::KeyWord(Keyword::Is)
+            | Token::KeyWord(Keyword::In)
+            | Token::KeyWord(Keyword::Between)
+            | Token::KeyWord(Keyword::Not)
+            | Token::DoubleColon
             | Token::LeftParen
     )
 }
 
 pub fn match_precedence(t: &Token) -> Precedence {
     match t {
-        Token::Equal | Token::NotEqual => Precedence::Equals,
+        Token::Equal
+        | Token::NotEqual
+        | Token::KeyWord(Keyword::Like)
+        | Token::KeyWord(Keyword::Is)
+        | Token::KeyWord(Keyword::In)
+        | Token::KeyWord(Keyword::Between)
+        // 中缀的 NOT 只出现在 NOT IN, NOT BETWEEN, NOT LIKE 中
+        | Token::KeyWord(Keyword::Not) => Precedence::Equals,
         Token::LessThan
         | Token::LessThanOrEqual
         | Token::GreaterThan
         | Token::GreaterThanOrEqual => Precedence::LessGreater,
-        Token::KeyWord(Keyword::And)
-        | Token::KeyWord(Keyword::Or)
-        | Token::KeyWord(Keyword::Not) => Precedence::Logical,
+        Token::KeyWord(Keyword::Or) => Precedence::Or,
+        Token::KeyWord(Keyword::And) => Precedence::And,
         Token::Add | Token::Minus => Precedence::Sum,
-        Token::Slash => Precedence::Product,
-        Token::Asterisk => Precedence::Product,
-        Token::LeftParen => Precedence::Call,
+        Token::Slash | Token::Asterisk | Token::Percent => Precedence::Product,
+        // -1::INT 等价于 -(1::INT)
+        Token::LeftParen | Token::DoubleColon => Precedence::Call,
         _ => Precedence::Lowest,
     }
 }
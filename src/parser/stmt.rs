@@ -1,14 +1,23 @@
-use std::collections::BTreeMap;
+use std::fmt;
 
-use crate::parser::column::Column;
+use crate::parser::column::{Column, TableConstraint};
 
-use super::expression::Expression;
+use super::display::{CommaSeparated, Ident, IdentList, OrderByList, QuotedString};
+use super::expression::{Expression, WindowSpec};
 
+// 每条语句只构造一次, SelectStmt 较大也不需要装箱
+#[allow(clippy::large_enum_variant)]
 #[derive(PartialEq, Debug)]
 pub enum Statement {
     Begin(BeginStmt),
     Commit,
     Rollback,
+    // SAVEPOINT name
+    Savepoint(String),
+    // RELEASE [SAVEPOINT] name
+    ReleaseSavepoint(String),
+    // ROLLBACK TO [SAVEPOINT] name
+    RollbackTo(String),
     Explain(ExplainStmt),
     CreateTable(CreateTableStmt),
     DropTable(DropTableStmt),
@@ -16,79 +25,154 @@ pub enum Statement {
     Insert(InsertStmt),
     Update(UpdateStmt),
     Select(SelectStmt),
+    // 带有 WITH 或集合运算的查询, 单个 SELECT 仍然是 Select
+    Query(Query),
     Alter(AlterStmt),
     CreateIndex(CreateIndexStmt),
+    DropIndex(DropIndexStmt),
+    CreateView(CreateViewStmt),
+    DropView(DropViewStmt),
+    // REFRESH MATERIALIZED VIEW name
+    RefreshMaterializedView(String),
     ShowDatabase,
     ShowTables,
+    // SHOW COLUMNS FROM table_name
+    ShowColumns(String),
+    // SHOW INDEXES FROM table_name
+    ShowIndexes(String),
+    // SHOW CREATE TABLE table_name
+    ShowCreateTable(String),
+    ShowVariables(ShowVariablesStmt),
+    CreateDatabase(CreateDatabaseStmt),
+    DropDatabase(DropDatabaseStmt),
+    // USE database_name
+    Use(String),
+    // TRUNCATE [TABLE] table_name [, ...]
+    Truncate(Vec<String>),
     Set(SetStmt),
     DescribeTable(String),
-}
-
-impl std::fmt::Display for Statement {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Begin(begin_stmt) => {
-                write!(f, "Statement: {}", begin_stmt)
-            }
-            Self::Commit => {
-                write!(f, "Statement: Commit")
-            }
-            _ => {
-                write!(f, "Statement: Unknown")
-            }
-        }
-    }
+    Prepare(PrepareStmt),
+    Execute(ExecuteStmt),
+    // DEALLOCATE name, None 表示 DEALLOCATE ALL
+    Deallocate(Option<String>),
 }
 
 #[derive(Eq, PartialEq, Debug)]
 pub struct BeginStmt {
     pub is_readonly: bool,
-    pub version: Option<u64>,
+    pub isolation_level: Option<TransactionIsolationLevel>,
+    pub version: Option<SystemTime>,
 }
 
-impl std::fmt::Display for BeginStmt {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "BeginStmt [is_readonly: {} version: {}]",
-            self.is_readonly,
-            match self.version {
-                Some(n) => n.to_string(),
-                None => "None".to_string(),
-            },
-        )
-    }
+// AS OF SYSTEM TIME 之后的版本号或者时间戳
+// 时间戳是 RFC 3339, YYYY-MM-DD HH:MM:SS[.f] 或者 YYYY-MM-DD 格式的原始字符串
+#[derive(Eq, PartialEq, Debug)]
+pub enum SystemTime {
+    Version(u64),
+    Timestamp(String),
 }
 
 #[derive(PartialEq, Debug)]
 pub struct ExplainStmt {
     pub statement: Box<Statement>,
 }
+// PREPARE name AS statement, statement 中可以使用绑定参数
+#[derive(PartialEq, Debug)]
+pub struct PrepareStmt {
+    pub name: String,
+    pub statement: Box<Statement>,
+}
+// EXECUTE name [(params)]
+#[derive(PartialEq, Debug)]
+pub struct ExecuteStmt {
+    pub name: String,
+    pub params: Vec<Expression>,
+}
 #[derive(PartialEq, Debug)]
 pub struct CreateTableStmt {
     pub columns: Vec<Column>,
+    pub constraints: Vec<TableConstraint>,
     pub table_name: String,
+    pub if_not_exists: bool,
 }
+// SHOW [SESSION | GLOBAL] VARIABLES [LIKE pattern]
+#[derive(Eq, PartialEq, Debug)]
+pub struct ShowVariablesStmt {
+    pub is_session: bool,
+    pub like: Option<String>,
+}
+#[derive(Eq, PartialEq, Debug)]
+pub struct CreateDatabaseStmt {
+    pub name: String,
+    pub if_not_exists: bool,
+}
+#[derive(Eq, PartialEq, Debug)]
+pub struct DropDatabaseStmt {
+    pub name: String,
+    pub if_exists: bool,
+}
+// DROP TABLE [IF EXISTS] table_name [, ...] [CASCADE]
 #[derive(Eq, PartialEq, Debug)]
 pub struct DropTableStmt {
-    pub table_name: String,
+    pub table_names: Vec<String>,
+    pub if_exists: bool,
+    pub cascade: bool,
 }
+// DELETE FROM table_name [USING using] [WHERE where]
+//     [ORDER BY order] [LIMIT limit] [RETURNING returning]
 #[derive(PartialEq, Debug)]
 pub struct DeleteTableStmt {
     pub table_name: String,
+    pub using: Option<FromItem>,
     pub r#where: Option<Expression>,
+    pub order: Option<Vec<(Expression, OrderByType)>>,
+    pub limit: Option<Expression>,
+    pub returning: Option<Vec<SelectItem>>,
 }
 #[derive(PartialEq, Debug)]
 pub struct InsertStmt {
     pub table_name: String,
     pub columns: Option<Vec<String>>,
-    pub values: Vec<Vec<Option<Expression>>>,
+    pub source: InsertSource,
+    pub on_conflict: Option<OnConflict>,
+    pub returning: Option<Vec<SelectItem>>,
+}
+#[derive(PartialEq, Debug)]
+pub enum InsertSource {
+    // VALUES (1, DEFAULT), (2, 3), None 表示 DEFAULT
+    Values(Vec<Vec<Option<Expression>>>),
+    // INSERT INTO t SELECT ...
+    Query(Box<Query>),
+    // DEFAULT VALUES
+    DefaultValues,
+}
+// ON CONFLICT [(columns)] DO NOTHING
+// ON CONFLICT (columns) DO UPDATE SET a = excluded.a [WHERE wheres]
+#[derive(PartialEq, Debug)]
+pub struct OnConflict {
+    pub columns: Option<Vec<String>>,
+    pub action: ConflictAction,
+}
+#[derive(PartialEq, Debug)]
+pub enum ConflictAction {
+    Nothing,
+    Update {
+        set: Vec<(String, Expression)>,
+        wheres: Option<Expression>,
+    },
 }
+// UPDATE table_name SET column = expr, ... [FROM from] [WHERE wheres]
+//     [ORDER BY order] [LIMIT limit] [RETURNING returning]
 #[derive(PartialEq, Debug)]
 pub struct UpdateStmt {
     pub table_name: String,
-    pub set: BTreeMap<String, Expression>,
+    // 按照 SQL 中的顺序保存, 同一列不能出现两次
+    pub set: Vec<(String, Expression)>,
+    pub from: Option<FromItem>,
     pub wheres: Option<Expression>,
+    pub order: Option<Vec<(Expression, OrderByType)>>,
+    pub limit: Option<Expression>,
+    pub returning: Option<Vec<SelectItem>>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -97,20 +181,39 @@ pub enum FromItem {
         name: String,
         alias: Option<String>,
     },
+    // FROM (SELECT ...) [AS] alias
+    Subquery {
+        query: Box<Query>,
+        alias: Option<String>,
+    },
+    // FROM a, b 等价于 FROM a CROSS JOIN b
     Join {
         left: Box<FromItem>,
         right: Box<FromItem>,
         join_type: JoinType,
-        predicate: Option<Expression>,
+        constraint: JoinConstraint,
     },
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum JoinType {
+    Inner,
     Left,
     Right,
-    Outer,
-    Inner,
+    Full,
+    Cross,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum JoinConstraint {
+    // ON a.id = b.id
+    On(Expression),
+    // USING (id, name)
+    Using(Vec<String>),
+    // NATURAL JOIN, 按同名的列连接
+    Natural,
+    // CROSS JOIN 没有连接条件
+    None,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -123,16 +226,104 @@ pub enum OrderByType {
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct SelectStmt {
-    pub selects: Vec<(Expression, Option<String>)>,
-    pub froms: Option<Vec<FromItem>>,
+    pub distinct: Option<Distinct>,
+    pub selects: Vec<SelectItem>,
+    pub from: Option<FromItem>,
     pub wheres: Option<Expression>,
     pub group_by: Option<Vec<Expression>>,
     pub having: Option<Expression>,
+    pub windows: Option<Vec<(String, WindowSpec)>>,
+    pub order: Option<Vec<(Expression, OrderByType)>>,
+    pub offset: Option<Expression>,
+    pub limit: Option<Expression>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Distinct {
+    // SELECT DISTINCT
+    Distinct,
+    // SELECT DISTINCT ON (a, b)
+    On(Vec<Expression>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SelectItem {
+    // SELECT *
+    Wildcard,
+    // SELECT t.*
+    QualifiedWildcard(String),
+    // SELECT expr [AS alias]
+    Expr(Expression, Option<String>),
+}
+
+// [WITH [RECURSIVE] cte [, ...]] body [ORDER BY ...] [LIMIT limit] [OFFSET offset]
+// body 只是单个 SELECT 时, ORDER BY, LIMIT 和 OFFSET 保存在 SelectStmt 中
+#[derive(Debug, PartialEq, Clone)]
+pub struct Query {
+    pub with: Option<With>,
+    pub body: SetExpr,
     pub order: Option<Vec<(Expression, OrderByType)>>,
     pub offset: Option<Expression>,
     pub limit: Option<Expression>,
 }
 
+impl From<SelectStmt> for Query {
+    fn from(select: SelectStmt) -> Self {
+        Self {
+            with: None,
+            body: SetExpr::Select(Box::new(select)),
+            order: None,
+            offset: None,
+            limit: None,
+        }
+    }
+}
+
+// SELECT ... UNION [ALL] SELECT ... INTERSECT (SELECT ...)
+#[derive(Debug, PartialEq, Clone)]
+pub enum SetExpr {
+    Select(Box<SelectStmt>),
+    // 括号中的查询
+    Query(Box<Query>),
+    SetOperation {
+        op: SetOperator,
+        all: bool,
+        left: Box<SetExpr>,
+        right: Box<SetExpr>,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SetOperator {
+    Union,
+    Intersect,
+    Except,
+}
+
+impl SetOperator {
+    // INTERSECT 的优先级比 UNION 和 EXCEPT 高
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Self::Union | Self::Except => 1,
+            Self::Intersect => 2,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct With {
+    pub recursive: bool,
+    pub ctes: Vec<Cte>,
+}
+
+// name [(column, ...)] AS (query)
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cte {
+    pub name: String,
+    pub columns: Option<Vec<String>>,
+    pub query: Query,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum AlterType {
     AddColumn(Column),                     // 增加列
@@ -150,7 +341,7 @@ pub struct AlterStmt {
     pub table_name: String,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum TransactionIsolationLevel {
     ReadUncommitted, // 读未提交
     ReadCommitted,   // 读已提交
@@ -178,8 +369,628 @@ pub struct SetStmt {
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct CreateIndexStmt {
-    pub index_name: String,
-    pub is_unique: bool, // 是否是唯一索引
+    pub index_name: Option<String>, // CREATE INDEX ON t (a) 没有名称
+    pub is_unique: bool,            // 是否是唯一索引
+    pub if_not_exists: bool,
     pub table_name: String,
-    pub columns: Vec<String>,
+    pub columns: Vec<Expression>,   // 列名或者表达式
+    pub wheres: Option<Expression>, // 部分索引的条件
+}
+
+// CREATE [OR REPLACE] [MATERIALIZED] VIEW name [(column, ...)] AS query
+#[derive(PartialEq, Debug)]
+pub struct CreateViewStmt {
+    pub name: String,
+    pub columns: Option<Vec<String>>,
+    pub query: Box<Query>,
+    pub or_replace: bool,
+    pub materialized: bool,
+}
+
+// DROP [MATERIALIZED] VIEW [IF EXISTS] name [, ...] [CASCADE]
+#[derive(Eq, PartialEq, Debug)]
+pub struct DropViewStmt {
+    pub names: Vec<String>,
+    pub materialized: bool,
+    pub if_exists: bool,
+    pub cascade: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DropIndexStmt {
+    pub index_name: String,
+    pub if_exists: bool,
+}
+
+// 将语法树还原为 SQL, 关键字统一大写, 标识符在必要时加上双引号
+// 重新解析输出的 SQL 可以得到相同的语法树
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Begin(stmt) => write!(f, "{}", stmt),
+            Self::Commit => write!(f, "COMMIT"),
+            Self::Rollback => write!(f, "ROLLBACK"),
+            Self::Savepoint(name) => write!(f, "SAVEPOINT {}", Ident(name)),
+            Self::ReleaseSavepoint(name) => write!(f, "RELEASE SAVEPOINT {}", Ident(name)),
+            Self::RollbackTo(name) => write!(f, "ROLLBACK TO SAVEPOINT {}", Ident(name)),
+            Self::Explain(stmt) => write!(f, "EXPLAIN {}", stmt.statement),
+            Self::CreateTable(stmt) => write!(f, "{}", stmt),
+            Self::DropTable(stmt) => write!(f, "{}", stmt),
+            Self::Delete(stmt) => write!(f, "{}", stmt),
+            Self::Insert(stmt) => write!(f, "{}", stmt),
+            Self::Update(stmt) => write!(f, "{}", stmt),
+            Self::Select(stmt) => write!(f, "{}", stmt),
+            Self::Query(query) => write!(f, "{}", query),
+            Self::Alter(stmt) => write!(f, "{}", stmt),
+            Self::CreateIndex(stmt) => write!(f, "{}", stmt),
+            Self::CreateView(stmt) => write!(f, "{}", stmt),
+            Self::DropView(stmt) => write!(f, "{}", stmt),
+            Self::RefreshMaterializedView(name) => {
+                write!(f, "REFRESH MATERIALIZED VIEW {}", Ident(name))
+            }
+            Self::DropIndex(stmt) => {
+                write!(f, "DROP INDEX ")?;
+                if stmt.if_exists {
+                    write!(f, "IF EXISTS ")?;
+                }
+                write!(f, "{}", Ident(&stmt.index_name))
+            }
+            Self::ShowDatabase => write!(f, "SHOW DATABASES"),
+            Self::ShowTables => write!(f, "SHOW TABLES"),
+            Self::ShowColumns(table) => write!(f, "SHOW COLUMNS FROM {}", Ident(table)),
+            Self::ShowIndexes(table) => write!(f, "SHOW INDEXES FROM {}", Ident(table)),
+            Self::ShowCreateTable(table) => write!(f, "SHOW CREATE TABLE {}", Ident(table)),
+            Self::ShowVariables(stmt) => {
+                write!(f, "SHOW ")?;
+                if !stmt.is_session {
+                    write!(f, "GLOBAL ")?;
+                }
+                write!(f, "VARIABLES")?;
+                if let Some(pattern) = &stmt.like {
+                    write!(f, " LIKE {}", QuotedString(pattern))?;
+                }
+                Ok(())
+            }
+            Self::CreateDatabase(stmt) => {
+                write!(f, "CREATE DATABASE ")?;
+                if stmt.if_not_exists {
+                    write!(f, "IF NOT EXISTS ")?;
+                }
+                write!(f, "{}", Ident(&stmt.name))
+            }
+            Self::DropDatabase(stmt) => {
+                write!(f, "DROP DATABASE ")?;
+                if stmt.if_exists {
+                    write!(f, "IF EXISTS ")?;
+                }
+                write!(f, "{}", Ident(&stmt.name))
+            }
+            Self::Use(name) => write!(f, "USE {}", Ident(name)),
+            Self::Truncate(tables) => write!(f, "TRUNCATE TABLE {}", IdentList(tables)),
+            Self::Set(stmt) => write!(f, "{}", stmt),
+            Self::DescribeTable(name) => write!(f, "DESCRIBE {}", Ident(name)),
+            Self::Prepare(stmt) => {
+                write!(f, "PREPARE {} AS {}", Ident(&stmt.name), stmt.statement)
+            }
+            Self::Execute(stmt) => {
+                write!(f, "EXECUTE {}", Ident(&stmt.name))?;
+                if !stmt.params.is_empty() {
+                    write!(f, "({})", CommaSeparated(&stmt.params))?;
+                }
+                Ok(())
+            }
+            Self::Deallocate(Some(name)) => write!(f, "DEALLOCATE {}", Ident(name)),
+            Self::Deallocate(None) => write!(f, "DEALLOCATE ALL"),
+        }
+    }
+}
+
+// BEGIN [ISOLATION LEVEL level] [READ ONLY] [AS OF SYSTEM TIME version]
+impl fmt::Display for BeginStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BEGIN")?;
+        if let Some(level) = &self.isolation_level {
+            write!(f, " ISOLATION LEVEL {}", level)?;
+        }
+        if self.is_readonly {
+            write!(f, " READ ONLY")?;
+        }
+        match &self.version {
+            Some(SystemTime::Version(version)) => write!(f, " AS OF SYSTEM TIME {}", version)?,
+            Some(SystemTime::Timestamp(timestamp)) => {
+                write!(f, " AS OF SYSTEM TIME {}", QuotedString(timestamp))?
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for CreateTableStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CREATE TABLE ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        write!(
+            f,
+            "{} ({}",
+            Ident(&self.table_name),
+            CommaSeparated(&self.columns)
+        )?;
+        // 列和表级约束是同一个逗号分隔的列表, 可以没有列
+        for (i, constraint) in self.constraints.iter().enumerate() {
+            if i > 0 || !self.columns.is_empty() {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", constraint)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for DeleteTableStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DELETE FROM {}", Ident(&self.table_name))?;
+        if let Some(using) = &self.using {
+            write!(f, " USING {}", using)?;
+        }
+        if let Some(expr) = &self.r#where {
+            write!(f, " WHERE {}", expr)?;
+        }
+        fmt_order_limit(f, &self.order, &self.limit, &None)?;
+        fmt_returning(f, &self.returning)
+    }
+}
+
+impl fmt::Display for InsertStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "INSERT INTO {}", Ident(&self.table_name))?;
+        if let Some(columns) = &self.columns {
+            write!(f, " ({})", IdentList(columns))?;
+        }
+        write!(f, " {}", self.source)?;
+        if let Some(on_conflict) = &self.on_conflict {
+            write!(f, " {}", on_conflict)?;
+        }
+        fmt_returning(f, &self.returning)
+    }
+}
+
+// INSERT, UPDATE 和 DELETE 的 RETURNING 子句
+fn fmt_returning(f: &mut fmt::Formatter<'_>, returning: &Option<Vec<SelectItem>>) -> fmt::Result {
+    if let Some(items) = returning {
+        write!(f, " RETURNING {}", CommaSeparated(items))?;
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for InsertSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Values(values) => {
+                write!(f, "VALUES ")?;
+                for (i, row) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "(")?;
+                    for (j, value) in row.iter().enumerate() {
+                        if j > 0 {
+                            write!(f, ", ")?;
+                        }
+                        match value {
+                            Some(expr) => write!(f, "{}", expr)?,
+                            None => write!(f, "DEFAULT")?,
+                        }
+                    }
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Self::Query(query) => write!(f, "{}", query),
+            Self::DefaultValues => write!(f, "DEFAULT VALUES"),
+        }
+    }
+}
+
+impl fmt::Display for OnConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ON CONFLICT")?;
+        if let Some(columns) = &self.columns {
+            write!(f, " ({})", IdentList(columns))?;
+        }
+        match &self.action {
+            ConflictAction::Nothing => write!(f, " DO NOTHING"),
+            ConflictAction::Update { set, wheres } => {
+                write!(f, " DO UPDATE SET ")?;
+                fmt_assignments(f, set)?;
+                if let Some(expr) = wheres {
+                    write!(f, " WHERE {}", expr)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for UpdateStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "UPDATE {} SET ", Ident(&self.table_name))?;
+        fmt_assignments(f, &self.set)?;
+        if let Some(from) = &self.from {
+            write!(f, " FROM {}", from)?;
+        }
+        if let Some(expr) = &self.wheres {
+            write!(f, " WHERE {}", expr)?;
+        }
+        fmt_order_limit(f, &self.order, &self.limit, &None)?;
+        fmt_returning(f, &self.returning)
+    }
+}
+
+// UPDATE 和 ON CONFLICT DO UPDATE 中的 a = 1, b = 2
+fn fmt_assignments(f: &mut fmt::Formatter<'_>, set: &[(String, Expression)]) -> fmt::Result {
+    for (i, (column, expr)) in set.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{} = {}", Ident(column), expr)?;
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for FromItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Table { name, alias } => {
+                write!(f, "{}", Ident(name))?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {}", Ident(alias))?;
+                }
+            }
+            Self::Subquery { query, alias } => {
+                write!(f, "({})", query)?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {}", Ident(alias))?;
+                }
+            }
+            Self::Join {
+                left,
+                right,
+                join_type,
+                constraint,
+            } => {
+                write!(f, "{} ", left)?;
+                if *constraint == JoinConstraint::Natural {
+                    write!(f, "NATURAL ")?;
+                }
+                // JOIN 是左结合的, 右边的 JOIN 需要加括号
+                match right.as_ref() {
+                    Self::Join { .. } => write!(f, "{} ({})", join_type, right)?,
+                    _ => write!(f, "{} {}", join_type, right)?,
+                }
+                match constraint {
+                    JoinConstraint::On(predicate) => write!(f, " ON {}", predicate)?,
+                    JoinConstraint::Using(columns) => write!(f, " USING ({})", IdentList(columns))?,
+                    JoinConstraint::Natural | JoinConstraint::None => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for JoinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Inner => write!(f, "JOIN"),
+            Self::Left => write!(f, "LEFT JOIN"),
+            Self::Right => write!(f, "RIGHT JOIN"),
+            Self::Full => write!(f, "FULL JOIN"),
+            Self::Cross => write!(f, "CROSS JOIN"),
+        }
+    }
+}
+
+impl fmt::Display for Distinct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Distinct => write!(f, "DISTINCT"),
+            Self::On(exprs) => write!(f, "DISTINCT ON ({})", CommaSeparated(exprs)),
+        }
+    }
+}
+
+impl fmt::Display for SelectItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Wildcard => write!(f, "*"),
+            Self::QualifiedWildcard(table) => write!(f, "{}.*", Ident(table)),
+            Self::Expr(expr, None) => write!(f, "{}", expr),
+            Self::Expr(expr, Some(alias)) => write!(f, "{} AS {}", expr, Ident(alias)),
+        }
+    }
+}
+
+// SELECT [DISTINCT [ON (exprs)]] selects [FROM from] [WHERE wheres] [GROUP BY group_by]
+//        [HAVING having] [WINDOW windows] [ORDER BY order] [LIMIT limit] [OFFSET offset]
+impl fmt::Display for SelectStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SELECT ")?;
+        if let Some(distinct) = &self.distinct {
+            write!(f, "{} ", distinct)?;
+        }
+        write!(f, "{}", CommaSeparated(&self.selects))?;
+        if let Some(from) = &self.from {
+            write!(f, " FROM {}", from)?;
+        }
+        if let Some(expr) = &self.wheres {
+            write!(f, " WHERE {}", expr)?;
+        }
+        if let Some(exprs) = &self.group_by {
+            write!(f, " GROUP BY {}", CommaSeparated(exprs))?;
+        }
+        if let Some(expr) = &self.having {
+            write!(f, " HAVING {}", expr)?;
+        }
+        if let Some(windows) = &self.windows {
+            write!(f, " WINDOW ")?;
+            for (i, (name, spec)) in windows.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{} AS ({})", Ident(name), spec)?;
+            }
+        }
+        fmt_order_limit(f, &self.order, &self.limit, &self.offset)
+    }
+}
+
+// [ORDER BY order] [LIMIT limit] [OFFSET offset]
+fn fmt_order_limit(
+    f: &mut fmt::Formatter<'_>,
+    order: &Option<Vec<(Expression, OrderByType)>>,
+    limit: &Option<Expression>,
+    offset: &Option<Expression>,
+) -> fmt::Result {
+    if let Some(orders) = order {
+        write!(f, " ORDER BY {}", OrderByList(orders))?;
+    }
+    if let Some(expr) = limit {
+        write!(f, " LIMIT {}", expr)?;
+    }
+    if let Some(expr) = offset {
+        write!(f, " OFFSET {}", expr)?;
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(with) = &self.with {
+            write!(f, "{} ", with)?;
+        }
+        write!(f, "{}", self.body)?;
+        fmt_order_limit(f, &self.order, &self.limit, &self.offset)
+    }
+}
+
+impl SetExpr {
+    // 和二元运算符一样, 左边优先级更低或者右边优先级不高于自己的子查询需要加括号
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, paren: bool) -> fmt::Result {
+        if paren {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::SetOperation { op, .. } => op.precedence(),
+            // 否则 ORDER BY 和 LIMIT 会被当成整个集合运算的子句
+            Self::Select(select)
+                if select.order.is_some() || select.limit.is_some() || select.offset.is_some() =>
+            {
+                0
+            }
+            _ => u8::MAX,
+        }
+    }
+}
+
+impl fmt::Display for SetExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Select(select) => write!(f, "{}", select),
+            Self::Query(query) => write!(f, "({})", query),
+            Self::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                let precedence = op.precedence();
+                left.fmt_operand(f, left.precedence() < precedence)?;
+                write!(f, " {}", op)?;
+                if *all {
+                    write!(f, " ALL")?;
+                }
+                write!(f, " ")?;
+                right.fmt_operand(f, right.precedence() <= precedence)
+            }
+        }
+    }
+}
+
+impl fmt::Display for SetOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Union => write!(f, "UNION"),
+            Self::Intersect => write!(f, "INTERSECT"),
+            Self::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
+impl fmt::Display for With {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WITH ")?;
+        if self.recursive {
+            write!(f, "RECURSIVE ")?;
+        }
+        write!(f, "{}", CommaSeparated(&self.ctes))
+    }
+}
+
+impl fmt::Display for Cte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Ident(&self.name))?;
+        if let Some(columns) = &self.columns {
+            write!(f, " ({})", IdentList(columns))?;
+        }
+        write!(f, " AS ({})", self.query)
+    }
+}
+
+impl fmt::Display for AlterStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ALTER TABLE {} ", Ident(&self.table_name))?;
+        match &self.alter_type {
+            AlterType::AddColumn(column) => write!(f, "ADD COLUMN {}", column),
+            AlterType::DropColumn(name) => write!(f, "DROP COLUMN {}", Ident(name)),
+            AlterType::ModifyColumn(column) => write!(f, "MODIFY COLUMN {}", column),
+            AlterType::RenameColumn(old_name, new_name) => write!(
+                f,
+                "RENAME COLUMN {} TO {}",
+                Ident(old_name),
+                Ident(new_name)
+            ),
+            AlterType::RenameTable(name) => write!(f, "RENAME TO {}", Ident(name)),
+            AlterType::AddIndex(Some(name), columns) => {
+                write!(f, "ADD INDEX {} ({})", Ident(name), IdentList(columns))
+            }
+            AlterType::AddIndex(None, columns) => write!(f, "ADD INDEX ({})", IdentList(columns)),
+            AlterType::RemoveIndex(name) => write!(f, "DROP INDEX {}", Ident(name)),
+        }
+    }
+}
+
+impl fmt::Display for TransactionIsolationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ReadUncommitted => write!(f, "READ UNCOMMITTED"),
+            Self::ReadCommitted => write!(f, "READ COMMITTED"),
+            Self::RepeatableRead => write!(f, "REPEATABLE READ"),
+            Self::Serializable => write!(f, "SERIALIZABLE"),
+        }
+    }
+}
+
+impl fmt::Display for SetStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SET ")?;
+        if !self.is_session {
+            write!(f, "GLOBAL ")?;
+        }
+        match &self.set_value {
+            SetVariableType::Transaction(level) => {
+                write!(f, "TRANSACTION ISOLATION LEVEL {}", level)
+            }
+            SetVariableType::Value(SetValue {
+                variable_name,
+                value,
+            }) => write!(f, "{} = {}", Ident(variable_name), value),
+        }
+    }
+}
+
+impl fmt::Display for CreateIndexStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CREATE ")?;
+        if self.is_unique {
+            write!(f, "UNIQUE ")?;
+        }
+        write!(f, "INDEX ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        if let Some(name) = &self.index_name {
+            write!(f, "{} ", Ident(name))?;
+        }
+        write!(f, "ON {} (", Ident(&self.table_name))?;
+        for (i, expr) in self.columns.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            // 列名和函数调用之外的表达式需要加括号
+            match expr.unspanned() {
+                Expression::Field(_, _) | Expression::Function(_) => write!(f, "{}", expr)?,
+                _ => write!(f, "({})", expr)?,
+            }
+        }
+        write!(f, ")")?;
+        if let Some(expr) = &self.wheres {
+            write!(f, " WHERE {}", expr)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for CreateViewStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CREATE ")?;
+        if self.or_replace {
+            write!(f, "OR REPLACE ")?;
+        }
+        if self.materialized {
+            write!(f, "MATERIALIZED ")?;
+        }
+        write!(f, "VIEW {}", Ident(&self.name))?;
+        if let Some(columns) = &self.columns {
+            write!(f, " ({})", IdentList(columns))?;
+        }
+        write!(f, " AS {}", self.query)
+    }
+}
+
+impl fmt::Display for DropViewStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DROP ")?;
+        if self.materialized {
+            write!(f, "MATERIALIZED ")?;
+        }
+        write!(f, "VIEW ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        write!(f, "{}", IdentList(&self.names))?;
+        if self.cascade {
+            write!(f, " CASCADE")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for DropTableStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DROP TABLE ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        write!(f, "{}", IdentList(&self.table_names))?;
+        if self.cascade {
+            write!(f, " CASCADE")?;
+        }
+
+        Ok(())
+    }
 }
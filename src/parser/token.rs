@@ -1,12 +1,17 @@
 use super::keyword::Keyword;
+use super::span::Span;
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Clone)]
 pub enum Token {
-    Number(String),     // 数字
+    Integer(String),    // 整数, 123 1_000
+    Float(String),      // 浮点数, 1.5 .5 1e10
+    HexInteger(String), // 十六进制整数, 0x1F 保存为 1F
     String(String),     // 字符串, 'xxx' "xxx"
     Ident(String),      // 用户定义
+    Parameter(String),  // 绑定参数, $1 或 :name
     KeyWord(Keyword),   // 关键字
     Period,             // .
+    DoubleColon,        // ::
     Equal,              // =
     GreaterThan,        // >
     GreaterThanOrEqual, // >=
@@ -19,6 +24,7 @@ pub enum Token {
     Caret,              // ^
     Percent,            // %
     Exclamation,        // !
+    Tilde,              // ~
     NotEqual,           // !=
     Question,           // ?
     LeftParen,          // (
@@ -26,6 +32,15 @@ pub enum Token {
     Comma,              // ,
     Semicolon,          // ;
     Eof,                // 语句结束
+    Illegal(String),    // 非法输入, 例如未闭合的字符串
+    Comment(String),    // -- xxx 或 /* xxx */, 只有 Lexer::with_comments 时才会返回
+}
+
+// lexer 产生的 Token 以及它在 SQL 中的位置
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
 }
 
 impl std::fmt::Display for Token {
@@ -34,11 +49,15 @@ impl std::fmt::Display for Token {
             f,
             "Token: {}",
             match self {
-                Self::Number(number) => format!("Number {}", number),
+                Self::Integer(number) => format!("Integer {}", number),
+                Self::Float(number) => format!("Float {}", number),
+                Self::HexInteger(number) => format!("HexInteger 0x{}", number),
                 Self::String(string) => format!("String {}", string),
                 Self::Ident(string) => format!("Ident: {}", string),
+                Self::Parameter(parameter) => format!("Parameter {}", parameter),
                 Self::KeyWord(keyword) => format!("{}", keyword),
                 Self::Period => "Period".to_string(),
+                Self::DoubleColon => "DoubleColon".to_string(),
                 Self::Equal => "Equal".to_string(),
                 Self::GreaterThan => "GreaterThan".to_string(),
                 Self::LessThan => "LessThan".to_string(),
@@ -50,6 +69,7 @@ impl std::fmt::Display for Token {
                 Self::Caret => "Caret".to_string(),
                 Self::Percent => "Percent".to_string(),
                 Self::Exclamation => "Exclamation".to_string(),
+                Self::Tilde => "Tilde".to_string(),
                 Self::NotEqual => "NotEqual".to_string(),
                 Self::Question => "Question".to_string(),
                 Self::LeftParen => "LeftParen".to_string(),
@@ -57,6 +77,8 @@ impl std::fmt::Display for Token {
                 Self::Comma => "Comma".to_string(),
                 Self::Semicolon => "Semicolon".to_string(),
                 Self::Eof => "Eof".to_string(),
+                Self::Illegal(message) => format!("Illegal {}", message),
+                Self::Comment(comment) => format!("Comment {}", comment),
                 _ => {
                     "unknown".to_string()
                 }
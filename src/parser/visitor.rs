@@ -0,0 +1,1151 @@
+use super::column::{Column, ConstraintKind};
+use super::expression::{Expression, Parameter, WindowFrameBound, WindowSpec, WindowType};
+use super::operation::Operation;
+use super::stmt::{
+    AlterType, ConflictAction, Distinct, FromItem, InsertSource, JoinConstraint, Query, SelectItem,
+    SelectStmt, SetExpr, SetVariableType, Statement, With,
+};
+
+// 遍历语法树, 每个 visit_xxx 的默认实现调用对应的 walk_xxx 访问子节点
+// 只需要重写关心的节点, 重写时调用 walk_xxx 可以继续访问子节点
+pub trait Visitor {
+    fn visit_statement(&mut self, stmt: &Statement) {
+        walk_statement(self, stmt)
+    }
+
+    fn visit_query(&mut self, query: &Query) {
+        walk_query(self, query)
+    }
+
+    fn visit_with(&mut self, with: &With) {
+        walk_with(self, with)
+    }
+
+    fn visit_set_expr(&mut self, set_expr: &SetExpr) {
+        walk_set_expr(self, set_expr)
+    }
+
+    fn visit_select(&mut self, select: &SelectStmt) {
+        walk_select(self, select)
+    }
+
+    fn visit_from_item(&mut self, item: &FromItem) {
+        walk_from_item(self, item)
+    }
+
+    fn visit_column(&mut self, column: &Column) {
+        walk_column(self, column)
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        walk_expression(self, expr)
+    }
+
+    fn visit_operation(&mut self, op: &Operation) {
+        walk_operation(self, op)
+    }
+
+    fn visit_window_spec(&mut self, spec: &WindowSpec) {
+        walk_window_spec(self, spec)
+    }
+
+    // 语句中出现的表名和视图名, 包括 FROM, INSERT INTO, UPDATE, REFERENCES 等
+    fn visit_table(&mut self, _name: &str) {}
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, stmt: &Statement) {
+    match stmt {
+        Statement::Begin(_)
+        | Statement::Commit
+        | Statement::Rollback
+        | Statement::Savepoint(_)
+        | Statement::ReleaseSavepoint(_)
+        | Statement::RollbackTo(_)
+        | Statement::ShowDatabase
+        | Statement::ShowTables
+        | Statement::ShowVariables(_)
+        | Statement::CreateDatabase(_)
+        | Statement::DropDatabase(_)
+        | Statement::Use(_)
+        | Statement::DropIndex(_)
+        | Statement::Deallocate(_) => {}
+        Statement::Explain(explain) => visitor.visit_statement(&explain.statement),
+        Statement::Prepare(prepare) => visitor.visit_statement(&prepare.statement),
+        Statement::Execute(execute) => {
+            for param in &execute.params {
+                visitor.visit_expression(param);
+            }
+        }
+        Statement::CreateTable(create) => {
+            visitor.visit_table(&create.table_name);
+            for column in &create.columns {
+                visitor.visit_column(column);
+            }
+            for constraint in &create.constraints {
+                match &constraint.kind {
+                    ConstraintKind::Check(expr) => visitor.visit_expression(expr),
+                    ConstraintKind::ForeignKey { references, .. } => {
+                        visitor.visit_table(&references.table)
+                    }
+                    ConstraintKind::PrimaryKey(_) | ConstraintKind::Unique(_) => {}
+                }
+            }
+        }
+        Statement::DropTable(drop) => {
+            for table in &drop.table_names {
+                visitor.visit_table(table);
+            }
+        }
+        Statement::CreateView(create) => {
+            visitor.visit_table(&create.name);
+            visitor.visit_query(&create.query);
+        }
+        Statement::DropView(drop) => {
+            for view in &drop.names {
+                visitor.visit_table(view);
+            }
+        }
+        Statement::RefreshMaterializedView(name)
+        | Statement::ShowColumns(name)
+        | Statement::ShowIndexes(name)
+        | Statement::ShowCreateTable(name) => visitor.visit_table(name),
+        Statement::Truncate(tables) => {
+            for table in tables {
+                visitor.visit_table(table);
+            }
+        }
+        Statement::Delete(delete) => {
+            visitor.visit_table(&delete.table_name);
+            if let Some(item) = &delete.using {
+                visitor.visit_from_item(item);
+            }
+            if let Some(expr) = &delete.r#where {
+                visitor.visit_expression(expr);
+            }
+            for (expr, _) in delete.order.iter().flatten() {
+                visitor.visit_expression(expr);
+            }
+            if let Some(expr) = &delete.limit {
+                visitor.visit_expression(expr);
+            }
+            for items in delete.returning.iter() {
+                walk_select_items(visitor, items);
+            }
+        }
+        Statement::Insert(insert) => {
+            visitor.visit_table(&insert.table_name);
+            match &insert.source {
+                InsertSource::Values(values) => {
+                    for expr in values.iter().flatten().flatten() {
+                        visitor.visit_expression(expr);
+                    }
+                }
+                InsertSource::Query(query) => visitor.visit_query(query),
+                InsertSource::DefaultValues => {}
+            }
+            if let Some(on_conflict) = &insert.on_conflict {
+                if let ConflictAction::Update { set, wheres } = &on_conflict.action {
+                    for (_, expr) in set {
+                        visitor.visit_expression(expr);
+                    }
+                    if let Some(expr) = wheres {
+                        visitor.visit_expression(expr);
+                    }
+                }
+            }
+            for items in insert.returning.iter() {
+                walk_select_items(visitor, items);
+            }
+        }
+        Statement::Update(update) => {
+            visitor.visit_table(&update.table_name);
+            for (_, expr) in &update.set {
+                visitor.visit_expression(expr);
+            }
+            if let Some(item) = &update.from {
+                visitor.visit_from_item(item);
+            }
+            if let Some(expr) = &update.wheres {
+                visitor.visit_expression(expr);
+            }
+            for (expr, _) in update.order.iter().flatten() {
+                visitor.visit_expression(expr);
+            }
+            if let Some(expr) = &update.limit {
+                visitor.visit_expression(expr);
+            }
+            for items in update.returning.iter() {
+                walk_select_items(visitor, items);
+            }
+        }
+        Statement::Select(select) => visitor.visit_select(select),
+        Statement::Query(query) => visitor.visit_query(query),
+        Statement::Alter(alter) => {
+            visitor.visit_table(&alter.table_name);
+            match &alter.alter_type {
+                AlterType::AddColumn(column) | AlterType::ModifyColumn(column) => {
+                    visitor.visit_column(column)
+                }
+                _ => {}
+            }
+        }
+        Statement::CreateIndex(create) => {
+            visitor.visit_table(&create.table_name);
+            for expr in &create.columns {
+                visitor.visit_expression(expr);
+            }
+            if let Some(expr) = &create.wheres {
+                visitor.visit_expression(expr);
+            }
+        }
+        Statement::Set(set) => {
+            if let SetVariableType::Value(set_value) = &set.set_value {
+                visitor.visit_expression(&set_value.value);
+            }
+        }
+        Statement::DescribeTable(name) => visitor.visit_table(name),
+    }
+}
+
+pub fn walk_query<V: Visitor + ?Sized>(visitor: &mut V, query: &Query) {
+    if let Some(with) = &query.with {
+        visitor.visit_with(with);
+    }
+    visitor.visit_set_expr(&query.body);
+    for (expr, _) in query.order.iter().flatten() {
+        visitor.visit_expression(expr);
+    }
+    if let Some(expr) = &query.limit {
+        visitor.visit_expression(expr);
+    }
+    if let Some(expr) = &query.offset {
+        visitor.visit_expression(expr);
+    }
+}
+
+pub fn walk_with<V: Visitor + ?Sized>(visitor: &mut V, with: &With) {
+    for cte in &with.ctes {
+        visitor.visit_query(&cte.query);
+    }
+}
+
+pub fn walk_set_expr<V: Visitor + ?Sized>(visitor: &mut V, set_expr: &SetExpr) {
+    match set_expr {
+        SetExpr::Select(select) => visitor.visit_select(select),
+        SetExpr::Query(query) => visitor.visit_query(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            visitor.visit_set_expr(left);
+            visitor.visit_set_expr(right);
+        }
+    }
+}
+
+pub fn walk_select<V: Visitor + ?Sized>(visitor: &mut V, select: &SelectStmt) {
+    if let Some(Distinct::On(exprs)) = &select.distinct {
+        for expr in exprs {
+            visitor.visit_expression(expr);
+        }
+    }
+    walk_select_items(visitor, &select.selects);
+    if let Some(item) = &select.from {
+        visitor.visit_from_item(item);
+    }
+    if let Some(expr) = &select.wheres {
+        visitor.visit_expression(expr);
+    }
+    for expr in select.group_by.iter().flatten() {
+        visitor.visit_expression(expr);
+    }
+    if let Some(expr) = &select.having {
+        visitor.visit_expression(expr);
+    }
+    for (_, spec) in select.windows.iter().flatten() {
+        visitor.visit_window_spec(spec);
+    }
+    for (expr, _) in select.order.iter().flatten() {
+        visitor.visit_expression(expr);
+    }
+    if let Some(expr) = &select.limit {
+        visitor.visit_expression(expr);
+    }
+    if let Some(expr) = &select.offset {
+        visitor.visit_expression(expr);
+    }
+}
+
+// SELECT 列表和 RETURNING 中的表达式
+fn walk_select_items<V: Visitor + ?Sized>(visitor: &mut V, items: &[SelectItem]) {
+    for item in items {
+        if let SelectItem::Expr(expr, _) = item {
+            visitor.visit_expression(expr);
+        }
+    }
+}
+
+pub fn walk_window_spec<V: Visitor + ?Sized>(visitor: &mut V, spec: &WindowSpec) {
+    for expr in spec.partition_by.iter().flatten() {
+        visitor.visit_expression(expr);
+    }
+    for (expr, _) in spec.order.iter().flatten() {
+        visitor.visit_expression(expr);
+    }
+    if let Some(frame) = &spec.frame {
+        for bound in std::iter::once(&frame.start).chain(frame.end.iter()) {
+            if let WindowFrameBound::Preceding(Some(expr))
+            | WindowFrameBound::Following(Some(expr)) = bound
+            {
+                visitor.visit_expression(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_from_item<V: Visitor + ?Sized>(visitor: &mut V, item: &FromItem) {
+    match item {
+        FromItem::Table { name, .. } => visitor.visit_table(name),
+        FromItem::Subquery { query, .. } => visitor.visit_query(query),
+        FromItem::Join {
+            left,
+            right,
+            constraint,
+            ..
+        } => {
+            visitor.visit_from_item(left);
+            visitor.visit_from_item(right);
+            if let JoinConstraint::On(expr) = constraint {
+                visitor.visit_expression(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_column<V: Visitor + ?Sized>(visitor: &mut V, column: &Column) {
+    if let Some(expr) = &column.default {
+        visitor.visit_expression(expr);
+    }
+    for (_, expr) in &column.checks {
+        visitor.visit_expression(expr);
+    }
+    if let Some(references) = &column.references {
+        visitor.visit_table(&references.table);
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expression) {
+    match expr {
+        Expression::Field(_, _)
+        | Expression::Column(_)
+        | Expression::Literal(_)
+        | Expression::Parameter(_) => {}
+        Expression::Function(function) => {
+            for arg in &function.args {
+                visitor.visit_expression(arg);
+            }
+            if let Some(filter) = &function.filter {
+                visitor.visit_expression(filter);
+            }
+            if let Some(WindowType::Spec(spec)) = function.over.as_deref() {
+                visitor.visit_window_spec(spec);
+            }
+        }
+        Expression::Operation(op) => visitor.visit_operation(op),
+        Expression::Case {
+            operand,
+            branches,
+            else_result,
+        } => {
+            if let Some(operand) = operand {
+                visitor.visit_expression(operand);
+            }
+            for (condition, result) in branches {
+                visitor.visit_expression(condition);
+                visitor.visit_expression(result);
+            }
+            if let Some(else_result) = else_result {
+                visitor.visit_expression(else_result);
+            }
+        }
+        Expression::Cast(expr, _) => visitor.visit_expression(expr),
+        Expression::Subquery(query) | Expression::Exists(query) => visitor.visit_query(query),
+        Expression::Spanned(spanned) => visitor.visit_expression(&spanned.node),
+    }
+}
+
+pub fn walk_operation<V: Visitor + ?Sized>(visitor: &mut V, op: &Operation) {
+    match op {
+        Operation::And(lhs, rhs)
+        | Operation::Or(lhs, rhs)
+        | Operation::NotEqual(lhs, rhs)
+        | Operation::Equal(lhs, rhs)
+        | Operation::GreaterThan(lhs, rhs)
+        | Operation::GreaterThanOrEqual(lhs, rhs)
+        | Operation::LessThan(lhs, rhs)
+        | Operation::LessThanOrEqual(lhs, rhs)
+        | Operation::Add(lhs, rhs)
+        | Operation::Subtract(lhs, rhs)
+        | Operation::Multiply(lhs, rhs)
+        | Operation::Divide(lhs, rhs)
+        | Operation::Like(lhs, rhs)
+        | Operation::NotLike(lhs, rhs)
+        | Operation::Modulo(lhs, rhs) => {
+            visitor.visit_expression(lhs);
+            visitor.visit_expression(rhs);
+        }
+        Operation::In(expr, list) | Operation::NotIn(expr, list) => {
+            visitor.visit_expression(expr);
+            for item in list {
+                visitor.visit_expression(item);
+            }
+        }
+        Operation::InSubquery(expr, query) | Operation::NotInSubquery(expr, query) => {
+            visitor.visit_expression(expr);
+            visitor.visit_query(query);
+        }
+        Operation::Between(expr, low, high) | Operation::NotBetween(expr, low, high) => {
+            visitor.visit_expression(expr);
+            visitor.visit_expression(low);
+            visitor.visit_expression(high);
+        }
+        Operation::Not(expr)
+        | Operation::IsNull(expr)
+        | Operation::IsNotNull(expr)
+        | Operation::Assert(expr)
+        | Operation::Negate(expr)
+        | Operation::BitWiseNot(expr) => visitor.visit_expression(expr),
+    }
+}
+
+// 和 Visitor 一样, 但是可以修改语法树, 用于改写查询
+pub trait VisitorMut {
+    fn visit_statement(&mut self, stmt: &mut Statement) {
+        walk_statement_mut(self, stmt)
+    }
+
+    fn visit_query(&mut self, query: &mut Query) {
+        walk_query_mut(self, query)
+    }
+
+    fn visit_with(&mut self, with: &mut With) {
+        walk_with_mut(self, with)
+    }
+
+    fn visit_set_expr(&mut self, set_expr: &mut SetExpr) {
+        walk_set_expr_mut(self, set_expr)
+    }
+
+    fn visit_select(&mut self, select: &mut SelectStmt) {
+        walk_select_mut(self, select)
+    }
+
+    fn visit_from_item(&mut self, item: &mut FromItem) {
+        walk_from_item_mut(self, item)
+    }
+
+    fn visit_column(&mut self, column: &mut Column) {
+        walk_column_mut(self, column)
+    }
+
+    fn visit_expression(&mut self, expr: &mut Expression) {
+        walk_expression_mut(self, expr)
+    }
+
+    fn visit_operation(&mut self, op: &mut Operation) {
+        walk_operation_mut(self, op)
+    }
+
+    fn visit_window_spec(&mut self, spec: &mut WindowSpec) {
+        walk_window_spec_mut(self, spec)
+    }
+
+    fn visit_table(&mut self, _name: &mut String) {}
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut Statement) {
+    match stmt {
+        Statement::Begin(_)
+        | Statement::Commit
+        | Statement::Rollback
+        | Statement::Savepoint(_)
+        | Statement::ReleaseSavepoint(_)
+        | Statement::RollbackTo(_)
+        | Statement::ShowDatabase
+        | Statement::ShowTables
+        | Statement::ShowVariables(_)
+        | Statement::CreateDatabase(_)
+        | Statement::DropDatabase(_)
+        | Statement::Use(_)
+        | Statement::DropIndex(_)
+        | Statement::Deallocate(_) => {}
+        Statement::Explain(explain) => visitor.visit_statement(&mut explain.statement),
+        Statement::Prepare(prepare) => visitor.visit_statement(&mut prepare.statement),
+        Statement::Execute(execute) => {
+            for param in &mut execute.params {
+                visitor.visit_expression(param);
+            }
+        }
+        Statement::CreateTable(create) => {
+            visitor.visit_table(&mut create.table_name);
+            for column in &mut create.columns {
+                visitor.visit_column(column);
+            }
+            for constraint in &mut create.constraints {
+                match &mut constraint.kind {
+                    ConstraintKind::Check(expr) => visitor.visit_expression(expr),
+                    ConstraintKind::ForeignKey { references, .. } => {
+                        visitor.visit_table(&mut references.table)
+                    }
+                    ConstraintKind::PrimaryKey(_) | ConstraintKind::Unique(_) => {}
+                }
+            }
+        }
+        Statement::DropTable(drop) => {
+            for table in &mut drop.table_names {
+                visitor.visit_table(table);
+            }
+        }
+        Statement::CreateView(create) => {
+            visitor.visit_table(&mut create.name);
+            visitor.visit_query(&mut create.query);
+        }
+        Statement::DropView(drop) => {
+            for view in &mut drop.names {
+                visitor.visit_table(view);
+            }
+        }
+        Statement::RefreshMaterializedView(name)
+        | Statement::ShowColumns(name)
+        | Statement::ShowIndexes(name)
+        | Statement::ShowCreateTable(name) => visitor.visit_table(name),
+        Statement::Truncate(tables) => {
+            for table in tables {
+                visitor.visit_table(table);
+            }
+        }
+        Statement::Delete(delete) => {
+            visitor.visit_table(&mut delete.table_name);
+            if let Some(item) = &mut delete.using {
+                visitor.visit_from_item(item);
+            }
+            if let Some(expr) = &mut delete.r#where {
+                visitor.visit_expression(expr);
+            }
+            for (expr, _) in delete.order.iter_mut().flatten() {
+                visitor.visit_expression(expr);
+            }
+            if let Some(expr) = &mut delete.limit {
+                visitor.visit_expression(expr);
+            }
+            for items in delete.returning.iter_mut() {
+                walk_select_items_mut(visitor, items);
+            }
+        }
+        Statement::Insert(insert) => {
+            visitor.visit_table(&mut insert.table_name);
+            match &mut insert.source {
+                InsertSource::Values(values) => {
+                    for expr in values.iter_mut().flatten().flatten() {
+                        visitor.visit_expression(expr);
+                    }
+                }
+                InsertSource::Query(query) => visitor.visit_query(query),
+                InsertSource::DefaultValues => {}
+            }
+            if let Some(on_conflict) = &mut insert.on_conflict {
+                if let ConflictAction::Update { set, wheres } = &mut on_conflict.action {
+                    for (_, expr) in set {
+                        visitor.visit_expression(expr);
+                    }
+                    if let Some(expr) = wheres {
+                        visitor.visit_expression(expr);
+                    }
+                }
+            }
+            for items in insert.returning.iter_mut() {
+                walk_select_items_mut(visitor, items);
+            }
+        }
+        Statement::Update(update) => {
+            visitor.visit_table(&mut update.table_name);
+            for (_, expr) in &mut update.set {
+                visitor.visit_expression(expr);
+            }
+            if let Some(item) = &mut update.from {
+                visitor.visit_from_item(item);
+            }
+            if let Some(expr) = &mut update.wheres {
+                visitor.visit_expression(expr);
+            }
+            for (expr, _) in update.order.iter_mut().flatten() {
+                visitor.visit_expression(expr);
+            }
+            if let Some(expr) = &mut update.limit {
+                visitor.visit_expression(expr);
+            }
+            for items in update.returning.iter_mut() {
+                walk_select_items_mut(visitor, items);
+            }
+        }
+        Statement::Select(select) => visitor.visit_select(select),
+        Statement::Query(query) => visitor.visit_query(query),
+        Statement::Alter(alter) => {
+            visitor.visit_table(&mut alter.table_name);
+            match &mut alter.alter_type {
+                AlterType::AddColumn(column) | AlterType::ModifyColumn(column) => {
+                    visitor.visit_column(column)
+                }
+                _ => {}
+            }
+        }
+        Statement::CreateIndex(create) => {
+            visitor.visit_table(&mut create.table_name);
+            for expr in &mut create.columns {
+                visitor.visit_expression(expr);
+            }
+            if let Some(expr) = &mut create.wheres {
+                visitor.visit_expression(expr);
+            }
+        }
+        Statement::Set(set) => {
+            if let SetVariableType::Value(set_value) = &mut set.set_value {
+                visitor.visit_expression(&mut set_value.value);
+            }
+        }
+        Statement::DescribeTable(name) => visitor.visit_table(name),
+    }
+}
+
+pub fn walk_query_mut<V: VisitorMut + ?Sized>(visitor: &mut V, query: &mut Query) {
+    if let Some(with) = &mut query.with {
+        visitor.visit_with(with);
+    }
+    visitor.visit_set_expr(&mut query.body);
+    for (expr, _) in query.order.iter_mut().flatten() {
+        visitor.visit_expression(expr);
+    }
+    if let Some(expr) = &mut query.limit {
+        visitor.visit_expression(expr);
+    }
+    if let Some(expr) = &mut query.offset {
+        visitor.visit_expression(expr);
+    }
+}
+
+pub fn walk_with_mut<V: VisitorMut + ?Sized>(visitor: &mut V, with: &mut With) {
+    for cte in &mut with.ctes {
+        visitor.visit_query(&mut cte.query);
+    }
+}
+
+pub fn walk_set_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, set_expr: &mut SetExpr) {
+    match set_expr {
+        SetExpr::Select(select) => visitor.visit_select(select),
+        SetExpr::Query(query) => visitor.visit_query(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            visitor.visit_set_expr(left);
+            visitor.visit_set_expr(right);
+        }
+    }
+}
+
+pub fn walk_select_mut<V: VisitorMut + ?Sized>(visitor: &mut V, select: &mut SelectStmt) {
+    if let Some(Distinct::On(exprs)) = &mut select.distinct {
+        for expr in exprs {
+            visitor.visit_expression(expr);
+        }
+    }
+    walk_select_items_mut(visitor, &mut select.selects);
+    if let Some(item) = &mut select.from {
+        visitor.visit_from_item(item);
+    }
+    if let Some(expr) = &mut select.wheres {
+        visitor.visit_expression(expr);
+    }
+    for expr in select.group_by.iter_mut().flatten() {
+        visitor.visit_expression(expr);
+    }
+    if let Some(expr) = &mut select.having {
+        visitor.visit_expression(expr);
+    }
+    for (_, spec) in select.windows.iter_mut().flatten() {
+        visitor.visit_window_spec(spec);
+    }
+    for (expr, _) in select.order.iter_mut().flatten() {
+        visitor.visit_expression(expr);
+    }
+    if let Some(expr) = &mut select.limit {
+        visitor.visit_expression(expr);
+    }
+    if let Some(expr) = &mut select.offset {
+        visitor.visit_expression(expr);
+    }
+}
+
+// SELECT 列表和 RETURNING 中的表达式
+fn walk_select_items_mut<V: VisitorMut + ?Sized>(visitor: &mut V, items: &mut [SelectItem]) {
+    for item in items {
+        if let SelectItem::Expr(expr, _) = item {
+            visitor.visit_expression(expr);
+        }
+    }
+}
+
+pub fn walk_window_spec_mut<V: VisitorMut + ?Sized>(visitor: &mut V, spec: &mut WindowSpec) {
+    for expr in spec.partition_by.iter_mut().flatten() {
+        visitor.visit_expression(expr);
+    }
+    for (expr, _) in spec.order.iter_mut().flatten() {
+        visitor.visit_expression(expr);
+    }
+    if let Some(frame) = &mut spec.frame {
+        for bound in std::iter::once(&mut frame.start).chain(frame.end.iter_mut()) {
+            if let WindowFrameBound::Preceding(Some(expr))
+            | WindowFrameBound::Following(Some(expr)) = bound
+            {
+                visitor.visit_expression(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_from_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut FromItem) {
+    match item {
+        FromItem::Table { name, .. } => visitor.visit_table(name),
+        FromItem::Subquery { query, .. } => visitor.visit_query(query),
+        FromItem::Join {
+            left,
+            right,
+            constraint,
+            ..
+        } => {
+            visitor.visit_from_item(left);
+            visitor.visit_from_item(right);
+            if let JoinConstraint::On(expr) = constraint {
+                visitor.visit_expression(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_column_mut<V: VisitorMut + ?Sized>(visitor: &mut V, column: &mut Column) {
+    if let Some(expr) = &mut column.default {
+        visitor.visit_expression(expr);
+    }
+    for (_, expr) in &mut column.checks {
+        visitor.visit_expression(expr);
+    }
+    if let Some(references) = &mut column.references {
+        visitor.visit_table(&mut references.table);
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expression) {
+    match expr {
+        Expression::Field(_, _)
+        | Expression::Column(_)
+        | Expression::Literal(_)
+        | Expression::Parameter(_) => {}
+        Expression::Function(function) => {
+            for arg in &mut function.args {
+                visitor.visit_expression(arg);
+            }
+            if let Some(filter) = &mut function.filter {
+                visitor.visit_expression(filter);
+            }
+            if let Some(WindowType::Spec(spec)) = function.over.as_deref_mut() {
+                visitor.visit_window_spec(spec);
+            }
+        }
+        Expression::Operation(op) => visitor.visit_operation(op),
+        Expression::Case {
+            operand,
+            branches,
+            else_result,
+        } => {
+            if let Some(operand) = operand {
+                visitor.visit_expression(operand);
+            }
+            for (condition, result) in branches {
+                visitor.visit_expression(condition);
+                visitor.visit_expression(result);
+            }
+            if let Some(else_result) = else_result {
+                visitor.visit_expression(else_result);
+            }
+        }
+        Expression::Cast(expr, _) => visitor.visit_expression(expr),
+        Expression::Subquery(query) | Expression::Exists(query) => visitor.visit_query(query),
+        Expression::Spanned(spanned) => visitor.visit_expression(&mut spanned.node),
+    }
+}
+
+pub fn walk_operation_mut<V: VisitorMut + ?Sized>(visitor: &mut V, op: &mut Operation) {
+    match op {
+        Operation::And(lhs, rhs)
+        | Operation::Or(lhs, rhs)
+        | Operation::NotEqual(lhs, rhs)
+        | Operation::Equal(lhs, rhs)
+        | Operation::GreaterThan(lhs, rhs)
+        | Operation::GreaterThanOrEqual(lhs, rhs)
+        | Operation::LessThan(lhs, rhs)
+        | Operation::LessThanOrEqual(lhs, rhs)
+        | Operation::Add(lhs, rhs)
+        | Operation::Subtract(lhs, rhs)
+        | Operation::Multiply(lhs, rhs)
+        | Operation::Divide(lhs, rhs)
+        | Operation::Like(lhs, rhs)
+        | Operation::NotLike(lhs, rhs)
+        | Operation::Modulo(lhs, rhs) => {
+            visitor.visit_expression(lhs);
+            visitor.visit_expression(rhs);
+        }
+        Operation::In(expr, list) | Operation::NotIn(expr, list) => {
+            visitor.visit_expression(expr);
+            for item in list {
+                visitor.visit_expression(item);
+            }
+        }
+        Operation::InSubquery(expr, query) | Operation::NotInSubquery(expr, query) => {
+            visitor.visit_expression(expr);
+            visitor.visit_query(query);
+        }
+        Operation::Between(expr, low, high) | Operation::NotBetween(expr, low, high) => {
+            visitor.visit_expression(expr);
+            visitor.visit_expression(low);
+            visitor.visit_expression(high);
+        }
+        Operation::Not(expr)
+        | Operation::IsNull(expr)
+        | Operation::IsNotNull(expr)
+        | Operation::Assert(expr)
+        | Operation::Negate(expr)
+        | Operation::BitWiseNot(expr) => visitor.visit_expression(expr),
+    }
+}
+
+// 语句中用到的所有表, 按第一次出现的顺序去重
+// WITH 定义的 CTE 名称在作用域内不是表, 不算在内
+pub fn collect_tables(stmt: &Statement) -> Vec<String> {
+    #[derive(Default)]
+    struct TableCollector {
+        tables: Vec<String>,
+        // 当前作用域内可见的 CTE 名称, 内层的在后面
+        ctes: Vec<String>,
+    }
+
+    impl Visitor for TableCollector {
+        fn visit_query(&mut self, query: &Query) {
+            // WITH 引入的名称只在这个查询内可见
+            let depth = self.ctes.len();
+            walk_query(self, query);
+            self.ctes.truncate(depth);
+        }
+
+        fn visit_with(&mut self, with: &With) {
+            // 非递归的 CTE 只能看到前面定义的 CTE, 递归的还能看到自己
+            for cte in &with.ctes {
+                if with.recursive {
+                    self.ctes.push(cte.name.clone());
+                    self.visit_query(&cte.query);
+                } else {
+                    self.visit_query(&cte.query);
+                    self.ctes.push(cte.name.clone());
+                }
+            }
+        }
+
+        fn visit_table(&mut self, name: &str) {
+            if !self.ctes.iter().any(|t| t == name) && !self.tables.iter().any(|t| t == name) {
+                self.tables.push(name.to_owned());
+            }
+        }
+    }
+
+    let mut collector = TableCollector::default();
+    collector.visit_statement(stmt);
+    collector.tables
+}
+
+// 语句中引用的所有列 Expression::Field (表名, 列名), 按第一次出现的顺序去重
+pub fn collect_columns(stmt: &Statement) -> Vec<(Option<String>, String)> {
+    struct ColumnCollector(Vec<(Option<String>, String)>);
+
+    impl Visitor for ColumnCollector {
+        fn visit_expression(&mut self, expr: &Expression) {
+            if let Expression::Field(table, name) = expr {
+                let column = (table.clone(), name.clone());
+                if !self.0.contains(&column) {
+                    self.0.push(column);
+                }
+            }
+            walk_expression(self, expr);
+        }
+    }
+
+    let mut collector = ColumnCollector(Vec::new());
+    collector.visit_statement(stmt);
+    collector.0
+}
+
+// 语句中的绑定参数
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Parameters {
+    // 执行时需要绑定的参数个数
+    pub count: usize,
+    // 使用 :name 时参数的名字, 按第一次出现的顺序去重, 也是绑定的顺序
+    pub names: Vec<String>,
+}
+
+// ? 的个数, $n 中最大的 n, 或者不同 :name 的个数
+pub fn collect_parameters(stmt: &Statement) -> Parameters {
+    struct ParameterCollector(Parameters);
+
+    impl Visitor for ParameterCollector {
+        fn visit_expression(&mut self, expr: &Expression) {
+            match expr {
+                Expression::Parameter(Parameter::Anonymous) => self.0.count += 1,
+                Expression::Parameter(Parameter::Indexed(index)) => {
+                    self.0.count = self.0.count.max(*index)
+                }
+                Expression::Parameter(Parameter::Named(name)) => {
+                    if !self.0.names.contains(name) {
+                        self.0.names.push(name.clone());
+                        self.0.count += 1;
+                    }
+                }
+                _ => walk_expression(self, expr),
+            }
+        }
+    }
+
+    let mut collector = ParameterCollector(Parameters::default());
+    collector.visit_statement(stmt);
+    collector.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::expression::Literal;
+    use crate::parser::test::init;
+    use crate::parser::Parser;
+
+    fn parse(sql: &str) -> Statement {
+        Parser::new_parser(sql.to_owned()).parse_stmt().unwrap()
+    }
+
+    #[test]
+    fn collect_tables_test() {
+        init();
+        let stmt = parse(
+            "SELECT a.id FROM person a LEFT JOIN address b ON a.id = b.person_id, person, city",
+        );
+        assert_eq!(collect_tables(&stmt), vec!["person", "address", "city"]);
+
+        let stmt = parse("SELECT * FROM (a NATURAL JOIN b) FULL JOIN c USING (id) CROSS JOIN d");
+        assert_eq!(collect_tables(&stmt), vec!["a", "b", "c", "d"]);
+
+        let stmt = parse("INSERT INTO t SELECT * FROM u ON CONFLICT (a) DO NOTHING RETURNING *");
+        assert_eq!(collect_tables(&stmt), vec!["t", "u"]);
+
+        let stmt = parse("CREATE TABLE t (id INT, city_id INT REFERENCES city)");
+        assert_eq!(collect_tables(&stmt), vec!["t", "city"]);
+
+        let stmt = parse(
+            "CREATE TABLE t (a INT REFERENCES u (id), b INT, FOREIGN KEY (b) REFERENCES v (id))",
+        );
+        assert_eq!(collect_tables(&stmt), vec!["t", "u", "v"]);
+
+        let stmt = parse("EXPLAIN DELETE FROM t WHERE id = 1");
+        assert_eq!(collect_tables(&stmt), vec!["t"]);
+
+        let stmt = parse("CREATE VIEW v AS SELECT * FROM a JOIN b USING (id)");
+        assert_eq!(collect_tables(&stmt), vec!["v", "a", "b"]);
+
+        let stmt = parse("DROP TABLE IF EXISTS a, b, a CASCADE");
+        assert_eq!(collect_tables(&stmt), vec!["a", "b"]);
+
+        let stmt =
+            parse("UPDATE t SET a = (SELECT max(a) FROM u) FROM v WHERE t.id = v.id RETURNING *");
+        assert_eq!(collect_tables(&stmt), vec!["t", "u", "v"]);
+
+        let stmt =
+            parse("DELETE FROM t USING u JOIN v ON u.id = v.id WHERE t.id IN (SELECT id FROM w)");
+        assert_eq!(collect_tables(&stmt), vec!["t", "u", "v", "w"]);
+
+        // 子查询中的表
+        let stmt = parse(
+            "SELECT (SELECT max(id) FROM a) FROM (SELECT * FROM b) AS t \
+             WHERE id IN (SELECT id FROM c) AND NOT EXISTS (SELECT * FROM d)",
+        );
+        assert_eq!(collect_tables(&stmt), vec!["a", "b", "c", "d"]);
+
+        let stmt = parse("WITH t AS (SELECT * FROM a) SELECT * FROM t, b");
+        assert_eq!(collect_tables(&stmt), vec!["a", "b"]);
+
+        // CTE 的名称只在定义它的查询内可见, 非递归 CTE 里的同名引用是表
+        let stmt = parse(
+            "WITH t AS (SELECT * FROM t), u AS (SELECT * FROM t) \
+             SELECT * FROM u WHERE id IN (SELECT id FROM t) UNION SELECT * FROM (SELECT * FROM u) x",
+        );
+        assert_eq!(collect_tables(&stmt), vec!["t"]);
+
+        let stmt = parse("SELECT * FROM (WITH t AS (SELECT * FROM a) SELECT * FROM t) x, t");
+        assert_eq!(collect_tables(&stmt), vec!["a", "t"]);
+
+        let stmt = parse(
+            "WITH RECURSIVE t AS (SELECT * FROM a UNION ALL SELECT * FROM t) SELECT * FROM t",
+        );
+        assert_eq!(collect_tables(&stmt), vec!["a"]);
+
+        let stmt = parse("SELECT * FROM a UNION (SELECT * FROM b EXCEPT SELECT * FROM a)");
+        assert_eq!(collect_tables(&stmt), vec!["a", "b"]);
+
+        let stmt = parse("TRUNCATE TABLE a, b");
+        assert_eq!(collect_tables(&stmt), vec!["a", "b"]);
+
+        assert!(collect_tables(&parse("COMMIT")).is_empty());
+        assert!(collect_tables(&parse("USE db")).is_empty());
+    }
+
+    #[test]
+    fn collect_columns_test() {
+        init();
+        let stmt = parse(
+            "SELECT a.id, count(name) FROM person a WHERE -age > 1 AND a.id != 2 \
+             GROUP BY name HAVING max(b) > 1 ORDER BY c",
+        );
+        assert_eq!(
+            collect_columns(&stmt),
+            vec![
+                (Some("a".to_owned()), "id".to_owned()),
+                (None, "name".to_owned()),
+                (None, "age".to_owned()),
+                (None, "b".to_owned()),
+                (None, "c".to_owned()),
+            ]
+        );
+
+        let stmt = parse("UPDATE t SET a = b + 1 WHERE c IS NULL OR d NOT IN (e)");
+        assert_eq!(
+            collect_columns(&stmt),
+            vec![
+                (None, "b".to_owned()),
+                (None, "c".to_owned()),
+                (None, "d".to_owned()),
+                (None, "e".to_owned()),
+            ]
+        );
+
+        let stmt = parse("CREATE INDEX i ON t (a, lower(b)) WHERE c IS NOT NULL");
+        assert_eq!(
+            collect_columns(&stmt),
+            vec![
+                (None, "a".to_owned()),
+                (None, "b".to_owned()),
+                (None, "c".to_owned()),
+            ]
+        );
+
+        let stmt =
+            parse("SELECT DISTINCT ON (a) t.*, count(DISTINCT b) FILTER (WHERE c > 1) FROM t");
+        assert_eq!(
+            collect_columns(&stmt),
+            vec![
+                (None, "a".to_owned()),
+                (None, "b".to_owned()),
+                (None, "c".to_owned()),
+            ]
+        );
+
+        let stmt = parse(
+            "SELECT sum(a) OVER (PARTITION BY b ORDER BY c ROWS d PRECEDING), rank() OVER w \
+             FROM t WINDOW w AS (ORDER BY e)",
+        );
+        assert_eq!(
+            collect_columns(&stmt),
+            vec![
+                (None, "a".to_owned()),
+                (None, "b".to_owned()),
+                (None, "c".to_owned()),
+                (None, "d".to_owned()),
+                (None, "e".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_parameters_test() {
+        init();
+        let stmt = parse("SELECT ? FROM t WHERE a IN (SELECT b FROM u WHERE c = ?) LIMIT ?");
+        assert_eq!(
+            collect_parameters(&stmt),
+            Parameters {
+                count: 3,
+                names: vec![],
+            }
+        );
+
+        let stmt = parse("SELECT $2 FROM t WHERE a = $4 OR a = $2");
+        assert_eq!(collect_parameters(&stmt).count, 4);
+
+        let stmt = parse("SELECT * FROM t WHERE a = :b AND c = :a OR a = :b");
+        assert_eq!(
+            collect_parameters(&stmt),
+            Parameters {
+                count: 2,
+                names: vec!["b".to_owned(), "a".to_owned()],
+            }
+        );
+
+        assert_eq!(
+            collect_parameters(&parse("SELECT 1")),
+            Parameters::default()
+        );
+
+        // PREPARE 中预编译的语句
+        let stmt = parse("PREPARE q AS INSERT INTO t VALUES ($1, $2), ($1, $3)");
+        assert_eq!(collect_parameters(&stmt).count, 3);
+        assert_eq!(collect_tables(&stmt), vec!["t"]);
+
+        let stmt = parse(
+            "INSERT INTO t VALUES (?, DEFAULT) ON CONFLICT (a) DO UPDATE SET b = ? WHERE c > ? RETURNING ?",
+        );
+        assert_eq!(collect_parameters(&stmt).count, 4);
+
+        // 每条语句可以使用不同形式的参数
+        let stmts = Parser::new_parser("SELECT ?; SELECT $1".to_owned())
+            .parse_statements()
+            .unwrap();
+        assert_eq!(collect_parameters(&stmts[1].node).count, 1);
+    }
+
+    #[test]
+    fn visitor_mut_test() {
+        init();
+
+        // 给表名加上前缀, 并把整数常量替换为 ?
+        struct Rewriter;
+
+        impl VisitorMut for Rewriter {
+            fn visit_table(&mut self, name: &mut String) {
+                name.insert_str(0, "tenant_");
+            }
+
+            fn visit_expression(&mut self, expr: &mut Expression) {
+                if let Expression::Literal(Literal::Int(_)) = expr {
+                    *expr = Expression::Literal(Literal::String("?".to_owned()));
+                }
+                walk_expression_mut(self, expr);
+            }
+        }
+
+        let mut stmt =
+            parse("SELECT id + 1 FROM person JOIN address ON id = 2 WHERE f(3) LIMIT 10");
+        Rewriter.visit_statement(&mut stmt);
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT id + '?' FROM tenant_person JOIN tenant_address ON id = '?' \
+             WHERE f('?') LIMIT '?'"
+        );
+    }
+}